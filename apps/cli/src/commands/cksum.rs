@@ -0,0 +1,40 @@
+use crate::utils::{exit_with_io_error, exit_with_schema_error, EXIT_CHECK_FAILED};
+use ovsdb_schema::{cksum, Schema, SchemaError};
+use std::fs;
+use std::process;
+
+pub fn run_cksum(schema_file: &str, verify: bool, update: bool) {
+    let text =
+        fs::read_to_string(schema_file).unwrap_or_else(|e| exit_with_io_error(schema_file, e));
+
+    if update {
+        match cksum::update(&text) {
+            Some(updated) => {
+                fs::write(schema_file, updated)
+                    .unwrap_or_else(|e| exit_with_io_error(schema_file, e));
+                println!(
+                    "Updated cksum of '{}' to \"{}\"",
+                    schema_file,
+                    Schema::compute_cksum(&text)
+                );
+            }
+            None => {
+                eprintln!("'{}' has no cksum field to update", schema_file);
+                process::exit(EXIT_CHECK_FAILED);
+            }
+        }
+    } else if verify {
+        let schema = text
+            .parse::<Schema>()
+            .unwrap_or_else(|e: SchemaError| exit_with_schema_error(&e.with_file(schema_file)));
+        match schema.verify_cksum(&text) {
+            Ok(()) => println!("cksum of '{}' is up to date", schema_file),
+            Err(mismatch) => {
+                eprintln!("{}: {}", schema_file, mismatch);
+                process::exit(EXIT_CHECK_FAILED);
+            }
+        }
+    } else {
+        println!("{}", Schema::compute_cksum(&text));
+    }
+}
@@ -1,7 +1,18 @@
-use crate::utils::load_schema_from_file;
+use crate::utils::{exit_with_io_error, load_schema_or_exit};
+use ovsdb_schema::codegen::generate;
+use std::fs;
+use std::path::Path;
 
 pub fn run_code_gen(output_dir: &str, mod_name: &str, schema_file: &str) {
-    let schema = load_schema_from_file(schema_file).expect("Failed to load schema");
-    // ...code generation logic...
-    println!("Generated code for module '{}' into directory '{}'", mod_name, output_dir);
+    let schema = load_schema_or_exit(schema_file);
+    let code = generate(&schema);
+
+    fs::create_dir_all(output_dir).unwrap_or_else(|e| exit_with_io_error(output_dir, e));
+    let path = Path::new(output_dir).join(format!("{}.rs", mod_name));
+    fs::write(&path, code).unwrap_or_else(|e| exit_with_io_error(&path.display().to_string(), e));
+    println!(
+        "Generated code for module '{}' into '{}'",
+        mod_name,
+        path.display()
+    );
 }
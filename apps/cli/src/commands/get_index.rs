@@ -1,7 +1,7 @@
-use crate::utils::load_schema_from_file;
+use crate::utils::load_schema_or_exit;
 
 pub fn run_get_index(schema_file: &str, table: Option<&str>) {
-    let schema = load_schema_from_file(schema_file).expect("Failed to load schema");
+    let schema = load_schema_or_exit(schema_file);
 
     if let Some(table_name) = table {
         if let Some(table_entry) = schema.get_table(table_name) {
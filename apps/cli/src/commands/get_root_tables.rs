@@ -1,7 +1,7 @@
-use crate::utils::load_schema_from_file;
+use crate::utils::load_schema_or_exit;
 
 pub fn run_get_root_tables(schema_file: &str) {
-    let schema = load_schema_from_file(schema_file).expect("Failed to load schema");
+    let schema = load_schema_or_exit(schema_file);
     schema
         .iter_tables()
         .filter(|(_, t)| t.is_root())
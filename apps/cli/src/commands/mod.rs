@@ -1,3 +1,6 @@
 pub mod get_root_tables;
 pub mod code_gen;
 pub mod get_index;
+pub mod cksum;
+pub mod validate_schema;
+pub mod schema_diff;
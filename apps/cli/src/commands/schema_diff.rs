@@ -0,0 +1,18 @@
+use crate::utils::load_schema_or_exit;
+
+pub fn run_schema_diff(old: &str, new: &str, json: bool) {
+    let old_schema = load_schema_or_exit(old);
+    let new_schema = load_schema_or_exit(new);
+    let diff = old_schema.diff(&new_schema);
+
+    if json {
+        let output = serde_json::to_string_pretty(&diff).expect("Failed to serialize diff");
+        println!("{}", output);
+        return;
+    }
+    for change in &diff.changes {
+        println!("{}", change);
+    }
+    let breaking = diff.breaking().count();
+    println!("{} change(s), {} breaking", diff.changes.len(), breaking);
+}
@@ -0,0 +1,20 @@
+use crate::utils::{load_schema_or_exit, EXIT_CHECK_FAILED};
+use std::process;
+
+pub fn run_validate_schema(schema_file: &str) {
+    let schema = load_schema_or_exit(schema_file);
+    let diagnostics = schema.validate();
+    for diagnostic in &diagnostics {
+        println!("{}", diagnostic);
+    }
+
+    let errors = diagnostics.iter().filter(|d| d.is_error()).count();
+    let warnings = diagnostics.len() - errors;
+    println!(
+        "{}: {} error(s), {} warning(s)",
+        schema_file, errors, warnings
+    );
+    if errors > 0 {
+        process::exit(EXIT_CHECK_FAILED);
+    }
+}
@@ -1,18 +1,9 @@
-use ovsdb_schema::Schema;
-use serde_json;
-use std::fs;
 use clap::Parser;
 mod option;
 mod commands;
 mod utils;
 use option::{CliOptions, Commands};
 
-/// Loads an OVSDB schema from the given file path.
-fn load_schema_from_file(path: &str) -> Result<Schema, serde_json::Error> {
-    let contents = fs::read_to_string(path).expect("Something went wrong reading the file");
-    serde_json::from_str(&contents)
-}
-
 /// Reads the CLI options and executes the corresponding subcommand.
 fn run_cli() {
     let opts = CliOptions::parse();
@@ -26,6 +17,15 @@ fn run_cli() {
         Some(Commands::GetIndex { schema_file, table }) => {
             commands::get_index::run_get_index(&schema_file, table.as_deref());
         },
+        Some(Commands::Cksum { schema_file, verify, update }) => {
+            commands::cksum::run_cksum(&schema_file, verify, update);
+        },
+        Some(Commands::ValidateSchema { schema_file }) => {
+            commands::validate_schema::run_validate_schema(&schema_file);
+        },
+        Some(Commands::SchemaDiff { old, new, json }) => {
+            commands::schema_diff::run_schema_diff(&old, &new, json);
+        },
         _ => {
             eprintln!("A valid subcommand is required.");
         }
@@ -38,7 +38,7 @@ fn main() {
 
 #[cfg(test)]
 mod tests {
-    use super::*;
+    use crate::utils::load_schema_from_file;
 
     #[test]
     fn test_schema_loading() {
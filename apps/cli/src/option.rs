@@ -38,4 +38,34 @@ pub enum Commands {
         #[arg(short, long)]
         table: Option<String>,
     },
+    /// Prints, verifies or updates the schema checksum.
+    Cksum {
+        /// OVSDB schema file path.
+        #[arg(short, long)]
+        schema_file: String,
+        /// Fails if the stored cksum does not match the schema contents.
+        #[arg(long, conflicts_with = "update")]
+        verify: bool,
+        /// Rewrites the stored cksum to match the schema contents.
+        #[arg(long)]
+        update: bool,
+    },
+    /// Checks a schema for semantic errors, like `ovsdb-tool check-schema`.
+    ValidateSchema {
+        /// OVSDB schema file path.
+        #[arg(short, long)]
+        schema_file: String,
+    },
+    /// Compares two schema versions and classifies each change.
+    SchemaDiff {
+        /// The schema currently deployed.
+        #[arg(long)]
+        old: String,
+        /// The schema to upgrade to.
+        #[arg(long)]
+        new: String,
+        /// Prints the changes as JSON.
+        #[arg(long)]
+        json: bool,
+    },
 }
@@ -1,8 +1,43 @@
-use ovsdb_schema::Schema;
-use serde_json;
-use std::fs;
+use ovsdb_schema::{Schema, SchemaError};
+use std::io;
+use std::process;
 
-pub fn load_schema_from_file(path: &str) -> Result<Schema, serde_json::Error> {
-    let contents = fs::read_to_string(path).expect("Something went wrong reading the file");
-    serde_json::from_str(&contents)
+// exit codes, so that scripts can tell failures apart; clap takes 2 for
+// usage errors
+/// A check failed: the schema has errors, or its cksum is stale.
+pub const EXIT_CHECK_FAILED: i32 = 1;
+/// A file could not be read or written.
+pub const EXIT_IO_ERROR: i32 = 3;
+/// A schema file is not valid JSON.
+pub const EXIT_SYNTAX_ERROR: i32 = 4;
+/// A schema file is JSON, but not a schema.
+pub const EXIT_INVALID_SCHEMA: i32 = 5;
+
+pub fn load_schema_from_file(path: &str) -> Result<Schema, SchemaError> {
+    Schema::from_path(path)
+}
+
+/// Loads a schema, or prints why it cannot and exits.
+pub fn load_schema_or_exit(path: &str) -> Schema {
+    load_schema_from_file(path).unwrap_or_else(|error| exit_with_schema_error(&error))
+}
+
+pub fn exit_code(error: &SchemaError) -> i32 {
+    match error {
+        SchemaError::Io { .. } => EXIT_IO_ERROR,
+        SchemaError::Syntax { .. } => EXIT_SYNTAX_ERROR,
+        SchemaError::Invalid { .. } => EXIT_INVALID_SCHEMA,
+    }
+}
+
+/// Prints the error as a diagnostic, e.g.
+/// `error: nb.ovsschema:12:5: $.tables.ACL: missing field `columns``, and exits.
+pub fn exit_with_schema_error(error: &SchemaError) -> ! {
+    eprintln!("error: {}", error);
+    process::exit(exit_code(error))
+}
+
+pub fn exit_with_io_error(path: &str, error: io::Error) -> ! {
+    eprintln!("error: {}: {}", path, error);
+    process::exit(EXIT_IO_ERROR)
 }
@@ -0,0 +1,40 @@
+use std::fs;
+use std::process::Command;
+
+fn cli(args: &[&str]) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_cli"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn test_print() {
+    let output = cli(&["cksum", "-s", "tests/ovn-nb.ovsschema"]);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "1931852754 30731\n"
+    );
+}
+
+#[test]
+fn test_verify_and_update() {
+    let output = cli(&["cksum", "-s", "tests/ovn-nb.ovsschema", "--verify"]);
+    assert!(output.status.success());
+
+    let path = std::env::temp_dir().join(format!("cksum-{}.ovsschema", std::process::id()));
+    let edited = fs::read_to_string("tests/ovn-nb.ovsschema")
+        .unwrap()
+        .replace("\"version\": \"5.33.1\"", "\"version\": \"5.34.0\"");
+    fs::write(&path, edited).unwrap();
+    let path = path.to_str().unwrap();
+
+    let output = cli(&["cksum", "-s", path, "--verify"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("1931852754 30731"));
+
+    assert!(cli(&["cksum", "-s", path, "--update"]).status.success());
+    assert!(cli(&["cksum", "-s", path, "--verify"]).status.success());
+    fs::remove_file(path).unwrap();
+}
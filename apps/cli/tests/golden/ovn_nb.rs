@@ -0,0 +1,1965 @@
+// Generated by ovsdb-rs from the OVN_Northbound schema, version 5.33.1. Do not edit.
+
+pub const SCHEMA_NAME: &str = "OVN_Northbound";
+pub const SCHEMA_VERSION: &str = "5.33.1";
+
+/// Values of the "action" column of the "ACL" table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AclAction {
+    Allow,
+    AllowRelated,
+    AllowStateless,
+    Drop,
+    Reject,
+}
+
+impl AclAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AclAction::Allow => "allow",
+            AclAction::AllowRelated => "allow-related",
+            AclAction::AllowStateless => "allow-stateless",
+            AclAction::Drop => "drop",
+            AclAction::Reject => "reject",
+        }
+    }
+}
+
+impl ::ovsdb_common::row::FromAtom for AclAction {
+    fn from_atom(atom: &::ovsdb_common::datum::Atom) -> ::std::option::Option<Self> {
+        match atom.as_str()? {
+            "allow" => Some(AclAction::Allow),
+            "allow-related" => Some(AclAction::AllowRelated),
+            "allow-stateless" => Some(AclAction::AllowStateless),
+            "drop" => Some(AclAction::Drop),
+            "reject" => Some(AclAction::Reject),
+            _ => None,
+        }
+    }
+}
+
+impl ::ovsdb_common::row::ToAtom for AclAction {
+    fn to_atom(&self) -> ::ovsdb_common::datum::Atom {
+        ::ovsdb_common::datum::Atom::String(self.as_str().to_string())
+    }
+}
+
+impl ::ovsdb_common::row::FromDatum for AclAction {
+    fn from_datum(datum: &::ovsdb_common::datum::Datum) -> ::std::option::Option<Self> {
+        ::ovsdb_common::row::scalar_from_datum(datum)
+    }
+}
+
+impl ::ovsdb_common::row::ToDatum for AclAction {
+    fn to_datum(&self) -> ::ovsdb_common::datum::Datum {
+        ::ovsdb_common::datum::Datum::Scalar(::ovsdb_common::row::ToAtom::to_atom(self))
+    }
+}
+
+/// Values of the "direction" column of the "ACL" table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AclDirection {
+    FromLport,
+    ToLport,
+}
+
+impl AclDirection {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AclDirection::FromLport => "from-lport",
+            AclDirection::ToLport => "to-lport",
+        }
+    }
+}
+
+impl ::ovsdb_common::row::FromAtom for AclDirection {
+    fn from_atom(atom: &::ovsdb_common::datum::Atom) -> ::std::option::Option<Self> {
+        match atom.as_str()? {
+            "from-lport" => Some(AclDirection::FromLport),
+            "to-lport" => Some(AclDirection::ToLport),
+            _ => None,
+        }
+    }
+}
+
+impl ::ovsdb_common::row::ToAtom for AclDirection {
+    fn to_atom(&self) -> ::ovsdb_common::datum::Atom {
+        ::ovsdb_common::datum::Atom::String(self.as_str().to_string())
+    }
+}
+
+impl ::ovsdb_common::row::FromDatum for AclDirection {
+    fn from_datum(datum: &::ovsdb_common::datum::Datum) -> ::std::option::Option<Self> {
+        ::ovsdb_common::row::scalar_from_datum(datum)
+    }
+}
+
+impl ::ovsdb_common::row::ToDatum for AclDirection {
+    fn to_datum(&self) -> ::ovsdb_common::datum::Datum {
+        ::ovsdb_common::datum::Datum::Scalar(::ovsdb_common::row::ToAtom::to_atom(self))
+    }
+}
+
+/// Values of the "severity" column of the "ACL" table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AclSeverity {
+    Alert,
+    Warning,
+    Notice,
+    Info,
+    Debug,
+}
+
+impl AclSeverity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AclSeverity::Alert => "alert",
+            AclSeverity::Warning => "warning",
+            AclSeverity::Notice => "notice",
+            AclSeverity::Info => "info",
+            AclSeverity::Debug => "debug",
+        }
+    }
+}
+
+impl ::ovsdb_common::row::FromAtom for AclSeverity {
+    fn from_atom(atom: &::ovsdb_common::datum::Atom) -> ::std::option::Option<Self> {
+        match atom.as_str()? {
+            "alert" => Some(AclSeverity::Alert),
+            "warning" => Some(AclSeverity::Warning),
+            "notice" => Some(AclSeverity::Notice),
+            "info" => Some(AclSeverity::Info),
+            "debug" => Some(AclSeverity::Debug),
+            _ => None,
+        }
+    }
+}
+
+impl ::ovsdb_common::row::ToAtom for AclSeverity {
+    fn to_atom(&self) -> ::ovsdb_common::datum::Atom {
+        ::ovsdb_common::datum::Atom::String(self.as_str().to_string())
+    }
+}
+
+impl ::ovsdb_common::row::FromDatum for AclSeverity {
+    fn from_datum(datum: &::ovsdb_common::datum::Datum) -> ::std::option::Option<Self> {
+        ::ovsdb_common::row::scalar_from_datum(datum)
+    }
+}
+
+impl ::ovsdb_common::row::ToDatum for AclSeverity {
+    fn to_datum(&self) -> ::ovsdb_common::datum::Datum {
+        ::ovsdb_common::datum::Datum::Scalar(::ovsdb_common::row::ToAtom::to_atom(self))
+    }
+}
+
+/// Values of the "status" column of the "BFD" table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum BfdStatus {
+    Down,
+    Init,
+    Up,
+    AdminDown,
+}
+
+impl BfdStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BfdStatus::Down => "down",
+            BfdStatus::Init => "init",
+            BfdStatus::Up => "up",
+            BfdStatus::AdminDown => "admin_down",
+        }
+    }
+}
+
+impl ::ovsdb_common::row::FromAtom for BfdStatus {
+    fn from_atom(atom: &::ovsdb_common::datum::Atom) -> ::std::option::Option<Self> {
+        match atom.as_str()? {
+            "down" => Some(BfdStatus::Down),
+            "init" => Some(BfdStatus::Init),
+            "up" => Some(BfdStatus::Up),
+            "admin_down" => Some(BfdStatus::AdminDown),
+            _ => None,
+        }
+    }
+}
+
+impl ::ovsdb_common::row::ToAtom for BfdStatus {
+    fn to_atom(&self) -> ::ovsdb_common::datum::Atom {
+        ::ovsdb_common::datum::Atom::String(self.as_str().to_string())
+    }
+}
+
+impl ::ovsdb_common::row::FromDatum for BfdStatus {
+    fn from_datum(datum: &::ovsdb_common::datum::Datum) -> ::std::option::Option<Self> {
+        ::ovsdb_common::row::scalar_from_datum(datum)
+    }
+}
+
+impl ::ovsdb_common::row::ToDatum for BfdStatus {
+    fn to_datum(&self) -> ::ovsdb_common::datum::Datum {
+        ::ovsdb_common::datum::Datum::Scalar(::ovsdb_common::row::ToAtom::to_atom(self))
+    }
+}
+
+/// Values of the "protocol" column of the "Load_Balancer" table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LoadBalancerProtocol {
+    Tcp,
+    Udp,
+    Sctp,
+}
+
+impl LoadBalancerProtocol {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LoadBalancerProtocol::Tcp => "tcp",
+            LoadBalancerProtocol::Udp => "udp",
+            LoadBalancerProtocol::Sctp => "sctp",
+        }
+    }
+}
+
+impl ::ovsdb_common::row::FromAtom for LoadBalancerProtocol {
+    fn from_atom(atom: &::ovsdb_common::datum::Atom) -> ::std::option::Option<Self> {
+        match atom.as_str()? {
+            "tcp" => Some(LoadBalancerProtocol::Tcp),
+            "udp" => Some(LoadBalancerProtocol::Udp),
+            "sctp" => Some(LoadBalancerProtocol::Sctp),
+            _ => None,
+        }
+    }
+}
+
+impl ::ovsdb_common::row::ToAtom for LoadBalancerProtocol {
+    fn to_atom(&self) -> ::ovsdb_common::datum::Atom {
+        ::ovsdb_common::datum::Atom::String(self.as_str().to_string())
+    }
+}
+
+impl ::ovsdb_common::row::FromDatum for LoadBalancerProtocol {
+    fn from_datum(datum: &::ovsdb_common::datum::Datum) -> ::std::option::Option<Self> {
+        ::ovsdb_common::row::scalar_from_datum(datum)
+    }
+}
+
+impl ::ovsdb_common::row::ToDatum for LoadBalancerProtocol {
+    fn to_datum(&self) -> ::ovsdb_common::datum::Datum {
+        ::ovsdb_common::datum::Datum::Scalar(::ovsdb_common::row::ToAtom::to_atom(self))
+    }
+}
+
+/// Values of the "selection_fields" column of the "Load_Balancer" table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LoadBalancerSelectionFields {
+    EthSrc,
+    EthDst,
+    IpSrc,
+    IpDst,
+    TpSrc,
+    TpDst,
+}
+
+impl LoadBalancerSelectionFields {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LoadBalancerSelectionFields::EthSrc => "eth_src",
+            LoadBalancerSelectionFields::EthDst => "eth_dst",
+            LoadBalancerSelectionFields::IpSrc => "ip_src",
+            LoadBalancerSelectionFields::IpDst => "ip_dst",
+            LoadBalancerSelectionFields::TpSrc => "tp_src",
+            LoadBalancerSelectionFields::TpDst => "tp_dst",
+        }
+    }
+}
+
+impl ::ovsdb_common::row::FromAtom for LoadBalancerSelectionFields {
+    fn from_atom(atom: &::ovsdb_common::datum::Atom) -> ::std::option::Option<Self> {
+        match atom.as_str()? {
+            "eth_src" => Some(LoadBalancerSelectionFields::EthSrc),
+            "eth_dst" => Some(LoadBalancerSelectionFields::EthDst),
+            "ip_src" => Some(LoadBalancerSelectionFields::IpSrc),
+            "ip_dst" => Some(LoadBalancerSelectionFields::IpDst),
+            "tp_src" => Some(LoadBalancerSelectionFields::TpSrc),
+            "tp_dst" => Some(LoadBalancerSelectionFields::TpDst),
+            _ => None,
+        }
+    }
+}
+
+impl ::ovsdb_common::row::ToAtom for LoadBalancerSelectionFields {
+    fn to_atom(&self) -> ::ovsdb_common::datum::Atom {
+        ::ovsdb_common::datum::Atom::String(self.as_str().to_string())
+    }
+}
+
+impl ::ovsdb_common::row::FromDatum for LoadBalancerSelectionFields {
+    fn from_datum(datum: &::ovsdb_common::datum::Datum) -> ::std::option::Option<Self> {
+        ::ovsdb_common::row::scalar_from_datum(datum)
+    }
+}
+
+impl ::ovsdb_common::row::ToDatum for LoadBalancerSelectionFields {
+    fn to_datum(&self) -> ::ovsdb_common::datum::Datum {
+        ::ovsdb_common::datum::Datum::Scalar(::ovsdb_common::row::ToAtom::to_atom(self))
+    }
+}
+
+/// Values of the "action" column of the "Logical_Router_Policy" table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LogicalRouterPolicyAction {
+    Allow,
+    Drop,
+    Reroute,
+}
+
+impl LogicalRouterPolicyAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogicalRouterPolicyAction::Allow => "allow",
+            LogicalRouterPolicyAction::Drop => "drop",
+            LogicalRouterPolicyAction::Reroute => "reroute",
+        }
+    }
+}
+
+impl ::ovsdb_common::row::FromAtom for LogicalRouterPolicyAction {
+    fn from_atom(atom: &::ovsdb_common::datum::Atom) -> ::std::option::Option<Self> {
+        match atom.as_str()? {
+            "allow" => Some(LogicalRouterPolicyAction::Allow),
+            "drop" => Some(LogicalRouterPolicyAction::Drop),
+            "reroute" => Some(LogicalRouterPolicyAction::Reroute),
+            _ => None,
+        }
+    }
+}
+
+impl ::ovsdb_common::row::ToAtom for LogicalRouterPolicyAction {
+    fn to_atom(&self) -> ::ovsdb_common::datum::Atom {
+        ::ovsdb_common::datum::Atom::String(self.as_str().to_string())
+    }
+}
+
+impl ::ovsdb_common::row::FromDatum for LogicalRouterPolicyAction {
+    fn from_datum(datum: &::ovsdb_common::datum::Datum) -> ::std::option::Option<Self> {
+        ::ovsdb_common::row::scalar_from_datum(datum)
+    }
+}
+
+impl ::ovsdb_common::row::ToDatum for LogicalRouterPolicyAction {
+    fn to_datum(&self) -> ::ovsdb_common::datum::Datum {
+        ::ovsdb_common::datum::Datum::Scalar(::ovsdb_common::row::ToAtom::to_atom(self))
+    }
+}
+
+/// Values of the "policy" column of the "Logical_Router_Static_Route" table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LogicalRouterStaticRoutePolicy {
+    SrcIp,
+    DstIp,
+}
+
+impl LogicalRouterStaticRoutePolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogicalRouterStaticRoutePolicy::SrcIp => "src-ip",
+            LogicalRouterStaticRoutePolicy::DstIp => "dst-ip",
+        }
+    }
+}
+
+impl ::ovsdb_common::row::FromAtom for LogicalRouterStaticRoutePolicy {
+    fn from_atom(atom: &::ovsdb_common::datum::Atom) -> ::std::option::Option<Self> {
+        match atom.as_str()? {
+            "src-ip" => Some(LogicalRouterStaticRoutePolicy::SrcIp),
+            "dst-ip" => Some(LogicalRouterStaticRoutePolicy::DstIp),
+            _ => None,
+        }
+    }
+}
+
+impl ::ovsdb_common::row::ToAtom for LogicalRouterStaticRoutePolicy {
+    fn to_atom(&self) -> ::ovsdb_common::datum::Atom {
+        ::ovsdb_common::datum::Atom::String(self.as_str().to_string())
+    }
+}
+
+impl ::ovsdb_common::row::FromDatum for LogicalRouterStaticRoutePolicy {
+    fn from_datum(datum: &::ovsdb_common::datum::Datum) -> ::std::option::Option<Self> {
+        ::ovsdb_common::row::scalar_from_datum(datum)
+    }
+}
+
+impl ::ovsdb_common::row::ToDatum for LogicalRouterStaticRoutePolicy {
+    fn to_datum(&self) -> ::ovsdb_common::datum::Datum {
+        ::ovsdb_common::datum::Datum::Scalar(::ovsdb_common::row::ToAtom::to_atom(self))
+    }
+}
+
+/// Values of the "unit" column of the "Meter" table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MeterUnit {
+    Kbps,
+    Pktps,
+}
+
+impl MeterUnit {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MeterUnit::Kbps => "kbps",
+            MeterUnit::Pktps => "pktps",
+        }
+    }
+}
+
+impl ::ovsdb_common::row::FromAtom for MeterUnit {
+    fn from_atom(atom: &::ovsdb_common::datum::Atom) -> ::std::option::Option<Self> {
+        match atom.as_str()? {
+            "kbps" => Some(MeterUnit::Kbps),
+            "pktps" => Some(MeterUnit::Pktps),
+            _ => None,
+        }
+    }
+}
+
+impl ::ovsdb_common::row::ToAtom for MeterUnit {
+    fn to_atom(&self) -> ::ovsdb_common::datum::Atom {
+        ::ovsdb_common::datum::Atom::String(self.as_str().to_string())
+    }
+}
+
+impl ::ovsdb_common::row::FromDatum for MeterUnit {
+    fn from_datum(datum: &::ovsdb_common::datum::Datum) -> ::std::option::Option<Self> {
+        ::ovsdb_common::row::scalar_from_datum(datum)
+    }
+}
+
+impl ::ovsdb_common::row::ToDatum for MeterUnit {
+    fn to_datum(&self) -> ::ovsdb_common::datum::Datum {
+        ::ovsdb_common::datum::Datum::Scalar(::ovsdb_common::row::ToAtom::to_atom(self))
+    }
+}
+
+/// Values of the "action" column of the "Meter_Band" table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MeterBandAction {
+    Drop,
+}
+
+impl MeterBandAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MeterBandAction::Drop => "drop",
+        }
+    }
+}
+
+impl ::ovsdb_common::row::FromAtom for MeterBandAction {
+    fn from_atom(atom: &::ovsdb_common::datum::Atom) -> ::std::option::Option<Self> {
+        match atom.as_str()? {
+            "drop" => Some(MeterBandAction::Drop),
+            _ => None,
+        }
+    }
+}
+
+impl ::ovsdb_common::row::ToAtom for MeterBandAction {
+    fn to_atom(&self) -> ::ovsdb_common::datum::Atom {
+        ::ovsdb_common::datum::Atom::String(self.as_str().to_string())
+    }
+}
+
+impl ::ovsdb_common::row::FromDatum for MeterBandAction {
+    fn from_datum(datum: &::ovsdb_common::datum::Datum) -> ::std::option::Option<Self> {
+        ::ovsdb_common::row::scalar_from_datum(datum)
+    }
+}
+
+impl ::ovsdb_common::row::ToDatum for MeterBandAction {
+    fn to_datum(&self) -> ::ovsdb_common::datum::Datum {
+        ::ovsdb_common::datum::Datum::Scalar(::ovsdb_common::row::ToAtom::to_atom(self))
+    }
+}
+
+/// Values of the "type" column of the "NAT" table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum NatType {
+    Dnat,
+    Snat,
+    DnatAndSnat,
+}
+
+impl NatType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NatType::Dnat => "dnat",
+            NatType::Snat => "snat",
+            NatType::DnatAndSnat => "dnat_and_snat",
+        }
+    }
+}
+
+impl ::ovsdb_common::row::FromAtom for NatType {
+    fn from_atom(atom: &::ovsdb_common::datum::Atom) -> ::std::option::Option<Self> {
+        match atom.as_str()? {
+            "dnat" => Some(NatType::Dnat),
+            "snat" => Some(NatType::Snat),
+            "dnat_and_snat" => Some(NatType::DnatAndSnat),
+            _ => None,
+        }
+    }
+}
+
+impl ::ovsdb_common::row::ToAtom for NatType {
+    fn to_atom(&self) -> ::ovsdb_common::datum::Atom {
+        ::ovsdb_common::datum::Atom::String(self.as_str().to_string())
+    }
+}
+
+impl ::ovsdb_common::row::FromDatum for NatType {
+    fn from_datum(datum: &::ovsdb_common::datum::Datum) -> ::std::option::Option<Self> {
+        ::ovsdb_common::row::scalar_from_datum(datum)
+    }
+}
+
+impl ::ovsdb_common::row::ToDatum for NatType {
+    fn to_datum(&self) -> ::ovsdb_common::datum::Datum {
+        ::ovsdb_common::datum::Datum::Scalar(::ovsdb_common::row::ToAtom::to_atom(self))
+    }
+}
+
+/// Values of the "action" column of the "QoS" table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum QoSAction {
+    Dscp,
+}
+
+impl QoSAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QoSAction::Dscp => "dscp",
+        }
+    }
+}
+
+impl ::ovsdb_common::row::FromAtom for QoSAction {
+    fn from_atom(atom: &::ovsdb_common::datum::Atom) -> ::std::option::Option<Self> {
+        match atom.as_str()? {
+            "dscp" => Some(QoSAction::Dscp),
+            _ => None,
+        }
+    }
+}
+
+impl ::ovsdb_common::row::ToAtom for QoSAction {
+    fn to_atom(&self) -> ::ovsdb_common::datum::Atom {
+        ::ovsdb_common::datum::Atom::String(self.as_str().to_string())
+    }
+}
+
+impl ::ovsdb_common::row::FromDatum for QoSAction {
+    fn from_datum(datum: &::ovsdb_common::datum::Datum) -> ::std::option::Option<Self> {
+        ::ovsdb_common::row::scalar_from_datum(datum)
+    }
+}
+
+impl ::ovsdb_common::row::ToDatum for QoSAction {
+    fn to_datum(&self) -> ::ovsdb_common::datum::Datum {
+        ::ovsdb_common::datum::Datum::Scalar(::ovsdb_common::row::ToAtom::to_atom(self))
+    }
+}
+
+/// Values of the "bandwidth" column of the "QoS" table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum QoSBandwidth {
+    Rate,
+    Burst,
+}
+
+impl QoSBandwidth {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QoSBandwidth::Rate => "rate",
+            QoSBandwidth::Burst => "burst",
+        }
+    }
+}
+
+impl ::ovsdb_common::row::FromAtom for QoSBandwidth {
+    fn from_atom(atom: &::ovsdb_common::datum::Atom) -> ::std::option::Option<Self> {
+        match atom.as_str()? {
+            "rate" => Some(QoSBandwidth::Rate),
+            "burst" => Some(QoSBandwidth::Burst),
+            _ => None,
+        }
+    }
+}
+
+impl ::ovsdb_common::row::ToAtom for QoSBandwidth {
+    fn to_atom(&self) -> ::ovsdb_common::datum::Atom {
+        ::ovsdb_common::datum::Atom::String(self.as_str().to_string())
+    }
+}
+
+impl ::ovsdb_common::row::FromDatum for QoSBandwidth {
+    fn from_datum(datum: &::ovsdb_common::datum::Datum) -> ::std::option::Option<Self> {
+        ::ovsdb_common::row::scalar_from_datum(datum)
+    }
+}
+
+impl ::ovsdb_common::row::ToDatum for QoSBandwidth {
+    fn to_datum(&self) -> ::ovsdb_common::datum::Datum {
+        ::ovsdb_common::datum::Datum::Scalar(::ovsdb_common::row::ToAtom::to_atom(self))
+    }
+}
+
+/// Values of the "direction" column of the "QoS" table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum QoSDirection {
+    FromLport,
+    ToLport,
+}
+
+impl QoSDirection {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QoSDirection::FromLport => "from-lport",
+            QoSDirection::ToLport => "to-lport",
+        }
+    }
+}
+
+impl ::ovsdb_common::row::FromAtom for QoSDirection {
+    fn from_atom(atom: &::ovsdb_common::datum::Atom) -> ::std::option::Option<Self> {
+        match atom.as_str()? {
+            "from-lport" => Some(QoSDirection::FromLport),
+            "to-lport" => Some(QoSDirection::ToLport),
+            _ => None,
+        }
+    }
+}
+
+impl ::ovsdb_common::row::ToAtom for QoSDirection {
+    fn to_atom(&self) -> ::ovsdb_common::datum::Atom {
+        ::ovsdb_common::datum::Atom::String(self.as_str().to_string())
+    }
+}
+
+impl ::ovsdb_common::row::FromDatum for QoSDirection {
+    fn from_datum(datum: &::ovsdb_common::datum::Datum) -> ::std::option::Option<Self> {
+        ::ovsdb_common::row::scalar_from_datum(datum)
+    }
+}
+
+impl ::ovsdb_common::row::ToDatum for QoSDirection {
+    fn to_datum(&self) -> ::ovsdb_common::datum::Datum {
+        ::ovsdb_common::datum::Datum::Scalar(::ovsdb_common::row::ToAtom::to_atom(self))
+    }
+}
+
+/// A row of the "ACL" table.
+///
+/// Not a root table: rows without strong references are garbage collected.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Acl {
+    pub _uuid: ::ovsdb_common::datum::Uuid,
+    pub action: AclAction,
+    pub direction: AclDirection,
+    pub external_ids: ::std::collections::BTreeMap<String, String>,
+    pub label: i64,
+    pub log: bool,
+    pub r#match: String,
+    pub meter: ::std::option::Option<String>,
+    pub name: ::std::option::Option<String>,
+    pub priority: i64,
+    pub severity: ::std::option::Option<AclSeverity>,
+}
+
+impl ::ovsdb_common::row::TableRow for Acl {
+    const TABLE: &'static str = "ACL";
+
+    fn uuid(&self) -> ::ovsdb_common::datum::Uuid {
+        self._uuid
+    }
+
+    fn from_row(
+        uuid: ::ovsdb_common::datum::Uuid,
+        row: &::ovsdb_common::row::Row,
+    ) -> ::std::result::Result<Self, ::ovsdb_common::row::ColumnError> {
+        Ok(Acl {
+            _uuid: uuid,
+            action: ::ovsdb_common::row::column(row, Self::TABLE, "action")?,
+            direction: ::ovsdb_common::row::column(row, Self::TABLE, "direction")?,
+            external_ids: ::ovsdb_common::row::column(row, Self::TABLE, "external_ids")?,
+            label: ::ovsdb_common::row::column(row, Self::TABLE, "label")?,
+            log: ::ovsdb_common::row::column(row, Self::TABLE, "log")?,
+            r#match: ::ovsdb_common::row::column(row, Self::TABLE, "match")?,
+            meter: ::ovsdb_common::row::column(row, Self::TABLE, "meter")?,
+            name: ::ovsdb_common::row::column(row, Self::TABLE, "name")?,
+            priority: ::ovsdb_common::row::column(row, Self::TABLE, "priority")?,
+            severity: ::ovsdb_common::row::column(row, Self::TABLE, "severity")?,
+        })
+    }
+
+    fn to_row(&self) -> ::ovsdb_common::row::Row {
+        let mut row = ::ovsdb_common::row::Row::new();
+        row.insert("action".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.action));
+        row.insert("direction".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.direction));
+        row.insert("external_ids".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.external_ids));
+        row.insert("label".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.label));
+        row.insert("log".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.log));
+        row.insert("match".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.r#match));
+        row.insert("meter".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.meter));
+        row.insert("name".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.name));
+        row.insert("priority".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.priority));
+        row.insert("severity".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.severity));
+        row
+    }
+}
+
+/// A row of the "Address_Set" table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AddressSet {
+    pub _uuid: ::ovsdb_common::datum::Uuid,
+    pub addresses: ::std::collections::BTreeSet<String>,
+    pub external_ids: ::std::collections::BTreeMap<String, String>,
+    pub name: String,
+}
+
+impl ::ovsdb_common::row::TableRow for AddressSet {
+    const TABLE: &'static str = "Address_Set";
+
+    fn uuid(&self) -> ::ovsdb_common::datum::Uuid {
+        self._uuid
+    }
+
+    fn from_row(
+        uuid: ::ovsdb_common::datum::Uuid,
+        row: &::ovsdb_common::row::Row,
+    ) -> ::std::result::Result<Self, ::ovsdb_common::row::ColumnError> {
+        Ok(AddressSet {
+            _uuid: uuid,
+            addresses: ::ovsdb_common::row::column(row, Self::TABLE, "addresses")?,
+            external_ids: ::ovsdb_common::row::column(row, Self::TABLE, "external_ids")?,
+            name: ::ovsdb_common::row::column(row, Self::TABLE, "name")?,
+        })
+    }
+
+    fn to_row(&self) -> ::ovsdb_common::row::Row {
+        let mut row = ::ovsdb_common::row::Row::new();
+        row.insert("addresses".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.addresses));
+        row.insert("external_ids".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.external_ids));
+        row.insert("name".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.name));
+        row
+    }
+}
+
+/// A row of the "BFD" table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bfd {
+    pub _uuid: ::ovsdb_common::datum::Uuid,
+    pub detect_mult: ::std::option::Option<i64>,
+    pub dst_ip: String,
+    pub external_ids: ::std::collections::BTreeMap<String, String>,
+    pub logical_port: String,
+    pub min_rx: ::std::option::Option<i64>,
+    pub min_tx: ::std::option::Option<i64>,
+    pub options: ::std::collections::BTreeMap<String, String>,
+    pub status: ::std::option::Option<BfdStatus>,
+}
+
+impl ::ovsdb_common::row::TableRow for Bfd {
+    const TABLE: &'static str = "BFD";
+
+    fn uuid(&self) -> ::ovsdb_common::datum::Uuid {
+        self._uuid
+    }
+
+    fn from_row(
+        uuid: ::ovsdb_common::datum::Uuid,
+        row: &::ovsdb_common::row::Row,
+    ) -> ::std::result::Result<Self, ::ovsdb_common::row::ColumnError> {
+        Ok(Bfd {
+            _uuid: uuid,
+            detect_mult: ::ovsdb_common::row::column(row, Self::TABLE, "detect_mult")?,
+            dst_ip: ::ovsdb_common::row::column(row, Self::TABLE, "dst_ip")?,
+            external_ids: ::ovsdb_common::row::column(row, Self::TABLE, "external_ids")?,
+            logical_port: ::ovsdb_common::row::column(row, Self::TABLE, "logical_port")?,
+            min_rx: ::ovsdb_common::row::column(row, Self::TABLE, "min_rx")?,
+            min_tx: ::ovsdb_common::row::column(row, Self::TABLE, "min_tx")?,
+            options: ::ovsdb_common::row::column(row, Self::TABLE, "options")?,
+            status: ::ovsdb_common::row::column(row, Self::TABLE, "status")?,
+        })
+    }
+
+    fn to_row(&self) -> ::ovsdb_common::row::Row {
+        let mut row = ::ovsdb_common::row::Row::new();
+        row.insert("detect_mult".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.detect_mult));
+        row.insert("dst_ip".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.dst_ip));
+        row.insert("external_ids".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.external_ids));
+        row.insert("logical_port".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.logical_port));
+        row.insert("min_rx".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.min_rx));
+        row.insert("min_tx".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.min_tx));
+        row.insert("options".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.options));
+        row.insert("status".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.status));
+        row
+    }
+}
+
+/// A row of the "Connection" table.
+///
+/// Not a root table: rows without strong references are garbage collected.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Connection {
+    pub _uuid: ::ovsdb_common::datum::Uuid,
+    pub external_ids: ::std::collections::BTreeMap<String, String>,
+    pub inactivity_probe: ::std::option::Option<i64>,
+    pub is_connected: bool,
+    pub max_backoff: ::std::option::Option<i64>,
+    pub other_config: ::std::collections::BTreeMap<String, String>,
+    pub status: ::std::collections::BTreeMap<String, String>,
+    pub target: String,
+}
+
+impl ::ovsdb_common::row::TableRow for Connection {
+    const TABLE: &'static str = "Connection";
+
+    fn uuid(&self) -> ::ovsdb_common::datum::Uuid {
+        self._uuid
+    }
+
+    fn from_row(
+        uuid: ::ovsdb_common::datum::Uuid,
+        row: &::ovsdb_common::row::Row,
+    ) -> ::std::result::Result<Self, ::ovsdb_common::row::ColumnError> {
+        Ok(Connection {
+            _uuid: uuid,
+            external_ids: ::ovsdb_common::row::column(row, Self::TABLE, "external_ids")?,
+            inactivity_probe: ::ovsdb_common::row::column(row, Self::TABLE, "inactivity_probe")?,
+            is_connected: ::ovsdb_common::row::column(row, Self::TABLE, "is_connected")?,
+            max_backoff: ::ovsdb_common::row::column(row, Self::TABLE, "max_backoff")?,
+            other_config: ::ovsdb_common::row::column(row, Self::TABLE, "other_config")?,
+            status: ::ovsdb_common::row::column(row, Self::TABLE, "status")?,
+            target: ::ovsdb_common::row::column(row, Self::TABLE, "target")?,
+        })
+    }
+
+    fn to_row(&self) -> ::ovsdb_common::row::Row {
+        let mut row = ::ovsdb_common::row::Row::new();
+        row.insert("external_ids".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.external_ids));
+        row.insert("inactivity_probe".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.inactivity_probe));
+        row.insert("is_connected".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.is_connected));
+        row.insert("max_backoff".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.max_backoff));
+        row.insert("other_config".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.other_config));
+        row.insert("status".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.status));
+        row.insert("target".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.target));
+        row
+    }
+}
+
+/// A row of the "Copp" table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Copp {
+    pub _uuid: ::ovsdb_common::datum::Uuid,
+    pub meters: ::std::collections::BTreeMap<String, String>,
+}
+
+impl ::ovsdb_common::row::TableRow for Copp {
+    const TABLE: &'static str = "Copp";
+
+    fn uuid(&self) -> ::ovsdb_common::datum::Uuid {
+        self._uuid
+    }
+
+    fn from_row(
+        uuid: ::ovsdb_common::datum::Uuid,
+        row: &::ovsdb_common::row::Row,
+    ) -> ::std::result::Result<Self, ::ovsdb_common::row::ColumnError> {
+        Ok(Copp {
+            _uuid: uuid,
+            meters: ::ovsdb_common::row::column(row, Self::TABLE, "meters")?,
+        })
+    }
+
+    fn to_row(&self) -> ::ovsdb_common::row::Row {
+        let mut row = ::ovsdb_common::row::Row::new();
+        row.insert("meters".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.meters));
+        row
+    }
+}
+
+/// A row of the "DHCP_Options" table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DhcpOptions {
+    pub _uuid: ::ovsdb_common::datum::Uuid,
+    pub cidr: String,
+    pub external_ids: ::std::collections::BTreeMap<String, String>,
+    pub options: ::std::collections::BTreeMap<String, String>,
+}
+
+impl ::ovsdb_common::row::TableRow for DhcpOptions {
+    const TABLE: &'static str = "DHCP_Options";
+
+    fn uuid(&self) -> ::ovsdb_common::datum::Uuid {
+        self._uuid
+    }
+
+    fn from_row(
+        uuid: ::ovsdb_common::datum::Uuid,
+        row: &::ovsdb_common::row::Row,
+    ) -> ::std::result::Result<Self, ::ovsdb_common::row::ColumnError> {
+        Ok(DhcpOptions {
+            _uuid: uuid,
+            cidr: ::ovsdb_common::row::column(row, Self::TABLE, "cidr")?,
+            external_ids: ::ovsdb_common::row::column(row, Self::TABLE, "external_ids")?,
+            options: ::ovsdb_common::row::column(row, Self::TABLE, "options")?,
+        })
+    }
+
+    fn to_row(&self) -> ::ovsdb_common::row::Row {
+        let mut row = ::ovsdb_common::row::Row::new();
+        row.insert("cidr".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.cidr));
+        row.insert("external_ids".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.external_ids));
+        row.insert("options".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.options));
+        row
+    }
+}
+
+/// A row of the "DNS" table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dns {
+    pub _uuid: ::ovsdb_common::datum::Uuid,
+    pub external_ids: ::std::collections::BTreeMap<String, String>,
+    pub records: ::std::collections::BTreeMap<String, String>,
+}
+
+impl ::ovsdb_common::row::TableRow for Dns {
+    const TABLE: &'static str = "DNS";
+
+    fn uuid(&self) -> ::ovsdb_common::datum::Uuid {
+        self._uuid
+    }
+
+    fn from_row(
+        uuid: ::ovsdb_common::datum::Uuid,
+        row: &::ovsdb_common::row::Row,
+    ) -> ::std::result::Result<Self, ::ovsdb_common::row::ColumnError> {
+        Ok(Dns {
+            _uuid: uuid,
+            external_ids: ::ovsdb_common::row::column(row, Self::TABLE, "external_ids")?,
+            records: ::ovsdb_common::row::column(row, Self::TABLE, "records")?,
+        })
+    }
+
+    fn to_row(&self) -> ::ovsdb_common::row::Row {
+        let mut row = ::ovsdb_common::row::Row::new();
+        row.insert("external_ids".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.external_ids));
+        row.insert("records".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.records));
+        row
+    }
+}
+
+/// A row of the "Forwarding_Group" table.
+///
+/// Not a root table: rows without strong references are garbage collected.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForwardingGroup {
+    pub _uuid: ::ovsdb_common::datum::Uuid,
+    pub child_port: ::std::collections::BTreeSet<String>,
+    pub external_ids: ::std::collections::BTreeMap<String, String>,
+    pub liveness: bool,
+    pub name: String,
+    pub vip: String,
+    pub vmac: String,
+}
+
+impl ::ovsdb_common::row::TableRow for ForwardingGroup {
+    const TABLE: &'static str = "Forwarding_Group";
+
+    fn uuid(&self) -> ::ovsdb_common::datum::Uuid {
+        self._uuid
+    }
+
+    fn from_row(
+        uuid: ::ovsdb_common::datum::Uuid,
+        row: &::ovsdb_common::row::Row,
+    ) -> ::std::result::Result<Self, ::ovsdb_common::row::ColumnError> {
+        Ok(ForwardingGroup {
+            _uuid: uuid,
+            child_port: ::ovsdb_common::row::column(row, Self::TABLE, "child_port")?,
+            external_ids: ::ovsdb_common::row::column(row, Self::TABLE, "external_ids")?,
+            liveness: ::ovsdb_common::row::column(row, Self::TABLE, "liveness")?,
+            name: ::ovsdb_common::row::column(row, Self::TABLE, "name")?,
+            vip: ::ovsdb_common::row::column(row, Self::TABLE, "vip")?,
+            vmac: ::ovsdb_common::row::column(row, Self::TABLE, "vmac")?,
+        })
+    }
+
+    fn to_row(&self) -> ::ovsdb_common::row::Row {
+        let mut row = ::ovsdb_common::row::Row::new();
+        row.insert("child_port".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.child_port));
+        row.insert("external_ids".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.external_ids));
+        row.insert("liveness".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.liveness));
+        row.insert("name".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.name));
+        row.insert("vip".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.vip));
+        row.insert("vmac".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.vmac));
+        row
+    }
+}
+
+/// A row of the "Gateway_Chassis" table.
+///
+/// Not a root table: rows without strong references are garbage collected.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GatewayChassis {
+    pub _uuid: ::ovsdb_common::datum::Uuid,
+    pub chassis_name: String,
+    pub external_ids: ::std::collections::BTreeMap<String, String>,
+    pub name: String,
+    pub options: ::std::collections::BTreeMap<String, String>,
+    pub priority: i64,
+}
+
+impl ::ovsdb_common::row::TableRow for GatewayChassis {
+    const TABLE: &'static str = "Gateway_Chassis";
+
+    fn uuid(&self) -> ::ovsdb_common::datum::Uuid {
+        self._uuid
+    }
+
+    fn from_row(
+        uuid: ::ovsdb_common::datum::Uuid,
+        row: &::ovsdb_common::row::Row,
+    ) -> ::std::result::Result<Self, ::ovsdb_common::row::ColumnError> {
+        Ok(GatewayChassis {
+            _uuid: uuid,
+            chassis_name: ::ovsdb_common::row::column(row, Self::TABLE, "chassis_name")?,
+            external_ids: ::ovsdb_common::row::column(row, Self::TABLE, "external_ids")?,
+            name: ::ovsdb_common::row::column(row, Self::TABLE, "name")?,
+            options: ::ovsdb_common::row::column(row, Self::TABLE, "options")?,
+            priority: ::ovsdb_common::row::column(row, Self::TABLE, "priority")?,
+        })
+    }
+
+    fn to_row(&self) -> ::ovsdb_common::row::Row {
+        let mut row = ::ovsdb_common::row::Row::new();
+        row.insert("chassis_name".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.chassis_name));
+        row.insert("external_ids".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.external_ids));
+        row.insert("name".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.name));
+        row.insert("options".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.options));
+        row.insert("priority".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.priority));
+        row
+    }
+}
+
+/// A row of the "HA_Chassis" table.
+///
+/// Not a root table: rows without strong references are garbage collected.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HaChassis {
+    pub _uuid: ::ovsdb_common::datum::Uuid,
+    pub chassis_name: String,
+    pub external_ids: ::std::collections::BTreeMap<String, String>,
+    pub priority: i64,
+}
+
+impl ::ovsdb_common::row::TableRow for HaChassis {
+    const TABLE: &'static str = "HA_Chassis";
+
+    fn uuid(&self) -> ::ovsdb_common::datum::Uuid {
+        self._uuid
+    }
+
+    fn from_row(
+        uuid: ::ovsdb_common::datum::Uuid,
+        row: &::ovsdb_common::row::Row,
+    ) -> ::std::result::Result<Self, ::ovsdb_common::row::ColumnError> {
+        Ok(HaChassis {
+            _uuid: uuid,
+            chassis_name: ::ovsdb_common::row::column(row, Self::TABLE, "chassis_name")?,
+            external_ids: ::ovsdb_common::row::column(row, Self::TABLE, "external_ids")?,
+            priority: ::ovsdb_common::row::column(row, Self::TABLE, "priority")?,
+        })
+    }
+
+    fn to_row(&self) -> ::ovsdb_common::row::Row {
+        let mut row = ::ovsdb_common::row::Row::new();
+        row.insert("chassis_name".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.chassis_name));
+        row.insert("external_ids".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.external_ids));
+        row.insert("priority".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.priority));
+        row
+    }
+}
+
+/// A row of the "HA_Chassis_Group" table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HaChassisGroup {
+    pub _uuid: ::ovsdb_common::datum::Uuid,
+    pub external_ids: ::std::collections::BTreeMap<String, String>,
+    pub ha_chassis: ::std::collections::BTreeSet<::ovsdb_common::row::Ref<HaChassis>>,
+    pub name: String,
+}
+
+impl ::ovsdb_common::row::TableRow for HaChassisGroup {
+    const TABLE: &'static str = "HA_Chassis_Group";
+
+    fn uuid(&self) -> ::ovsdb_common::datum::Uuid {
+        self._uuid
+    }
+
+    fn from_row(
+        uuid: ::ovsdb_common::datum::Uuid,
+        row: &::ovsdb_common::row::Row,
+    ) -> ::std::result::Result<Self, ::ovsdb_common::row::ColumnError> {
+        Ok(HaChassisGroup {
+            _uuid: uuid,
+            external_ids: ::ovsdb_common::row::column(row, Self::TABLE, "external_ids")?,
+            ha_chassis: ::ovsdb_common::row::column(row, Self::TABLE, "ha_chassis")?,
+            name: ::ovsdb_common::row::column(row, Self::TABLE, "name")?,
+        })
+    }
+
+    fn to_row(&self) -> ::ovsdb_common::row::Row {
+        let mut row = ::ovsdb_common::row::Row::new();
+        row.insert("external_ids".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.external_ids));
+        row.insert("ha_chassis".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.ha_chassis));
+        row.insert("name".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.name));
+        row
+    }
+}
+
+/// A row of the "Load_Balancer" table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadBalancer {
+    pub _uuid: ::ovsdb_common::datum::Uuid,
+    pub external_ids: ::std::collections::BTreeMap<String, String>,
+    pub health_check: ::std::collections::BTreeSet<::ovsdb_common::row::Ref<LoadBalancerHealthCheck>>,
+    pub ip_port_mappings: ::std::collections::BTreeMap<String, String>,
+    pub name: String,
+    pub options: ::std::collections::BTreeMap<String, String>,
+    pub protocol: ::std::option::Option<LoadBalancerProtocol>,
+    pub selection_fields: ::std::collections::BTreeSet<LoadBalancerSelectionFields>,
+    pub vips: ::std::collections::BTreeMap<String, String>,
+}
+
+impl ::ovsdb_common::row::TableRow for LoadBalancer {
+    const TABLE: &'static str = "Load_Balancer";
+
+    fn uuid(&self) -> ::ovsdb_common::datum::Uuid {
+        self._uuid
+    }
+
+    fn from_row(
+        uuid: ::ovsdb_common::datum::Uuid,
+        row: &::ovsdb_common::row::Row,
+    ) -> ::std::result::Result<Self, ::ovsdb_common::row::ColumnError> {
+        Ok(LoadBalancer {
+            _uuid: uuid,
+            external_ids: ::ovsdb_common::row::column(row, Self::TABLE, "external_ids")?,
+            health_check: ::ovsdb_common::row::column(row, Self::TABLE, "health_check")?,
+            ip_port_mappings: ::ovsdb_common::row::column(row, Self::TABLE, "ip_port_mappings")?,
+            name: ::ovsdb_common::row::column(row, Self::TABLE, "name")?,
+            options: ::ovsdb_common::row::column(row, Self::TABLE, "options")?,
+            protocol: ::ovsdb_common::row::column(row, Self::TABLE, "protocol")?,
+            selection_fields: ::ovsdb_common::row::column(row, Self::TABLE, "selection_fields")?,
+            vips: ::ovsdb_common::row::column(row, Self::TABLE, "vips")?,
+        })
+    }
+
+    fn to_row(&self) -> ::ovsdb_common::row::Row {
+        let mut row = ::ovsdb_common::row::Row::new();
+        row.insert("external_ids".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.external_ids));
+        row.insert("health_check".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.health_check));
+        row.insert("ip_port_mappings".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.ip_port_mappings));
+        row.insert("name".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.name));
+        row.insert("options".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.options));
+        row.insert("protocol".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.protocol));
+        row.insert("selection_fields".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.selection_fields));
+        row.insert("vips".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.vips));
+        row
+    }
+}
+
+/// A row of the "Load_Balancer_Group" table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadBalancerGroup {
+    pub _uuid: ::ovsdb_common::datum::Uuid,
+    pub load_balancer: ::std::collections::BTreeSet<::ovsdb_common::row::Ref<LoadBalancer>>,
+    pub name: String,
+}
+
+impl ::ovsdb_common::row::TableRow for LoadBalancerGroup {
+    const TABLE: &'static str = "Load_Balancer_Group";
+
+    fn uuid(&self) -> ::ovsdb_common::datum::Uuid {
+        self._uuid
+    }
+
+    fn from_row(
+        uuid: ::ovsdb_common::datum::Uuid,
+        row: &::ovsdb_common::row::Row,
+    ) -> ::std::result::Result<Self, ::ovsdb_common::row::ColumnError> {
+        Ok(LoadBalancerGroup {
+            _uuid: uuid,
+            load_balancer: ::ovsdb_common::row::column(row, Self::TABLE, "load_balancer")?,
+            name: ::ovsdb_common::row::column(row, Self::TABLE, "name")?,
+        })
+    }
+
+    fn to_row(&self) -> ::ovsdb_common::row::Row {
+        let mut row = ::ovsdb_common::row::Row::new();
+        row.insert("load_balancer".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.load_balancer));
+        row.insert("name".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.name));
+        row
+    }
+}
+
+/// A row of the "Load_Balancer_Health_Check" table.
+///
+/// Not a root table: rows without strong references are garbage collected.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadBalancerHealthCheck {
+    pub _uuid: ::ovsdb_common::datum::Uuid,
+    pub external_ids: ::std::collections::BTreeMap<String, String>,
+    pub options: ::std::collections::BTreeMap<String, String>,
+    pub vip: String,
+}
+
+impl ::ovsdb_common::row::TableRow for LoadBalancerHealthCheck {
+    const TABLE: &'static str = "Load_Balancer_Health_Check";
+
+    fn uuid(&self) -> ::ovsdb_common::datum::Uuid {
+        self._uuid
+    }
+
+    fn from_row(
+        uuid: ::ovsdb_common::datum::Uuid,
+        row: &::ovsdb_common::row::Row,
+    ) -> ::std::result::Result<Self, ::ovsdb_common::row::ColumnError> {
+        Ok(LoadBalancerHealthCheck {
+            _uuid: uuid,
+            external_ids: ::ovsdb_common::row::column(row, Self::TABLE, "external_ids")?,
+            options: ::ovsdb_common::row::column(row, Self::TABLE, "options")?,
+            vip: ::ovsdb_common::row::column(row, Self::TABLE, "vip")?,
+        })
+    }
+
+    fn to_row(&self) -> ::ovsdb_common::row::Row {
+        let mut row = ::ovsdb_common::row::Row::new();
+        row.insert("external_ids".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.external_ids));
+        row.insert("options".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.options));
+        row.insert("vip".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.vip));
+        row
+    }
+}
+
+/// A row of the "Logical_Router" table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogicalRouter {
+    pub _uuid: ::ovsdb_common::datum::Uuid,
+    pub copp: ::std::option::Option<::ovsdb_common::row::Ref<Copp>>,
+    pub enabled: ::std::option::Option<bool>,
+    pub external_ids: ::std::collections::BTreeMap<String, String>,
+    pub load_balancer: ::std::collections::BTreeSet<::ovsdb_common::row::Ref<LoadBalancer>>,
+    pub load_balancer_group: ::std::collections::BTreeSet<::ovsdb_common::row::Ref<LoadBalancerGroup>>,
+    pub name: String,
+    pub nat: ::std::collections::BTreeSet<::ovsdb_common::row::Ref<Nat>>,
+    pub options: ::std::collections::BTreeMap<String, String>,
+    pub policies: ::std::collections::BTreeSet<::ovsdb_common::row::Ref<LogicalRouterPolicy>>,
+    pub ports: ::std::collections::BTreeSet<::ovsdb_common::row::Ref<LogicalRouterPort>>,
+    pub static_routes: ::std::collections::BTreeSet<::ovsdb_common::row::Ref<LogicalRouterStaticRoute>>,
+}
+
+impl ::ovsdb_common::row::TableRow for LogicalRouter {
+    const TABLE: &'static str = "Logical_Router";
+
+    fn uuid(&self) -> ::ovsdb_common::datum::Uuid {
+        self._uuid
+    }
+
+    fn from_row(
+        uuid: ::ovsdb_common::datum::Uuid,
+        row: &::ovsdb_common::row::Row,
+    ) -> ::std::result::Result<Self, ::ovsdb_common::row::ColumnError> {
+        Ok(LogicalRouter {
+            _uuid: uuid,
+            copp: ::ovsdb_common::row::column(row, Self::TABLE, "copp")?,
+            enabled: ::ovsdb_common::row::column(row, Self::TABLE, "enabled")?,
+            external_ids: ::ovsdb_common::row::column(row, Self::TABLE, "external_ids")?,
+            load_balancer: ::ovsdb_common::row::column(row, Self::TABLE, "load_balancer")?,
+            load_balancer_group: ::ovsdb_common::row::column(row, Self::TABLE, "load_balancer_group")?,
+            name: ::ovsdb_common::row::column(row, Self::TABLE, "name")?,
+            nat: ::ovsdb_common::row::column(row, Self::TABLE, "nat")?,
+            options: ::ovsdb_common::row::column(row, Self::TABLE, "options")?,
+            policies: ::ovsdb_common::row::column(row, Self::TABLE, "policies")?,
+            ports: ::ovsdb_common::row::column(row, Self::TABLE, "ports")?,
+            static_routes: ::ovsdb_common::row::column(row, Self::TABLE, "static_routes")?,
+        })
+    }
+
+    fn to_row(&self) -> ::ovsdb_common::row::Row {
+        let mut row = ::ovsdb_common::row::Row::new();
+        row.insert("copp".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.copp));
+        row.insert("enabled".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.enabled));
+        row.insert("external_ids".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.external_ids));
+        row.insert("load_balancer".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.load_balancer));
+        row.insert("load_balancer_group".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.load_balancer_group));
+        row.insert("name".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.name));
+        row.insert("nat".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.nat));
+        row.insert("options".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.options));
+        row.insert("policies".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.policies));
+        row.insert("ports".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.ports));
+        row.insert("static_routes".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.static_routes));
+        row
+    }
+}
+
+/// A row of the "Logical_Router_Policy" table.
+///
+/// Not a root table: rows without strong references are garbage collected.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogicalRouterPolicy {
+    pub _uuid: ::ovsdb_common::datum::Uuid,
+    pub action: LogicalRouterPolicyAction,
+    pub external_ids: ::std::collections::BTreeMap<String, String>,
+    pub r#match: String,
+    pub nexthop: ::std::option::Option<String>,
+    pub nexthops: ::std::collections::BTreeSet<String>,
+    pub options: ::std::collections::BTreeMap<String, String>,
+    pub priority: i64,
+}
+
+impl ::ovsdb_common::row::TableRow for LogicalRouterPolicy {
+    const TABLE: &'static str = "Logical_Router_Policy";
+
+    fn uuid(&self) -> ::ovsdb_common::datum::Uuid {
+        self._uuid
+    }
+
+    fn from_row(
+        uuid: ::ovsdb_common::datum::Uuid,
+        row: &::ovsdb_common::row::Row,
+    ) -> ::std::result::Result<Self, ::ovsdb_common::row::ColumnError> {
+        Ok(LogicalRouterPolicy {
+            _uuid: uuid,
+            action: ::ovsdb_common::row::column(row, Self::TABLE, "action")?,
+            external_ids: ::ovsdb_common::row::column(row, Self::TABLE, "external_ids")?,
+            r#match: ::ovsdb_common::row::column(row, Self::TABLE, "match")?,
+            nexthop: ::ovsdb_common::row::column(row, Self::TABLE, "nexthop")?,
+            nexthops: ::ovsdb_common::row::column(row, Self::TABLE, "nexthops")?,
+            options: ::ovsdb_common::row::column(row, Self::TABLE, "options")?,
+            priority: ::ovsdb_common::row::column(row, Self::TABLE, "priority")?,
+        })
+    }
+
+    fn to_row(&self) -> ::ovsdb_common::row::Row {
+        let mut row = ::ovsdb_common::row::Row::new();
+        row.insert("action".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.action));
+        row.insert("external_ids".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.external_ids));
+        row.insert("match".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.r#match));
+        row.insert("nexthop".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.nexthop));
+        row.insert("nexthops".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.nexthops));
+        row.insert("options".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.options));
+        row.insert("priority".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.priority));
+        row
+    }
+}
+
+/// A row of the "Logical_Router_Port" table.
+///
+/// Not a root table: rows without strong references are garbage collected.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogicalRouterPort {
+    pub _uuid: ::ovsdb_common::datum::Uuid,
+    pub enabled: ::std::option::Option<bool>,
+    pub external_ids: ::std::collections::BTreeMap<String, String>,
+    pub gateway_chassis: ::std::collections::BTreeSet<::ovsdb_common::row::Ref<GatewayChassis>>,
+    pub ha_chassis_group: ::std::option::Option<::ovsdb_common::row::Ref<HaChassisGroup>>,
+    pub ipv6_prefix: ::std::collections::BTreeSet<String>,
+    pub ipv6_ra_configs: ::std::collections::BTreeMap<String, String>,
+    pub mac: String,
+    pub name: String,
+    pub networks: ::std::collections::BTreeSet<String>,
+    pub options: ::std::collections::BTreeMap<String, String>,
+    pub peer: ::std::option::Option<String>,
+}
+
+impl ::ovsdb_common::row::TableRow for LogicalRouterPort {
+    const TABLE: &'static str = "Logical_Router_Port";
+
+    fn uuid(&self) -> ::ovsdb_common::datum::Uuid {
+        self._uuid
+    }
+
+    fn from_row(
+        uuid: ::ovsdb_common::datum::Uuid,
+        row: &::ovsdb_common::row::Row,
+    ) -> ::std::result::Result<Self, ::ovsdb_common::row::ColumnError> {
+        Ok(LogicalRouterPort {
+            _uuid: uuid,
+            enabled: ::ovsdb_common::row::column(row, Self::TABLE, "enabled")?,
+            external_ids: ::ovsdb_common::row::column(row, Self::TABLE, "external_ids")?,
+            gateway_chassis: ::ovsdb_common::row::column(row, Self::TABLE, "gateway_chassis")?,
+            ha_chassis_group: ::ovsdb_common::row::column(row, Self::TABLE, "ha_chassis_group")?,
+            ipv6_prefix: ::ovsdb_common::row::column(row, Self::TABLE, "ipv6_prefix")?,
+            ipv6_ra_configs: ::ovsdb_common::row::column(row, Self::TABLE, "ipv6_ra_configs")?,
+            mac: ::ovsdb_common::row::column(row, Self::TABLE, "mac")?,
+            name: ::ovsdb_common::row::column(row, Self::TABLE, "name")?,
+            networks: ::ovsdb_common::row::column(row, Self::TABLE, "networks")?,
+            options: ::ovsdb_common::row::column(row, Self::TABLE, "options")?,
+            peer: ::ovsdb_common::row::column(row, Self::TABLE, "peer")?,
+        })
+    }
+
+    fn to_row(&self) -> ::ovsdb_common::row::Row {
+        let mut row = ::ovsdb_common::row::Row::new();
+        row.insert("enabled".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.enabled));
+        row.insert("external_ids".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.external_ids));
+        row.insert("gateway_chassis".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.gateway_chassis));
+        row.insert("ha_chassis_group".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.ha_chassis_group));
+        row.insert("ipv6_prefix".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.ipv6_prefix));
+        row.insert("ipv6_ra_configs".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.ipv6_ra_configs));
+        row.insert("mac".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.mac));
+        row.insert("name".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.name));
+        row.insert("networks".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.networks));
+        row.insert("options".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.options));
+        row.insert("peer".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.peer));
+        row
+    }
+}
+
+/// A row of the "Logical_Router_Static_Route" table.
+///
+/// Not a root table: rows without strong references are garbage collected.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogicalRouterStaticRoute {
+    pub _uuid: ::ovsdb_common::datum::Uuid,
+    pub bfd: ::std::option::Option<::ovsdb_common::row::Ref<Bfd>>,
+    pub external_ids: ::std::collections::BTreeMap<String, String>,
+    pub ip_prefix: String,
+    pub nexthop: String,
+    pub options: ::std::collections::BTreeMap<String, String>,
+    pub output_port: ::std::option::Option<String>,
+    pub policy: ::std::option::Option<LogicalRouterStaticRoutePolicy>,
+}
+
+impl ::ovsdb_common::row::TableRow for LogicalRouterStaticRoute {
+    const TABLE: &'static str = "Logical_Router_Static_Route";
+
+    fn uuid(&self) -> ::ovsdb_common::datum::Uuid {
+        self._uuid
+    }
+
+    fn from_row(
+        uuid: ::ovsdb_common::datum::Uuid,
+        row: &::ovsdb_common::row::Row,
+    ) -> ::std::result::Result<Self, ::ovsdb_common::row::ColumnError> {
+        Ok(LogicalRouterStaticRoute {
+            _uuid: uuid,
+            bfd: ::ovsdb_common::row::column(row, Self::TABLE, "bfd")?,
+            external_ids: ::ovsdb_common::row::column(row, Self::TABLE, "external_ids")?,
+            ip_prefix: ::ovsdb_common::row::column(row, Self::TABLE, "ip_prefix")?,
+            nexthop: ::ovsdb_common::row::column(row, Self::TABLE, "nexthop")?,
+            options: ::ovsdb_common::row::column(row, Self::TABLE, "options")?,
+            output_port: ::ovsdb_common::row::column(row, Self::TABLE, "output_port")?,
+            policy: ::ovsdb_common::row::column(row, Self::TABLE, "policy")?,
+        })
+    }
+
+    fn to_row(&self) -> ::ovsdb_common::row::Row {
+        let mut row = ::ovsdb_common::row::Row::new();
+        row.insert("bfd".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.bfd));
+        row.insert("external_ids".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.external_ids));
+        row.insert("ip_prefix".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.ip_prefix));
+        row.insert("nexthop".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.nexthop));
+        row.insert("options".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.options));
+        row.insert("output_port".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.output_port));
+        row.insert("policy".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.policy));
+        row
+    }
+}
+
+/// A row of the "Logical_Switch" table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogicalSwitch {
+    pub _uuid: ::ovsdb_common::datum::Uuid,
+    pub acls: ::std::collections::BTreeSet<::ovsdb_common::row::Ref<Acl>>,
+    pub copp: ::std::option::Option<::ovsdb_common::row::Ref<Copp>>,
+    pub dns_records: ::std::collections::BTreeSet<::ovsdb_common::row::Ref<Dns>>,
+    pub external_ids: ::std::collections::BTreeMap<String, String>,
+    pub forwarding_groups: ::std::collections::BTreeSet<::ovsdb_common::row::Ref<ForwardingGroup>>,
+    pub load_balancer: ::std::collections::BTreeSet<::ovsdb_common::row::Ref<LoadBalancer>>,
+    pub load_balancer_group: ::std::collections::BTreeSet<::ovsdb_common::row::Ref<LoadBalancerGroup>>,
+    pub name: String,
+    pub other_config: ::std::collections::BTreeMap<String, String>,
+    pub ports: ::std::collections::BTreeSet<::ovsdb_common::row::Ref<LogicalSwitchPort>>,
+    pub qos_rules: ::std::collections::BTreeSet<::ovsdb_common::row::Ref<QoS>>,
+}
+
+impl ::ovsdb_common::row::TableRow for LogicalSwitch {
+    const TABLE: &'static str = "Logical_Switch";
+
+    fn uuid(&self) -> ::ovsdb_common::datum::Uuid {
+        self._uuid
+    }
+
+    fn from_row(
+        uuid: ::ovsdb_common::datum::Uuid,
+        row: &::ovsdb_common::row::Row,
+    ) -> ::std::result::Result<Self, ::ovsdb_common::row::ColumnError> {
+        Ok(LogicalSwitch {
+            _uuid: uuid,
+            acls: ::ovsdb_common::row::column(row, Self::TABLE, "acls")?,
+            copp: ::ovsdb_common::row::column(row, Self::TABLE, "copp")?,
+            dns_records: ::ovsdb_common::row::column(row, Self::TABLE, "dns_records")?,
+            external_ids: ::ovsdb_common::row::column(row, Self::TABLE, "external_ids")?,
+            forwarding_groups: ::ovsdb_common::row::column(row, Self::TABLE, "forwarding_groups")?,
+            load_balancer: ::ovsdb_common::row::column(row, Self::TABLE, "load_balancer")?,
+            load_balancer_group: ::ovsdb_common::row::column(row, Self::TABLE, "load_balancer_group")?,
+            name: ::ovsdb_common::row::column(row, Self::TABLE, "name")?,
+            other_config: ::ovsdb_common::row::column(row, Self::TABLE, "other_config")?,
+            ports: ::ovsdb_common::row::column(row, Self::TABLE, "ports")?,
+            qos_rules: ::ovsdb_common::row::column(row, Self::TABLE, "qos_rules")?,
+        })
+    }
+
+    fn to_row(&self) -> ::ovsdb_common::row::Row {
+        let mut row = ::ovsdb_common::row::Row::new();
+        row.insert("acls".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.acls));
+        row.insert("copp".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.copp));
+        row.insert("dns_records".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.dns_records));
+        row.insert("external_ids".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.external_ids));
+        row.insert("forwarding_groups".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.forwarding_groups));
+        row.insert("load_balancer".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.load_balancer));
+        row.insert("load_balancer_group".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.load_balancer_group));
+        row.insert("name".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.name));
+        row.insert("other_config".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.other_config));
+        row.insert("ports".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.ports));
+        row.insert("qos_rules".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.qos_rules));
+        row
+    }
+}
+
+/// A row of the "Logical_Switch_Port" table.
+///
+/// Not a root table: rows without strong references are garbage collected.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogicalSwitchPort {
+    pub _uuid: ::ovsdb_common::datum::Uuid,
+    pub addresses: ::std::collections::BTreeSet<String>,
+    pub dhcpv4_options: ::std::option::Option<::ovsdb_common::row::Ref<DhcpOptions>>,
+    pub dhcpv6_options: ::std::option::Option<::ovsdb_common::row::Ref<DhcpOptions>>,
+    pub dynamic_addresses: ::std::option::Option<String>,
+    pub enabled: ::std::option::Option<bool>,
+    pub external_ids: ::std::collections::BTreeMap<String, String>,
+    pub ha_chassis_group: ::std::option::Option<::ovsdb_common::row::Ref<HaChassisGroup>>,
+    pub name: String,
+    pub options: ::std::collections::BTreeMap<String, String>,
+    pub parent_name: ::std::option::Option<String>,
+    pub port_security: ::std::collections::BTreeSet<String>,
+    pub tag: ::std::option::Option<i64>,
+    pub tag_request: ::std::option::Option<i64>,
+    pub r#type: String,
+    pub up: ::std::option::Option<bool>,
+}
+
+impl ::ovsdb_common::row::TableRow for LogicalSwitchPort {
+    const TABLE: &'static str = "Logical_Switch_Port";
+
+    fn uuid(&self) -> ::ovsdb_common::datum::Uuid {
+        self._uuid
+    }
+
+    fn from_row(
+        uuid: ::ovsdb_common::datum::Uuid,
+        row: &::ovsdb_common::row::Row,
+    ) -> ::std::result::Result<Self, ::ovsdb_common::row::ColumnError> {
+        Ok(LogicalSwitchPort {
+            _uuid: uuid,
+            addresses: ::ovsdb_common::row::column(row, Self::TABLE, "addresses")?,
+            dhcpv4_options: ::ovsdb_common::row::column(row, Self::TABLE, "dhcpv4_options")?,
+            dhcpv6_options: ::ovsdb_common::row::column(row, Self::TABLE, "dhcpv6_options")?,
+            dynamic_addresses: ::ovsdb_common::row::column(row, Self::TABLE, "dynamic_addresses")?,
+            enabled: ::ovsdb_common::row::column(row, Self::TABLE, "enabled")?,
+            external_ids: ::ovsdb_common::row::column(row, Self::TABLE, "external_ids")?,
+            ha_chassis_group: ::ovsdb_common::row::column(row, Self::TABLE, "ha_chassis_group")?,
+            name: ::ovsdb_common::row::column(row, Self::TABLE, "name")?,
+            options: ::ovsdb_common::row::column(row, Self::TABLE, "options")?,
+            parent_name: ::ovsdb_common::row::column(row, Self::TABLE, "parent_name")?,
+            port_security: ::ovsdb_common::row::column(row, Self::TABLE, "port_security")?,
+            tag: ::ovsdb_common::row::column(row, Self::TABLE, "tag")?,
+            tag_request: ::ovsdb_common::row::column(row, Self::TABLE, "tag_request")?,
+            r#type: ::ovsdb_common::row::column(row, Self::TABLE, "type")?,
+            up: ::ovsdb_common::row::column(row, Self::TABLE, "up")?,
+        })
+    }
+
+    fn to_row(&self) -> ::ovsdb_common::row::Row {
+        let mut row = ::ovsdb_common::row::Row::new();
+        row.insert("addresses".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.addresses));
+        row.insert("dhcpv4_options".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.dhcpv4_options));
+        row.insert("dhcpv6_options".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.dhcpv6_options));
+        row.insert("dynamic_addresses".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.dynamic_addresses));
+        row.insert("enabled".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.enabled));
+        row.insert("external_ids".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.external_ids));
+        row.insert("ha_chassis_group".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.ha_chassis_group));
+        row.insert("name".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.name));
+        row.insert("options".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.options));
+        row.insert("parent_name".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.parent_name));
+        row.insert("port_security".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.port_security));
+        row.insert("tag".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.tag));
+        row.insert("tag_request".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.tag_request));
+        row.insert("type".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.r#type));
+        row.insert("up".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.up));
+        row
+    }
+}
+
+/// A row of the "Meter" table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Meter {
+    pub _uuid: ::ovsdb_common::datum::Uuid,
+    pub bands: ::std::collections::BTreeSet<::ovsdb_common::row::Ref<MeterBand>>,
+    pub external_ids: ::std::collections::BTreeMap<String, String>,
+    pub fair: ::std::option::Option<bool>,
+    pub name: String,
+    pub unit: MeterUnit,
+}
+
+impl ::ovsdb_common::row::TableRow for Meter {
+    const TABLE: &'static str = "Meter";
+
+    fn uuid(&self) -> ::ovsdb_common::datum::Uuid {
+        self._uuid
+    }
+
+    fn from_row(
+        uuid: ::ovsdb_common::datum::Uuid,
+        row: &::ovsdb_common::row::Row,
+    ) -> ::std::result::Result<Self, ::ovsdb_common::row::ColumnError> {
+        Ok(Meter {
+            _uuid: uuid,
+            bands: ::ovsdb_common::row::column(row, Self::TABLE, "bands")?,
+            external_ids: ::ovsdb_common::row::column(row, Self::TABLE, "external_ids")?,
+            fair: ::ovsdb_common::row::column(row, Self::TABLE, "fair")?,
+            name: ::ovsdb_common::row::column(row, Self::TABLE, "name")?,
+            unit: ::ovsdb_common::row::column(row, Self::TABLE, "unit")?,
+        })
+    }
+
+    fn to_row(&self) -> ::ovsdb_common::row::Row {
+        let mut row = ::ovsdb_common::row::Row::new();
+        row.insert("bands".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.bands));
+        row.insert("external_ids".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.external_ids));
+        row.insert("fair".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.fair));
+        row.insert("name".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.name));
+        row.insert("unit".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.unit));
+        row
+    }
+}
+
+/// A row of the "Meter_Band" table.
+///
+/// Not a root table: rows without strong references are garbage collected.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeterBand {
+    pub _uuid: ::ovsdb_common::datum::Uuid,
+    pub action: MeterBandAction,
+    pub burst_size: i64,
+    pub external_ids: ::std::collections::BTreeMap<String, String>,
+    pub rate: i64,
+}
+
+impl ::ovsdb_common::row::TableRow for MeterBand {
+    const TABLE: &'static str = "Meter_Band";
+
+    fn uuid(&self) -> ::ovsdb_common::datum::Uuid {
+        self._uuid
+    }
+
+    fn from_row(
+        uuid: ::ovsdb_common::datum::Uuid,
+        row: &::ovsdb_common::row::Row,
+    ) -> ::std::result::Result<Self, ::ovsdb_common::row::ColumnError> {
+        Ok(MeterBand {
+            _uuid: uuid,
+            action: ::ovsdb_common::row::column(row, Self::TABLE, "action")?,
+            burst_size: ::ovsdb_common::row::column(row, Self::TABLE, "burst_size")?,
+            external_ids: ::ovsdb_common::row::column(row, Self::TABLE, "external_ids")?,
+            rate: ::ovsdb_common::row::column(row, Self::TABLE, "rate")?,
+        })
+    }
+
+    fn to_row(&self) -> ::ovsdb_common::row::Row {
+        let mut row = ::ovsdb_common::row::Row::new();
+        row.insert("action".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.action));
+        row.insert("burst_size".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.burst_size));
+        row.insert("external_ids".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.external_ids));
+        row.insert("rate".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.rate));
+        row
+    }
+}
+
+/// A row of the "NAT" table.
+///
+/// Not a root table: rows without strong references are garbage collected.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Nat {
+    pub _uuid: ::ovsdb_common::datum::Uuid,
+    pub allowed_ext_ips: ::std::option::Option<::ovsdb_common::row::Ref<AddressSet>>,
+    pub exempted_ext_ips: ::std::option::Option<::ovsdb_common::row::Ref<AddressSet>>,
+    pub external_ids: ::std::collections::BTreeMap<String, String>,
+    pub external_ip: String,
+    pub external_mac: ::std::option::Option<String>,
+    pub external_port_range: String,
+    pub logical_ip: String,
+    pub logical_port: ::std::option::Option<String>,
+    pub options: ::std::collections::BTreeMap<String, String>,
+    pub r#type: NatType,
+}
+
+impl ::ovsdb_common::row::TableRow for Nat {
+    const TABLE: &'static str = "NAT";
+
+    fn uuid(&self) -> ::ovsdb_common::datum::Uuid {
+        self._uuid
+    }
+
+    fn from_row(
+        uuid: ::ovsdb_common::datum::Uuid,
+        row: &::ovsdb_common::row::Row,
+    ) -> ::std::result::Result<Self, ::ovsdb_common::row::ColumnError> {
+        Ok(Nat {
+            _uuid: uuid,
+            allowed_ext_ips: ::ovsdb_common::row::column(row, Self::TABLE, "allowed_ext_ips")?,
+            exempted_ext_ips: ::ovsdb_common::row::column(row, Self::TABLE, "exempted_ext_ips")?,
+            external_ids: ::ovsdb_common::row::column(row, Self::TABLE, "external_ids")?,
+            external_ip: ::ovsdb_common::row::column(row, Self::TABLE, "external_ip")?,
+            external_mac: ::ovsdb_common::row::column(row, Self::TABLE, "external_mac")?,
+            external_port_range: ::ovsdb_common::row::column(row, Self::TABLE, "external_port_range")?,
+            logical_ip: ::ovsdb_common::row::column(row, Self::TABLE, "logical_ip")?,
+            logical_port: ::ovsdb_common::row::column(row, Self::TABLE, "logical_port")?,
+            options: ::ovsdb_common::row::column(row, Self::TABLE, "options")?,
+            r#type: ::ovsdb_common::row::column(row, Self::TABLE, "type")?,
+        })
+    }
+
+    fn to_row(&self) -> ::ovsdb_common::row::Row {
+        let mut row = ::ovsdb_common::row::Row::new();
+        row.insert("allowed_ext_ips".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.allowed_ext_ips));
+        row.insert("exempted_ext_ips".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.exempted_ext_ips));
+        row.insert("external_ids".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.external_ids));
+        row.insert("external_ip".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.external_ip));
+        row.insert("external_mac".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.external_mac));
+        row.insert("external_port_range".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.external_port_range));
+        row.insert("logical_ip".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.logical_ip));
+        row.insert("logical_port".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.logical_port));
+        row.insert("options".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.options));
+        row.insert("type".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.r#type));
+        row
+    }
+}
+
+/// A row of the "NB_Global" table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NbGlobal {
+    pub _uuid: ::ovsdb_common::datum::Uuid,
+    pub connections: ::std::collections::BTreeSet<::ovsdb_common::row::Ref<Connection>>,
+    pub external_ids: ::std::collections::BTreeMap<String, String>,
+    pub hv_cfg: i64,
+    pub hv_cfg_timestamp: i64,
+    pub ipsec: bool,
+    pub name: String,
+    pub nb_cfg: i64,
+    pub nb_cfg_timestamp: i64,
+    pub options: ::std::collections::BTreeMap<String, String>,
+    pub sb_cfg: i64,
+    pub sb_cfg_timestamp: i64,
+    pub ssl: ::std::option::Option<::ovsdb_common::row::Ref<Ssl>>,
+}
+
+impl ::ovsdb_common::row::TableRow for NbGlobal {
+    const TABLE: &'static str = "NB_Global";
+
+    fn uuid(&self) -> ::ovsdb_common::datum::Uuid {
+        self._uuid
+    }
+
+    fn from_row(
+        uuid: ::ovsdb_common::datum::Uuid,
+        row: &::ovsdb_common::row::Row,
+    ) -> ::std::result::Result<Self, ::ovsdb_common::row::ColumnError> {
+        Ok(NbGlobal {
+            _uuid: uuid,
+            connections: ::ovsdb_common::row::column(row, Self::TABLE, "connections")?,
+            external_ids: ::ovsdb_common::row::column(row, Self::TABLE, "external_ids")?,
+            hv_cfg: ::ovsdb_common::row::column(row, Self::TABLE, "hv_cfg")?,
+            hv_cfg_timestamp: ::ovsdb_common::row::column(row, Self::TABLE, "hv_cfg_timestamp")?,
+            ipsec: ::ovsdb_common::row::column(row, Self::TABLE, "ipsec")?,
+            name: ::ovsdb_common::row::column(row, Self::TABLE, "name")?,
+            nb_cfg: ::ovsdb_common::row::column(row, Self::TABLE, "nb_cfg")?,
+            nb_cfg_timestamp: ::ovsdb_common::row::column(row, Self::TABLE, "nb_cfg_timestamp")?,
+            options: ::ovsdb_common::row::column(row, Self::TABLE, "options")?,
+            sb_cfg: ::ovsdb_common::row::column(row, Self::TABLE, "sb_cfg")?,
+            sb_cfg_timestamp: ::ovsdb_common::row::column(row, Self::TABLE, "sb_cfg_timestamp")?,
+            ssl: ::ovsdb_common::row::column(row, Self::TABLE, "ssl")?,
+        })
+    }
+
+    fn to_row(&self) -> ::ovsdb_common::row::Row {
+        let mut row = ::ovsdb_common::row::Row::new();
+        row.insert("connections".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.connections));
+        row.insert("external_ids".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.external_ids));
+        row.insert("hv_cfg".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.hv_cfg));
+        row.insert("hv_cfg_timestamp".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.hv_cfg_timestamp));
+        row.insert("ipsec".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.ipsec));
+        row.insert("name".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.name));
+        row.insert("nb_cfg".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.nb_cfg));
+        row.insert("nb_cfg_timestamp".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.nb_cfg_timestamp));
+        row.insert("options".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.options));
+        row.insert("sb_cfg".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.sb_cfg));
+        row.insert("sb_cfg_timestamp".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.sb_cfg_timestamp));
+        row.insert("ssl".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.ssl));
+        row
+    }
+}
+
+/// A row of the "Port_Group" table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PortGroup {
+    pub _uuid: ::ovsdb_common::datum::Uuid,
+    pub acls: ::std::collections::BTreeSet<::ovsdb_common::row::Ref<Acl>>,
+    pub external_ids: ::std::collections::BTreeMap<String, String>,
+    pub name: String,
+    pub ports: ::std::collections::BTreeSet<::ovsdb_common::row::Ref<LogicalSwitchPort>>,
+}
+
+impl ::ovsdb_common::row::TableRow for PortGroup {
+    const TABLE: &'static str = "Port_Group";
+
+    fn uuid(&self) -> ::ovsdb_common::datum::Uuid {
+        self._uuid
+    }
+
+    fn from_row(
+        uuid: ::ovsdb_common::datum::Uuid,
+        row: &::ovsdb_common::row::Row,
+    ) -> ::std::result::Result<Self, ::ovsdb_common::row::ColumnError> {
+        Ok(PortGroup {
+            _uuid: uuid,
+            acls: ::ovsdb_common::row::column(row, Self::TABLE, "acls")?,
+            external_ids: ::ovsdb_common::row::column(row, Self::TABLE, "external_ids")?,
+            name: ::ovsdb_common::row::column(row, Self::TABLE, "name")?,
+            ports: ::ovsdb_common::row::column(row, Self::TABLE, "ports")?,
+        })
+    }
+
+    fn to_row(&self) -> ::ovsdb_common::row::Row {
+        let mut row = ::ovsdb_common::row::Row::new();
+        row.insert("acls".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.acls));
+        row.insert("external_ids".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.external_ids));
+        row.insert("name".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.name));
+        row.insert("ports".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.ports));
+        row
+    }
+}
+
+/// A row of the "QoS" table.
+///
+/// Not a root table: rows without strong references are garbage collected.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QoS {
+    pub _uuid: ::ovsdb_common::datum::Uuid,
+    pub action: ::std::collections::BTreeMap<QoSAction, i64>,
+    pub bandwidth: ::std::collections::BTreeMap<QoSBandwidth, i64>,
+    pub direction: QoSDirection,
+    pub external_ids: ::std::collections::BTreeMap<String, String>,
+    pub r#match: String,
+    pub priority: i64,
+}
+
+impl ::ovsdb_common::row::TableRow for QoS {
+    const TABLE: &'static str = "QoS";
+
+    fn uuid(&self) -> ::ovsdb_common::datum::Uuid {
+        self._uuid
+    }
+
+    fn from_row(
+        uuid: ::ovsdb_common::datum::Uuid,
+        row: &::ovsdb_common::row::Row,
+    ) -> ::std::result::Result<Self, ::ovsdb_common::row::ColumnError> {
+        Ok(QoS {
+            _uuid: uuid,
+            action: ::ovsdb_common::row::column(row, Self::TABLE, "action")?,
+            bandwidth: ::ovsdb_common::row::column(row, Self::TABLE, "bandwidth")?,
+            direction: ::ovsdb_common::row::column(row, Self::TABLE, "direction")?,
+            external_ids: ::ovsdb_common::row::column(row, Self::TABLE, "external_ids")?,
+            r#match: ::ovsdb_common::row::column(row, Self::TABLE, "match")?,
+            priority: ::ovsdb_common::row::column(row, Self::TABLE, "priority")?,
+        })
+    }
+
+    fn to_row(&self) -> ::ovsdb_common::row::Row {
+        let mut row = ::ovsdb_common::row::Row::new();
+        row.insert("action".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.action));
+        row.insert("bandwidth".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.bandwidth));
+        row.insert("direction".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.direction));
+        row.insert("external_ids".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.external_ids));
+        row.insert("match".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.r#match));
+        row.insert("priority".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.priority));
+        row
+    }
+}
+
+/// A row of the "SSL" table.
+///
+/// Not a root table: rows without strong references are garbage collected.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ssl {
+    pub _uuid: ::ovsdb_common::datum::Uuid,
+    pub bootstrap_ca_cert: bool,
+    pub ca_cert: String,
+    pub certificate: String,
+    pub external_ids: ::std::collections::BTreeMap<String, String>,
+    pub private_key: String,
+    pub ssl_ciphers: String,
+    pub ssl_protocols: String,
+}
+
+impl ::ovsdb_common::row::TableRow for Ssl {
+    const TABLE: &'static str = "SSL";
+
+    fn uuid(&self) -> ::ovsdb_common::datum::Uuid {
+        self._uuid
+    }
+
+    fn from_row(
+        uuid: ::ovsdb_common::datum::Uuid,
+        row: &::ovsdb_common::row::Row,
+    ) -> ::std::result::Result<Self, ::ovsdb_common::row::ColumnError> {
+        Ok(Ssl {
+            _uuid: uuid,
+            bootstrap_ca_cert: ::ovsdb_common::row::column(row, Self::TABLE, "bootstrap_ca_cert")?,
+            ca_cert: ::ovsdb_common::row::column(row, Self::TABLE, "ca_cert")?,
+            certificate: ::ovsdb_common::row::column(row, Self::TABLE, "certificate")?,
+            external_ids: ::ovsdb_common::row::column(row, Self::TABLE, "external_ids")?,
+            private_key: ::ovsdb_common::row::column(row, Self::TABLE, "private_key")?,
+            ssl_ciphers: ::ovsdb_common::row::column(row, Self::TABLE, "ssl_ciphers")?,
+            ssl_protocols: ::ovsdb_common::row::column(row, Self::TABLE, "ssl_protocols")?,
+        })
+    }
+
+    fn to_row(&self) -> ::ovsdb_common::row::Row {
+        let mut row = ::ovsdb_common::row::Row::new();
+        row.insert("bootstrap_ca_cert".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.bootstrap_ca_cert));
+        row.insert("ca_cert".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.ca_cert));
+        row.insert("certificate".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.certificate));
+        row.insert("external_ids".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.external_ids));
+        row.insert("private_key".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.private_key));
+        row.insert("ssl_ciphers".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.ssl_ciphers));
+        row.insert("ssl_protocols".to_string(), ::ovsdb_common::row::ToDatum::to_datum(&self.ssl_protocols));
+        row
+    }
+}
@@ -0,0 +1,37 @@
+use std::fs;
+use std::process::Command;
+
+fn schema_diff(old: &str, new: &str, json: bool) -> String {
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_cli"));
+    cmd.args(["schema-diff", "--old", old, "--new", new]);
+    if json {
+        cmd.arg("--json");
+    }
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn test_schema_diff() {
+    let path = std::env::temp_dir().join(format!("diff-{}.ovsschema", std::process::id()));
+    let edited = fs::read_to_string("tests/ovn-nb.ovsschema")
+        .unwrap()
+        .replace("\"BFD\": {", "\"BFD_Session\": {");
+    fs::write(&path, edited).unwrap();
+    let path = path.to_str().unwrap();
+
+    let human = schema_diff("tests/ovn-nb.ovsschema", path, false);
+    assert_eq!(
+        human,
+        "[breaking] table BFD removed\n\
+         [compatible] table BFD_Session added\n\
+         2 change(s), 1 breaking\n"
+    );
+
+    let json: serde_json::Value =
+        serde_json::from_str(&schema_diff("tests/ovn-nb.ovsschema", path, true)).unwrap();
+    fs::remove_file(path).unwrap();
+    assert_eq!(json["changes"][0]["change"], "table-removed");
+    assert_eq!(json["changes"][0]["compatibility"], "breaking");
+}
@@ -0,0 +1,80 @@
+use std::fs;
+use std::process::Command;
+
+fn validate(path: &str) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_cli"))
+        .args(["validate-schema", "-s", path])
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn test_valid_schema() {
+    let output = validate("tests/ovn-nb.ovsschema");
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "tests/ovn-nb.ovsschema: 0 error(s), 0 warning(s)\n"
+    );
+}
+
+#[test]
+fn test_invalid_schema() {
+    let path = std::env::temp_dir().join(format!("invalid-{}.ovsschema", std::process::id()));
+    let edited = fs::read_to_string("tests/ovn-nb.ovsschema")
+        .unwrap()
+        .replace(
+            "\"refTable\": \"Logical_Switch_Port\"",
+            "\"refTable\": \"LSP\"",
+        );
+    fs::write(&path, edited).unwrap();
+
+    let output = validate(path.to_str().unwrap());
+    fs::remove_file(&path).unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains(
+        "error: $.tables.Logical_Switch.columns.ports.type.key.refTable: \
+         refTable names unknown table LSP"
+    ));
+}
+
+#[test]
+fn test_missing_schema_file() {
+    let output = validate("tests/no-such.ovsschema");
+    assert_eq!(output.status.code(), Some(3));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.starts_with("error: tests/no-such.ovsschema: "),
+        "{}",
+        stderr
+    );
+    assert!(!stderr.contains("panicked"));
+}
+
+#[test]
+fn test_malformed_schema() {
+    let path = std::env::temp_dir().join(format!("malformed-{}.ovsschema", std::process::id()));
+    let cases = [
+        (
+            "{\"name\": \"db\",\n \"tables\": {",
+            4,
+            ":2:12: EOF while parsing an object",
+        ),
+        (
+            "{\"name\": \"db\",\n \"tables\": {\"T\": {}}}",
+            5,
+            ":2:19: $.tables.T: missing field `columns`",
+        ),
+    ];
+    for (text, code, diagnostic) in cases {
+        fs::write(&path, text).unwrap();
+        let output = validate(path.to_str().unwrap());
+        assert_eq!(output.status.code(), Some(code));
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            format!("error: {}{}\n", path.display(), diagnostic)
+        );
+    }
+    fs::remove_file(&path).unwrap();
+}
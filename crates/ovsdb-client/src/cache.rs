@@ -0,0 +1,826 @@
+use crate::error::{Error, Result};
+use crate::index::Index;
+use crate::monitor::{MonitorRequest, MonitorRequests, RowUpdate, Select, TableUpdates};
+use ovsdb_common::datum::Datum;
+use ovsdb_common::row::{ColumnError, Row, TableRow};
+use ovsdb_schema::integrity::Tables;
+use ovsdb_schema::schema::Table;
+use ovsdb_schema::Schema;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::ops::RangeBounds;
+use uuid::Uuid;
+
+/// How a row changed in the last batch applied to a [`Cache`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum RowChange {
+    Insert,
+    /// The names of the columns whose value changed.
+    Modify(BTreeSet<String>),
+    /// The row as it was before it was deleted.
+    Delete(Row),
+}
+
+/// A local replica of (the monitored part of) a database, kept up to date
+/// from monitor updates, like OVS's `ovsdb-idl`.
+///
+/// ```no_run
+/// # async fn example(client: ovsdb_client::Client) -> ovsdb_client::Result<()> {
+/// use futures::StreamExt;
+/// use ovsdb_client::{Cache, MonitorRequests};
+///
+/// let schema = client.get_schema("OVN_Northbound").await?;
+/// let mut requests = MonitorRequests::new();
+/// requests.insert("Logical_Switch".to_string(), Default::default());
+/// let mut monitor = client.monitor_cond("OVN_Northbound", &requests).await?;
+///
+/// let mut cache = Cache::for_requests(schema, &requests);
+/// cache.apply(monitor.initial())?;
+/// while let Some(updates) = monitor.next().await {
+///     cache.apply(&updates?)?;
+///     for (table, uuid, change) in cache.changes() {
+///         println!("{} {} {:?}", table, uuid, change);
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Cache {
+    schema: Schema,
+    tables: BTreeMap<String, TableCache>,
+}
+
+/// The cached rows of one table.
+///
+/// Every index the schema declares for the table is kept as a hash index;
+/// [`Cache::add_index`] adds ordered ones.
+#[derive(Debug, Default)]
+pub struct TableCache {
+    rows: HashMap<Uuid, Row>,
+    changes: BTreeMap<Uuid, RowChange>,
+    indexes: Vec<Index>,
+    /// The monitored columns, or `None` for all of them.
+    columns: Option<BTreeSet<String>>,
+    /// Whether the monitor reports every row of the table, rather than the
+    /// ones its conditions select.
+    complete: bool,
+}
+
+impl TableCache {
+    pub fn get(&self, uuid: &Uuid) -> Option<&Row> {
+        self.rows.get(uuid)
+    }
+
+    pub fn contains(&self, uuid: &Uuid) -> bool {
+        self.rows.contains_key(uuid)
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Iterates over all rows, in no particular order.
+    pub fn rows(&self) -> impl Iterator<Item = (&Uuid, &Row)> {
+        self.rows.iter()
+    }
+
+    /// The rows of this table that changed in the last batch.
+    pub fn changes(&self) -> impl Iterator<Item = (&Uuid, &RowChange)> {
+        self.changes.iter()
+    }
+
+    /// Looks up the rows whose `columns` equal `key`, one datum per column.
+    ///
+    /// `columns` must name the columns of an index of this table, in the
+    /// same order; otherwise this returns `None` rather than scanning.
+    ///
+    /// ```no_run
+    /// # fn example(cache: &ovsdb_client::Cache) -> Option<()> {
+    /// use ovsdb_common::datum::Datum;
+    ///
+    /// let switches = cache.table("Logical_Switch")?;
+    /// for (uuid, row) in switches.by_index(&["name"], &[Datum::from("sw0")])? {
+    ///     println!("{} {:?}", uuid, row);
+    /// }
+    /// # Some(())
+    /// # }
+    /// ```
+    pub fn by_index(
+        &self,
+        columns: &[&str],
+        key: &[Datum],
+    ) -> Option<impl Iterator<Item = (&Uuid, &Row)>> {
+        let index = self.find_index(columns, false)?;
+        Some(index.get(key).map(|uuid| (uuid, &self.rows[uuid])))
+    }
+
+    /// Looks up the rows whose key on `columns` falls in `range`, in key
+    /// order. Needs an ordered index, see [`Cache::add_index`].
+    pub fn by_range<R>(
+        &self,
+        columns: &[&str],
+        range: R,
+    ) -> Option<impl Iterator<Item = (&Uuid, &Row)>>
+    where
+        R: RangeBounds<Vec<Datum>>,
+    {
+        let index = self.find_index(columns, true)?;
+        Some(index.range(range).map(|uuid| (uuid, &self.rows[uuid])))
+    }
+
+    fn find_index(&self, columns: &[&str], ordered: bool) -> Option<&Index> {
+        self.indexes.iter().find(|index| {
+            (index.is_ordered() || !ordered) && index.columns().iter().eq(columns.iter())
+        })
+    }
+
+    fn insert(&mut self, uuid: Uuid, row: Row) {
+        if let Some(old) = self.rows.get(&uuid) {
+            for index in &mut self.indexes {
+                index.remove(&uuid, old);
+            }
+        }
+        for index in &mut self.indexes {
+            index.insert(&uuid, &row);
+        }
+        self.rows.insert(uuid, row);
+    }
+
+    /// Changes a row in place, keeping the indexes up to date.
+    fn update(&mut self, uuid: Uuid, f: impl FnOnce(&mut Row)) -> bool {
+        let Some(row) = self.rows.get_mut(&uuid) else {
+            return false;
+        };
+        for index in &mut self.indexes {
+            index.remove(&uuid, row);
+        }
+        f(row);
+        for index in &mut self.indexes {
+            index.insert(&uuid, row);
+        }
+        true
+    }
+
+    fn remove(&mut self, uuid: Uuid) -> Option<Row> {
+        let row = self.rows.remove(&uuid)?;
+        for index in &mut self.indexes {
+            index.remove(&uuid, &row);
+        }
+        Some(row)
+    }
+}
+
+impl Cache {
+    /// Creates an empty cache for every table of `schema`.
+    pub fn new(schema: Schema) -> Self {
+        let requests = schema
+            .tables
+            .keys()
+            .map(|name| (name.clone(), MonitorRequest::default()))
+            .collect();
+        Cache::for_requests(schema, &requests)
+    }
+
+    /// Creates an empty cache for the tables and columns that `requests`
+    /// monitor. The other tables of `schema` are left out.
+    pub fn for_requests(schema: Schema, requests: &MonitorRequests) -> Self {
+        let tables = requests
+            .iter()
+            .filter_map(|(name, request)| {
+                let indexes = schema
+                    .get_table(name)?
+                    .index()
+                    .into_iter()
+                    .flatten()
+                    .map(|columns| Index::hash(columns.clone()))
+                    .collect();
+                let cache = TableCache {
+                    indexes,
+                    columns: request
+                        .columns
+                        .as_ref()
+                        .map(|columns| columns.iter().cloned().collect()),
+                    complete: request.where_.as_ref().is_none_or(|w| w.is_empty())
+                        && request
+                            .select
+                            .is_none_or(|select| select == Select::default()),
+                    ..Default::default()
+                };
+                Some((name.clone(), cache))
+            })
+            .collect();
+        Cache { schema, tables }
+    }
+
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    pub fn table(&self, name: &str) -> Option<&TableCache> {
+        self.tables.get(name)
+    }
+
+    pub fn get(&self, table: &str, uuid: &Uuid) -> Option<&Row> {
+        self.tables.get(table)?.get(uuid)
+    }
+
+    /// Adds an ordered index on `columns` of `table`, which supports range
+    /// lookups through [`TableCache::by_range`] as well as exact ones.
+    pub fn add_index(&mut self, table: &str, columns: &[&str]) -> Result<()> {
+        let (Some(schema), Some(cache)) =
+            (self.schema.get_table(table), self.tables.get_mut(table))
+        else {
+            return Err(Error::InvalidIndex(format!("unknown table {}", table)));
+        };
+        if columns.is_empty() {
+            return Err(Error::InvalidIndex("index has no columns".to_string()));
+        }
+        if let Some(column) = columns
+            .iter()
+            .find(|column| **column != "_uuid" && !schema.columns.contains_key(**column))
+        {
+            return Err(Error::InvalidIndex(format!(
+                "unknown column {} in table {}",
+                column, table
+            )));
+        }
+        let mut index = Index::ordered(columns.iter().map(|c| c.to_string()).collect());
+        for (uuid, row) in &cache.rows {
+            index.insert(uuid, row);
+        }
+        cache.indexes.push(index);
+        Ok(())
+    }
+
+    /// Reads every row of `T`'s table as a `T`.
+    pub fn rows<T: TableRow>(
+        &self,
+    ) -> impl Iterator<Item = std::result::Result<T, ColumnError>> + '_ {
+        self.tables
+            .get(T::TABLE)
+            .into_iter()
+            .flat_map(|table| table.rows())
+            .map(|(uuid, row)| T::from_row(*uuid, row))
+    }
+
+    /// Reads one row of `T`'s table as a `T`.
+    pub fn row<T: TableRow>(&self, uuid: &Uuid) -> Option<std::result::Result<T, ColumnError>> {
+        self.get(T::TABLE, uuid).map(|row| T::from_row(*uuid, row))
+    }
+
+    /// Iterates over `(table, uuid, change)` for the last batch.
+    pub fn changes(&self) -> impl Iterator<Item = (&str, &Uuid, &RowChange)> {
+        self.tables.iter().flat_map(|(name, table)| {
+            table
+                .changes()
+                .map(move |(uuid, change)| (name.as_str(), uuid, change))
+        })
+    }
+
+    /// A copy of the cached rows, for trying out changes with
+    /// [`Integrity`](ovsdb_schema::Integrity) before committing them: it
+    /// tells whether the server would refuse them for a dangling strong
+    /// reference and which rows it would garbage collect.
+    ///
+    /// Only what is monitored is in the copy: the monitored columns of the
+    /// tables monitored without conditions. [`Integrity`] takes the rest to
+    /// be unknown, so it neither reports references to rows it cannot see
+    /// nor collects rows that it cannot see references to.
+    ///
+    /// [`Integrity`]: ovsdb_schema::Integrity
+    pub fn to_tables(&self) -> Tables {
+        self.tables
+            .iter()
+            .filter(|(_, table)| table.complete)
+            .map(|(name, table)| {
+                let rows = table
+                    .rows
+                    .iter()
+                    .map(|(uuid, row)| (*uuid, row.clone()))
+                    .collect();
+                (name.clone(), rows)
+            })
+            .collect()
+    }
+
+    /// Drops every row, e.g. before a full resynchronisation.
+    pub fn clear(&mut self) {
+        for table in self.tables.values_mut() {
+            table.rows.clear();
+            table.changes.clear();
+            for index in &mut table.indexes {
+                index.clear();
+            }
+        }
+    }
+
+    /// Applies one batch of updates. The changes of the previous batch are
+    /// forgotten first.
+    ///
+    /// Fails on updates that do not fit the cache, such as a modification
+    /// of an unknown row. The updates before the offending one stay applied.
+    pub fn apply(&mut self, updates: &TableUpdates) -> Result<()> {
+        for table in self.tables.values_mut() {
+            table.changes.clear();
+        }
+        for (name, uuid, update) in updates.iter() {
+            let (Some(schema), Some(table)) =
+                (self.schema.get_table(name), self.tables.get_mut(name))
+            else {
+                return Err(Error::InconsistentUpdate(format!("unknown table {}", name)));
+            };
+            apply_row(schema, table, name, *uuid, update)?;
+        }
+        Ok(())
+    }
+
+    /// Replaces the contents of the cache with `snapshot`, the full initial
+    /// contents of a new monitor, e.g. after reconnecting to a server that
+    /// could not resume from the last transaction.
+    ///
+    /// The changes of this batch are the difference between the old and the
+    /// new contents, so rows that did not change are not reported.
+    pub fn resync(&mut self, snapshot: &TableUpdates) -> Result<()> {
+        for (name, table) in &mut self.tables {
+            let schema = &self.schema.tables[name];
+            let mut old = std::mem::take(&mut table.rows);
+            table.changes.clear();
+            for index in &mut table.indexes {
+                index.clear();
+            }
+            for (uuid, update) in snapshot.tables.get(name).into_iter().flatten() {
+                let (RowUpdate::Initial(row) | RowUpdate::Insert(row)) = update else {
+                    return Err(Error::InconsistentUpdate(format!(
+                        "snapshot holds a change to row {} in table {}",
+                        uuid, name
+                    )));
+                };
+                let row = full_row(schema, table.columns.as_ref(), row);
+                let change = match old.remove(uuid) {
+                    None => Some(RowChange::Insert),
+                    Some(old) => {
+                        let changed: BTreeSet<String> = row
+                            .iter()
+                            .filter(|(column, value)| old.get(*column) != Some(value))
+                            .map(|(column, _)| column.clone())
+                            .collect();
+                        (!changed.is_empty()).then_some(RowChange::Modify(changed))
+                    }
+                };
+                if let Some(change) = change {
+                    table.changes.insert(*uuid, change);
+                }
+                table.insert(*uuid, row);
+            }
+            for (uuid, row) in old {
+                table.changes.insert(uuid, RowChange::Delete(row));
+            }
+        }
+        match snapshot
+            .tables
+            .keys()
+            .find(|name| !self.tables.contains_key(*name))
+        {
+            Some(name) => Err(Error::InconsistentUpdate(format!("unknown table {}", name))),
+            None => Ok(()),
+        }
+    }
+}
+
+/// `row` with the monitored columns it leaves out set to their default:
+/// update2 leaves out columns that have their default value.
+fn full_row(schema: &Table, columns: Option<&BTreeSet<String>>, row: &Row) -> Row {
+    let mut full: Row = schema
+        .iter_columns()
+        .filter(|(column, _)| columns.is_none_or(|columns| columns.contains(*column)))
+        .map(|(column, def)| (column.clone(), def.type_.default_datum()))
+        .collect();
+    full.extend(row.iter().map(|(k, v)| (k.clone(), v.clone())));
+    full
+}
+
+fn apply_row(
+    schema: &Table,
+    table: &mut TableCache,
+    name: &str,
+    uuid: Uuid,
+    update: &RowUpdate,
+) -> Result<()> {
+    let missing = || Error::InconsistentUpdate(format!("no row {} in table {}", uuid, name));
+    match update {
+        RowUpdate::Initial(row) | RowUpdate::Insert(row) => {
+            let row = full_row(schema, table.columns.as_ref(), row);
+            table.insert(uuid, row);
+            table.changes.insert(uuid, RowChange::Insert);
+        }
+        RowUpdate::Modify(diff) => {
+            let found = table.update(uuid, |row| {
+                for (column, diff) in diff {
+                    let new = match (schema.columns.get(column), row.get(column)) {
+                        (Some(def), Some(old)) => def.type_.apply_diff(old, diff),
+                        _ => diff.clone(),
+                    };
+                    row.insert(column.clone(), new);
+                }
+            });
+            if !found {
+                return Err(missing());
+            }
+            track_modify(table, uuid, diff.keys());
+        }
+        RowUpdate::Update { old, new } => {
+            let found = table.update(uuid, |row| {
+                row.extend(new.iter().map(|(k, v)| (k.clone(), v.clone())));
+            });
+            if !found {
+                return Err(missing());
+            }
+            track_modify(table, uuid, old.keys());
+        }
+        RowUpdate::Delete(_) => {
+            let row = table.remove(uuid).ok_or_else(missing)?;
+            // a row inserted and deleted within one batch never showed up
+            match table.changes.get(&uuid) {
+                Some(RowChange::Insert) => {
+                    table.changes.remove(&uuid);
+                }
+                _ => {
+                    table.changes.insert(uuid, RowChange::Delete(row));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn track_modify<'a>(table: &mut TableCache, uuid: Uuid, columns: impl Iterator<Item = &'a String>) {
+    match table
+        .changes
+        .entry(uuid)
+        .or_insert_with(|| RowChange::Modify(BTreeSet::new()))
+    {
+        RowChange::Modify(changed) => changed.extend(columns.cloned()),
+        // still an insert, as far as the caller is concerned
+        RowChange::Insert | RowChange::Delete(_) => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cache::*;
+    use ovsdb_common::condition::Condition;
+    use ovsdb_common::datum::Datum;
+    use ovsdb_schema::Cleanup;
+    use serde_json::json;
+
+    fn schema() -> Schema {
+        serde_json::from_value(json!({
+            "name": "Test",
+            "tables": {
+                "Logical_Switch": {
+                    "columns": {
+                        "name": {"type": "string"},
+                        "ports": {"type": {"key": "uuid", "min": 0, "max": "unlimited"}},
+                        "other_config": {"type": {"key": "string", "value": "string",
+                                                  "min": 0, "max": "unlimited"}}
+                    },
+                    "indexes": [["name"]]
+                }
+            }
+        }))
+        .unwrap()
+    }
+
+    fn batch(rows: Vec<(u128, RowUpdate)>) -> TableUpdates {
+        let rows = rows
+            .into_iter()
+            .map(|(uuid, update)| (Uuid::from_u128(uuid), update))
+            .collect();
+        TableUpdates {
+            last_txn_id: None,
+            tables: BTreeMap::from([("Logical_Switch".to_string(), rows)]),
+        }
+    }
+
+    fn row(columns: Vec<(&str, Datum)>) -> Row {
+        columns
+            .into_iter()
+            .map(|(column, datum)| (column.to_string(), datum))
+            .collect()
+    }
+
+    #[test]
+    fn test_insert_fills_defaults() {
+        let mut cache = Cache::new(schema());
+        let update = RowUpdate::Initial(row(vec![("name", Datum::from("sw0"))]));
+        cache.apply(&batch(vec![(1, update)])).unwrap();
+
+        let sw0 = cache.get("Logical_Switch", &Uuid::from_u128(1)).unwrap();
+        assert_eq!(sw0["name"], Datum::from("sw0"));
+        assert_eq!(sw0["ports"], Datum::empty_set());
+        assert_eq!(sw0["other_config"], Datum::empty_map());
+        assert_eq!(
+            cache.changes().collect::<Vec<_>>(),
+            vec![("Logical_Switch", &Uuid::from_u128(1), &RowChange::Insert)]
+        );
+    }
+
+    #[test]
+    fn test_modify_and_delete() {
+        let mut cache = Cache::new(schema());
+        let initial = row(vec![
+            ("name", Datum::from("sw0")),
+            ("ports", Datum::set([Uuid::from_u128(10)])),
+        ]);
+        cache
+            .apply(&batch(vec![(1, RowUpdate::Initial(initial))]))
+            .unwrap();
+
+        let diff = row(vec![
+            (
+                "ports",
+                Datum::set([Uuid::from_u128(10), Uuid::from_u128(11)]),
+            ),
+            ("other_config", Datum::map([("k", "v")])),
+        ]);
+        cache
+            .apply(&batch(vec![(1, RowUpdate::Modify(diff))]))
+            .unwrap();
+        let table = cache.table("Logical_Switch").unwrap();
+        let sw0 = table.get(&Uuid::from_u128(1)).unwrap();
+        assert_eq!(sw0["ports"], Datum::from(Uuid::from_u128(11)));
+        assert_eq!(sw0["other_config"], Datum::map([("k", "v")]));
+        assert_eq!(
+            table.changes().collect::<Vec<_>>(),
+            vec![(
+                &Uuid::from_u128(1),
+                &RowChange::Modify(BTreeSet::from([
+                    "other_config".to_string(),
+                    "ports".to_string()
+                ]))
+            )]
+        );
+
+        cache
+            .apply(&batch(vec![(1, RowUpdate::Delete(None))]))
+            .unwrap();
+        assert!(cache.table("Logical_Switch").unwrap().is_empty());
+        let changes: Vec<_> = cache.changes().collect();
+        match changes[0].2 {
+            RowChange::Delete(old) => assert_eq!(old["name"], Datum::from("sw0")),
+            other => panic!("Expected RowChange::Delete, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_rfc7047_update() {
+        let mut cache = Cache::new(schema());
+        let initial = row(vec![("name", Datum::from("sw0"))]);
+        cache
+            .apply(&batch(vec![(1, RowUpdate::Initial(initial))]))
+            .unwrap();
+        let update = RowUpdate::Update {
+            old: row(vec![("name", Datum::from("sw0"))]),
+            new: row(vec![("name", Datum::from("sw1"))]),
+        };
+        cache.apply(&batch(vec![(1, update)])).unwrap();
+        assert_eq!(
+            cache.get("Logical_Switch", &Uuid::from_u128(1)).unwrap()["name"],
+            Datum::from("sw1")
+        );
+    }
+
+    #[test]
+    fn test_inconsistent_updates() {
+        let mut cache = Cache::new(schema());
+        let modify = RowUpdate::Modify(row(vec![("name", Datum::from("x"))]));
+        assert!(matches!(
+            cache.apply(&batch(vec![(1, modify)])),
+            Err(Error::InconsistentUpdate(_))
+        ));
+        let mut updates = batch(vec![]);
+        updates.tables.insert("Nope".to_string(), BTreeMap::new());
+        updates
+            .tables
+            .get_mut("Nope")
+            .unwrap()
+            .insert(Uuid::from_u128(1), RowUpdate::Delete(None));
+        assert!(cache.apply(&updates).is_err());
+    }
+
+    #[test]
+    fn test_resync() {
+        let mut cache = Cache::new(schema());
+        let sw = |name: &str| RowUpdate::Initial(row(vec![("name", Datum::from(name))]));
+        cache
+            .apply(&batch(vec![(1, sw("sw0")), (2, sw("sw1")), (3, sw("sw2"))]))
+            .unwrap();
+
+        // while disconnected: sw0 renamed, sw1 deleted, sw3 added
+        cache
+            .resync(&batch(vec![(1, sw("sw9")), (3, sw("sw2")), (4, sw("sw3"))]))
+            .unwrap();
+        let changes: BTreeMap<_, _> = cache
+            .changes()
+            .map(|(_, uuid, change)| (uuid.as_u128(), change.clone()))
+            .collect();
+        assert_eq!(changes.len(), 3);
+        assert_eq!(
+            changes[&1],
+            RowChange::Modify(BTreeSet::from(["name".to_string()]))
+        );
+        assert!(
+            matches!(&changes[&2], RowChange::Delete(old) if old["name"] == Datum::from("sw1"))
+        );
+        assert_eq!(changes[&4], RowChange::Insert);
+
+        let table = cache.table("Logical_Switch").unwrap();
+        assert_eq!(table.len(), 3);
+        assert!(names(table.by_index(&["name"], &[Datum::from("sw0")])).is_empty());
+        assert_eq!(
+            names(table.by_index(&["name"], &[Datum::from("sw9")])),
+            vec![Datum::from("sw9")]
+        );
+    }
+
+    fn names<'a>(rows: Option<impl Iterator<Item = (&'a Uuid, &'a Row)>>) -> Vec<Datum> {
+        rows.unwrap().map(|(_, row)| row["name"].clone()).collect()
+    }
+
+    fn switch_schema() -> Schema {
+        serde_json::from_value(json!({
+            "name": "Test",
+            "tables": {
+                "Logical_Switch": {
+                    "columns": {
+                        "name": {"type": "string"},
+                        "ports": {"type": {"key": {"type": "uuid",
+                                                   "refTable": "Logical_Switch_Port"},
+                                           "min": 0, "max": "unlimited"}}
+                    },
+                    "isRoot": true
+                },
+                "Logical_Switch_Port": {"columns": {}, "isRoot": false}
+            }
+        }))
+        .unwrap()
+    }
+
+    fn switch_and_port(sw0: Uuid, lsp0: Uuid) -> TableUpdates {
+        TableUpdates {
+            last_txn_id: None,
+            tables: BTreeMap::from([
+                (
+                    "Logical_Switch".to_string(),
+                    BTreeMap::from([(
+                        sw0,
+                        RowUpdate::Initial(row(vec![("ports", Datum::set([lsp0]))])),
+                    )]),
+                ),
+                (
+                    "Logical_Switch_Port".to_string(),
+                    BTreeMap::from([(lsp0, RowUpdate::Initial(Row::new()))]),
+                ),
+            ]),
+        }
+    }
+
+    #[test]
+    fn test_pre_commit_integrity() {
+        let mut cache = Cache::new(switch_schema());
+        let (sw0, lsp0) = (Uuid::from_u128(1), Uuid::from_u128(2));
+        cache.apply(&switch_and_port(sw0, lsp0)).unwrap();
+        let integrity = ovsdb_schema::Integrity::new(cache.schema());
+
+        // deleting the port while the switch still refers to it
+        let mut tables = cache.to_tables();
+        tables.get_mut("Logical_Switch_Port").unwrap().remove(&lsp0);
+        assert!(integrity.check_references(&tables).is_err());
+
+        // emptying the switch's ports collects the port
+        let mut tables = cache.to_tables();
+        tables
+            .get_mut("Logical_Switch")
+            .unwrap()
+            .get_mut(&sw0)
+            .unwrap()
+            .insert("ports".to_string(), Datum::empty_set());
+        let cleanup = integrity.enforce(&mut tables).unwrap();
+        assert_eq!(
+            cleanup.collected,
+            vec![("Logical_Switch_Port".to_string(), lsp0)]
+        );
+        // the cache itself is untouched
+        assert!(cache.get("Logical_Switch_Port", &lsp0).is_some());
+    }
+
+    #[test]
+    fn test_partial_monitor_integrity() {
+        let (sw0, lsp0) = (Uuid::from_u128(1), Uuid::from_u128(2));
+        let mut updates = switch_and_port(sw0, lsp0);
+        updates.tables.remove("Logical_Switch");
+
+        // ports only: the switches that refer to them are not seen
+        let mut requests = MonitorRequests::new();
+        requests.insert("Logical_Switch_Port".to_string(), Default::default());
+        let mut cache = Cache::for_requests(switch_schema(), &requests);
+        assert!(cache.table("Logical_Switch").is_none());
+        cache.apply(&updates).unwrap();
+        let integrity = ovsdb_schema::Integrity::new(cache.schema());
+        let mut tables = cache.to_tables();
+        assert_eq!(
+            tables.keys().collect::<Vec<_>>(),
+            vec!["Logical_Switch_Port"]
+        );
+        assert_eq!(integrity.enforce(&mut tables), Ok(Cleanup::default()));
+
+        // switches without their ports column
+        let name_only = MonitorRequest {
+            columns: Some(vec!["name".to_string()]),
+            ..Default::default()
+        };
+        requests.insert("Logical_Switch".to_string(), name_only);
+        let mut cache = Cache::for_requests(switch_schema(), &requests);
+        let name = row(vec![("name", Datum::from("sw0"))]);
+        updates.tables.insert(
+            "Logical_Switch".to_string(),
+            BTreeMap::from([(sw0, RowUpdate::Initial(name))]),
+        );
+        cache.apply(&updates).unwrap();
+        let sw = cache.get("Logical_Switch", &sw0).unwrap();
+        assert_eq!(sw.keys().collect::<Vec<_>>(), vec!["name"]);
+        let mut tables = cache.to_tables();
+        assert_eq!(tables.len(), 2);
+        assert_eq!(integrity.enforce(&mut tables), Ok(Cleanup::default()));
+
+        // ports under a condition: the copy leaves them out
+        let some_ports = MonitorRequest {
+            where_: Some(vec![Condition::Constant(false)]),
+            ..Default::default()
+        };
+        requests.insert("Logical_Switch_Port".to_string(), some_ports);
+        let cache = Cache::for_requests(switch_schema(), &requests);
+        assert_eq!(
+            cache.to_tables().keys().collect::<Vec<_>>(),
+            vec!["Logical_Switch"]
+        );
+    }
+
+    #[test]
+    fn test_schema_index() {
+        let mut cache = Cache::new(schema());
+        let sw = |name: &str| RowUpdate::Initial(row(vec![("name", Datum::from(name))]));
+        cache
+            .apply(&batch(vec![(1, sw("sw0")), (2, sw("sw1"))]))
+            .unwrap();
+        let table = cache.table("Logical_Switch").unwrap();
+        assert_eq!(
+            names(table.by_index(&["name"], &[Datum::from("sw1")])),
+            vec![Datum::from("sw1")]
+        );
+        assert!(table.by_index(&["ports"], &[Datum::empty_set()]).is_none());
+        assert!(table.by_range(&["name"], ..).is_none());
+
+        let rename = RowUpdate::Modify(row(vec![("name", Datum::from("sw2"))]));
+        cache
+            .apply(&batch(vec![(2, rename), (1, RowUpdate::Delete(None))]))
+            .unwrap();
+        let table = cache.table("Logical_Switch").unwrap();
+        assert!(names(table.by_index(&["name"], &[Datum::from("sw0")])).is_empty());
+        assert!(names(table.by_index(&["name"], &[Datum::from("sw1")])).is_empty());
+        assert_eq!(
+            names(table.by_index(&["name"], &[Datum::from("sw2")])),
+            vec![Datum::from("sw2")]
+        );
+    }
+
+    #[test]
+    fn test_ordered_index() {
+        let mut cache = Cache::new(schema());
+        let sw = |name: &str| RowUpdate::Initial(row(vec![("name", Datum::from(name))]));
+        cache
+            .apply(&batch(vec![(1, sw("c")), (2, sw("a")), (3, sw("b"))]))
+            .unwrap();
+        cache.add_index("Logical_Switch", &["name"]).unwrap();
+        cache.apply(&batch(vec![(4, sw("d"))])).unwrap();
+
+        let table = cache.table("Logical_Switch").unwrap();
+        let range = vec![Datum::from("b")]..vec![Datum::from("d")];
+        assert_eq!(
+            names(table.by_range(&["name"], range)),
+            vec![Datum::from("b"), Datum::from("c")]
+        );
+        assert_eq!(names(table.by_range(&["name"], ..)).len(), 4);
+
+        assert!(matches!(
+            cache.add_index("Logical_Switch", &["nope"]),
+            Err(Error::InvalidIndex(_))
+        ));
+        assert!(cache.add_index("Nope", &["name"]).is_err());
+        assert!(cache.add_index("Logical_Switch", &[]).is_err());
+    }
+}
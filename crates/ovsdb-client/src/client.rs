@@ -0,0 +1,597 @@
+use crate::codec::JsonCodec;
+use crate::error::{Error, Result};
+use crate::lock::{self, Lock, Locks};
+use crate::monitor::{self, Monitor, MonitorKind, MonitorRequests, Monitors};
+use crate::remote::Remote;
+use crate::ssl::SslOptions;
+use futures::future::OptionFuture;
+use futures::{SinkExt, StreamExt};
+use ovsdb_common::jsonrpc::{Message, Notification, Request, Response};
+use ovsdb_schema::Schema;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::sync::{mpsc, oneshot, watch};
+use tokio::time::Instant;
+use tokio_util::codec::Framed;
+use uuid::Uuid;
+
+/// Unsolicited messages from the server. Updates for monitors and lock
+/// notifications for locks requested through [`Client`] go to their
+/// [`Monitor`] or [`Lock`] instead.
+pub type Notifications = mpsc::UnboundedReceiver<Notification>;
+
+type Pending = Arc<Mutex<HashMap<u64, oneshot::Sender<Response>>>>;
+
+/// Why the connection was closed, if reading from it failed.
+type ReadFailure = Arc<OnceLock<Arc<Error>>>;
+
+/// The inactivity probe of a connection, like OVS's `inactivity_probe`:
+/// after `idle` without hearing from the server, the client sends an
+/// `echo`, and if nothing at all arrives within `timeout` of that, it
+/// declares the connection dead and closes it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Probe {
+    pub idle: Duration,
+    pub timeout: Duration,
+}
+
+impl Default for Probe {
+    /// The default `inactivity_probe` of OVS: 5 s, for both.
+    fn default() -> Self {
+        Probe {
+            idle: Duration::from_secs(5),
+            timeout: Duration::from_secs(5),
+        }
+    }
+}
+
+/// An OVSDB JSON-RPC session.
+///
+/// The client is cheap to clone; all clones share one connection. The
+/// connection is closed once every clone has been dropped.
+#[derive(Debug, Clone)]
+pub struct Client {
+    shared: Arc<Shared>,
+}
+
+#[derive(Debug)]
+struct Shared {
+    outgoing: mpsc::UnboundedSender<Message>,
+    pending: Pending,
+    monitors: Monitors,
+    locks: Locks,
+    probe: watch::Sender<Option<Probe>>,
+    read_failure: ReadFailure,
+    next_id: AtomicU64,
+}
+
+impl Client {
+    /// Connects to a remote such as `tcp:127.0.0.1:6641` or `unix:/run/ovn/ovnnb_db.sock`.
+    pub async fn connect(remote: &str) -> Result<(Client, Notifications)> {
+        Client::connect_with(remote, None).await
+    }
+
+    /// Connects like [`Client::connect`], also accepting `ssl:` remotes
+    /// when given the client's key and certificates.
+    pub async fn connect_with(
+        remote: &str,
+        ssl: Option<&SslOptions>,
+    ) -> Result<(Client, Notifications)> {
+        let remote: Remote = remote.parse()?;
+        let stream = remote.connect_with(ssl).await?;
+        Ok(Client::from_stream(stream))
+    }
+
+    /// Runs a session over an already established stream.
+    ///
+    /// Must be called from within a tokio runtime, as the session is driven
+    /// by a spawned task.
+    pub fn from_stream<S>(stream: S) -> (Client, Notifications)
+    where
+        S: AsyncRead + AsyncWrite + Send + 'static,
+    {
+        let (outgoing_tx, outgoing_rx) = mpsc::unbounded_channel();
+        let (notify_tx, notify_rx) = mpsc::unbounded_channel();
+        let pending = Pending::default();
+        let monitors = Monitors::default();
+        let locks = Locks::default();
+        let (probe_tx, probe_rx) = watch::channel(None);
+        let read_failure = ReadFailure::default();
+        tokio::spawn({
+            let (pending, monitors, locks, read_failure) = (
+                pending.clone(),
+                monitors.clone(),
+                locks.clone(),
+                read_failure.clone(),
+            );
+            async move {
+                let mut outgoing = outgoing_rx;
+                let framed = Framed::new(stream, JsonCodec::default());
+                let result = run_session(
+                    framed,
+                    &mut outgoing,
+                    &pending,
+                    &monitors,
+                    &locks,
+                    probe_rx,
+                    notify_tx,
+                )
+                .await;
+                if let Err(error) = result {
+                    let _ = read_failure.set(Arc::new(error));
+                }
+                close_session(outgoing, &pending, &monitors, &locks, read_failure.get());
+            }
+        });
+        let client = Client {
+            shared: Arc::new(Shared {
+                outgoing: outgoing_tx,
+                pending,
+                monitors,
+                locks,
+                probe: probe_tx,
+                read_failure,
+                next_id: AtomicU64::new(0),
+            }),
+        };
+        (client, notify_rx)
+    }
+
+    pub(crate) fn next_id(&self) -> u64 {
+        self.shared.next_id.fetch_add(1, Ordering::Relaxed)
+    }
+
+    pub(crate) fn monitors(&self) -> &Monitors {
+        &self.shared.monitors
+    }
+
+    pub(crate) fn locks(&self) -> &Locks {
+        &self.shared.locks
+    }
+
+    /// The error for a request on a closed connection: why reading from it
+    /// failed, if it did.
+    pub(crate) fn closed_error(&self) -> Error {
+        match self.shared.read_failure.get() {
+            Some(error) => Error::ReadFailed(error.clone()),
+            None => Error::Disconnected,
+        }
+    }
+
+    /// Sets the inactivity probe, or turns it off with `None`, which is the
+    /// default. `echo` requests from the server are answered either way.
+    pub fn set_probe(&self, probe: Option<Probe>) {
+        self.shared.probe.send_replace(probe);
+    }
+
+    /// Sends a request and waits for the matching response.
+    pub async fn request(&self, method: &str, params: Value) -> Result<Value> {
+        self.request_with_id(self.next_id(), method, params).await
+    }
+
+    /// Sends a request with an id taken from `next_id`, and waits for the
+    /// matching response.
+    pub(crate) async fn request_with_id(
+        &self,
+        id: u64,
+        method: &str,
+        params: Value,
+    ) -> Result<Value> {
+        let (tx, rx) = oneshot::channel();
+        self.shared.pending.lock().unwrap().insert(id, tx);
+
+        let request = Request::new(method, params, json!(id));
+        if self.shared.outgoing.send(request.into()).is_err() {
+            self.shared.pending.lock().unwrap().remove(&id);
+            return Err(self.closed_error());
+        }
+
+        let response = rx.await.map_err(|_| self.closed_error())?;
+        match response.error {
+            Some(error) => Err(Error::Rpc(error)),
+            None => Ok(response.result),
+        }
+    }
+
+    /// Sends a request without waiting for the response, which is dropped
+    /// when it arrives. For cleanup that cannot wait, such as in `Drop`.
+    pub(crate) fn request_detached(&self, method: &str, params: Value) {
+        let request = Request::new(method, params, json!(self.next_id()));
+        let _ = self.shared.outgoing.send(request.into());
+    }
+
+    /// Sends a notification; the server does not reply.
+    pub fn notify(&self, method: &str, params: Value) -> Result<()> {
+        self.shared
+            .outgoing
+            .send(Notification::new(method, params).into())
+            .map_err(|_| self.closed_error())
+    }
+
+    /// Returns true once the underlying connection has gone away.
+    pub fn is_closed(&self) -> bool {
+        self.shared.outgoing.is_closed()
+    }
+
+    // https://tools.ietf.org/html/rfc7047#section-4.1.1
+    pub async fn list_dbs(&self) -> Result<Vec<String>> {
+        let result = self.request("list_dbs", json!([])).await?;
+        Ok(serde_json::from_value(result)?)
+    }
+
+    // https://tools.ietf.org/html/rfc7047#section-4.1.2
+    pub async fn get_schema(&self, db: &str) -> Result<Schema> {
+        let result = self.request("get_schema", json!([db])).await?;
+        Ok(serde_json::from_value(result)?)
+    }
+
+    // https://tools.ietf.org/html/rfc7047#section-4.1.3
+    pub async fn transact(&self, db: &str, operations: Vec<Value>) -> Result<Vec<Value>> {
+        let mut params = vec![json!(db)];
+        params.extend(operations);
+        let result = self.request("transact", Value::Array(params)).await?;
+        match result {
+            Value::Array(results) => Ok(results),
+            other => Err(Error::UnexpectedResponse(other.to_string())),
+        }
+    }
+
+    // https://tools.ietf.org/html/rfc7047#section-4.1.5
+    pub async fn monitor(&self, db: &str, requests: &MonitorRequests) -> Result<Monitor> {
+        Monitor::start(self, MonitorKind::Monitor, db, requests, None).await
+    }
+
+    // https://docs.openvswitch.org/en/latest/ref/ovsdb-server.7/#monitor-cond
+    pub async fn monitor_cond(&self, db: &str, requests: &MonitorRequests) -> Result<Monitor> {
+        Monitor::start(self, MonitorKind::Cond, db, requests, None).await
+    }
+
+    /// Like [`Client::monitor_cond`], but the server only sends the changes
+    /// after `last_txn_id` if it still has them; see [`Monitor::found`].
+    // https://docs.openvswitch.org/en/latest/ref/ovsdb-server.7/#monitor-cond-since
+    pub async fn monitor_cond_since(
+        &self,
+        db: &str,
+        requests: &MonitorRequests,
+        last_txn_id: Option<Uuid>,
+    ) -> Result<Monitor> {
+        Monitor::start(self, MonitorKind::CondSince, db, requests, last_txn_id).await
+    }
+
+    /// Asks for the lock `name`. The returned [`Lock`] may not be held yet;
+    /// see [`Lock::acquired`].
+    // https://tools.ietf.org/html/rfc7047#section-4.1.8
+    pub async fn lock(&self, name: &str) -> Result<Lock> {
+        Lock::request(self, "lock", name).await
+    }
+
+    /// Takes the lock `name` away from its current owner, if any.
+    // https://tools.ietf.org/html/rfc7047#section-4.1.9
+    pub async fn steal(&self, name: &str) -> Result<Lock> {
+        Lock::request(self, "steal", name).await
+    }
+
+    // https://tools.ietf.org/html/rfc7047#section-4.1.11
+    pub async fn echo(&self, params: Value) -> Result<Value> {
+        self.request("echo", params).await
+    }
+}
+
+/// Runs the connection until it closes, which is an error if reading from
+/// it failed.
+async fn run_session<S>(
+    framed: Framed<S, JsonCodec>,
+    outgoing: &mut mpsc::UnboundedReceiver<Message>,
+    pending: &Pending,
+    monitors: &Monitors,
+    locks: &Locks,
+    mut probe: watch::Receiver<Option<Probe>>,
+    notifications: mpsc::UnboundedSender<Notification>,
+) -> Result<()>
+where
+    S: AsyncRead + AsyncWrite,
+{
+    let (mut sink, mut stream) = framed.split();
+    let mut last_received = Instant::now();
+    // whether our echo is out, waiting for the server to show signs of life
+    let mut probing = false;
+    loop {
+        let deadline = probe.borrow().map(|probe| match probing {
+            false => last_received + probe.idle,
+            true => last_received + probe.idle + probe.timeout,
+        });
+        let expired = OptionFuture::from(deadline.map(tokio::time::sleep_until));
+        tokio::select! {
+            Some(()) = expired => {
+                if probing {
+                    break;
+                }
+                // as OVS does, with an id that no request of ours uses
+                let echo = Request::new("echo", json!([]), json!("echo"));
+                if sink.send(echo.into()).await.is_err() {
+                    break;
+                }
+                probing = true;
+            }
+            // the next loop picks up the new deadline
+            Ok(()) = probe.changed() => {}
+            message = outgoing.recv() => match message {
+                Some(message) => {
+                    if sink.send(message).await.is_err() {
+                        break;
+                    }
+                }
+                // every Client handle has been dropped
+                None => break,
+            },
+            message = stream.next() => {
+                last_received = Instant::now();
+                probing = false;
+                match message {
+                    Some(Ok(Message::Response(response))) => {
+                        lock::reply(locks, &response);
+                        let waiter = response
+                            .id
+                            .as_u64()
+                            .and_then(|id| pending.lock().unwrap().remove(&id));
+                        if let Some(waiter) = waiter {
+                            let _ = waiter.send(response);
+                        }
+                    }
+                    Some(Ok(Message::Notification(notification))) => {
+                        let unclaimed = monitor::dispatch(monitors, notification)
+                            .and_then(|notification| lock::dispatch(locks, notification));
+                        if let Some(notification) = unclaimed {
+                            let _ = notifications.send(notification);
+                        }
+                    }
+                    // the server's own inactivity probe
+                    // https://tools.ietf.org/html/rfc7047#section-4.1.11
+                    Some(Ok(Message::Request(request))) => {
+                        let response = match request.method.as_str() {
+                            "echo" => Response::ok(request.id, request.params),
+                            _ => Response::err(
+                                request.id,
+                                ovsdb_common::error::Error::new(
+                                    "unknown method",
+                                    Some(request.method),
+                                ),
+                            ),
+                        };
+                        if sink.send(response.into()).await.is_err() {
+                            break;
+                        }
+                    }
+                    Some(Err(error)) => return Err(error),
+                    None => break,
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn close_session(
+    mut outgoing: mpsc::UnboundedReceiver<Message>,
+    pending: &Pending,
+    monitors: &Monitors,
+    locks: &Locks,
+    read_failure: Option<&Arc<Error>>,
+) {
+    // refuse new requests first, then fail the outstanding ones with
+    // Error::Disconnected, or Error::ReadFailed, by dropping their waiters
+    outgoing.close();
+    pending.lock().unwrap().clear();
+    // ends the monitor and lock streams; the server drops our locks too
+    let mut monitors = monitors.lock().unwrap();
+    if let Some(error) = read_failure {
+        // the message that could not be read may have been an update
+        for monitor in monitors.values() {
+            let _ = monitor.send(Err(Error::ReadFailed(error.clone())));
+        }
+    }
+    monitors.clear();
+    let mut locks = locks.lock().unwrap();
+    locks.values().for_each(lock::LockState::release);
+    locks.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::*;
+    use tokio::io::AsyncWriteExt;
+    use tokio::net::TcpListener;
+
+    type Server = Framed<tokio::io::DuplexStream, JsonCodec>;
+
+    fn pair() -> (Client, Notifications, Server) {
+        let (client_side, server_side) = tokio::io::duplex(4096);
+        let (client, notifications) = Client::from_stream(client_side);
+        (
+            client,
+            notifications,
+            Framed::new(server_side, JsonCodec::default()),
+        )
+    }
+
+    async fn next_request(server: &mut Server) -> Request {
+        match server.next().await.unwrap().unwrap() {
+            Message::Request(request) => request,
+            other => panic!("Expected Message::Request, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_responses_matched_by_id() {
+        let (client, _notifications, mut server) = pair();
+        let first = tokio::spawn({
+            let client = client.clone();
+            async move { client.echo(json!(["first"])).await }
+        });
+        let a = next_request(&mut server).await;
+        let second = tokio::spawn({
+            let client = client.clone();
+            async move { client.echo(json!(["second"])).await }
+        });
+        let b = next_request(&mut server).await;
+
+        // answer out of order
+        server
+            .send(Response::ok(b.id, b.params).into())
+            .await
+            .unwrap();
+        server
+            .send(Response::ok(a.id, a.params).into())
+            .await
+            .unwrap();
+
+        assert_eq!(first.await.unwrap().unwrap(), json!(["first"]));
+        assert_eq!(second.await.unwrap().unwrap(), json!(["second"]));
+    }
+
+    #[tokio::test]
+    async fn test_error_response() {
+        let (client, _notifications, mut server) = pair();
+        let call = tokio::spawn(async move { client.get_schema("Foo").await });
+        let request = next_request(&mut server).await;
+        assert_eq!(request.method, "get_schema");
+        assert_eq!(request.params, json!(["Foo"]));
+        let error = ovsdb_common::error::Error::new("unknown database", None);
+        server
+            .send(Response::err(request.id, error).into())
+            .await
+            .unwrap();
+        match call.await.unwrap() {
+            Err(Error::Rpc(e)) => assert_eq!(e.error(), "unknown database"),
+            other => panic!("Expected Error::Rpc, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_notifications_routed_separately() {
+        let (client, mut notifications, mut server) = pair();
+        let call = tokio::spawn(async move { client.list_dbs().await });
+        let request = next_request(&mut server).await;
+        server
+            .send(Notification::new("locked", json!(["lock0"])).into())
+            .await
+            .unwrap();
+        server
+            .send(Response::ok(request.id, json!(["OVN_Northbound"])).into())
+            .await
+            .unwrap();
+
+        assert_eq!(call.await.unwrap().unwrap(), vec!["OVN_Northbound"]);
+        let notification = notifications.recv().await.unwrap();
+        assert_eq!(notification.method, "locked");
+        assert_eq!(notification.params, json!(["lock0"]));
+    }
+
+    #[tokio::test]
+    async fn test_disconnect_fails_pending_requests() {
+        let (client, _notifications, mut server) = pair();
+        let call = tokio::spawn({
+            let client = client.clone();
+            async move { client.list_dbs().await }
+        });
+        next_request(&mut server).await;
+        drop(server);
+        assert!(matches!(call.await.unwrap(), Err(Error::Disconnected)));
+        assert!(matches!(client.list_dbs().await, Err(Error::Disconnected)));
+    }
+
+    #[tokio::test]
+    async fn test_read_failure_fails_pending_requests() {
+        let (client, _notifications, mut server) = pair();
+        let call = tokio::spawn({
+            let client = client.clone();
+            async move { client.list_dbs().await }
+        });
+        next_request(&mut server).await;
+        server.get_mut().write_all(b"[1]").await.unwrap();
+        for result in [call.await.unwrap(), client.list_dbs().await] {
+            let Err(Error::ReadFailed(error)) = result else {
+                panic!("Expected Error::ReadFailed, got {:?}", result);
+            };
+            assert!(matches!(*error, Error::Json(_)));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_server_echo_answered() {
+        let (_client, _notifications, mut server) = pair();
+        let echo = Request::new("echo", json!(["probe"]), json!("echo"));
+        server.send(echo.into()).await.unwrap();
+        match server.next().await.unwrap().unwrap() {
+            Message::Response(response) => {
+                assert_eq!(response.id, json!("echo"));
+                assert_eq!(response.result, json!(["probe"]));
+                assert!(response.error.is_none());
+            }
+            other => panic!("Expected Message::Response, got {:?}", other),
+        }
+
+        let request = Request::new("transact", json!([]), json!(7));
+        server.send(request.into()).await.unwrap();
+        match server.next().await.unwrap().unwrap() {
+            Message::Response(response) => {
+                assert_eq!(response.id, json!(7));
+                assert_eq!(response.error.unwrap().error(), "unknown method");
+            }
+            other => panic!("Expected Message::Response, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_probe() {
+        let (client, _notifications, mut server) = pair();
+        client.set_probe(Some(Probe {
+            idle: Duration::from_millis(50),
+            timeout: Duration::from_millis(50),
+        }));
+
+        // an idle connection is probed, and an answer keeps it open
+        let echo = next_request(&mut server).await;
+        assert_eq!(echo.method, "echo");
+        assert_eq!(echo.id, json!("echo"));
+        server
+            .send(Response::ok(echo.id, echo.params).into())
+            .await
+            .unwrap();
+
+        // unanswered, the next probe gets the connection closed
+        let echo = next_request(&mut server).await;
+        assert_eq!(echo.method, "echo");
+        assert!(server.next().await.is_none());
+        assert!(client.is_closed());
+        assert!(matches!(client.list_dbs().await, Err(Error::Disconnected)));
+    }
+
+    #[tokio::test]
+    async fn test_connect_tcp() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut server = Framed::new(stream, JsonCodec::default());
+            let request = match server.next().await.unwrap().unwrap() {
+                Message::Request(request) => request,
+                other => panic!("Expected Message::Request, got {:?}", other),
+            };
+            server
+                .send(Response::ok(request.id, json!(["_Server"])).into())
+                .await
+                .unwrap();
+        });
+
+        let (client, _notifications) = Client::connect(&format!("tcp:127.0.0.1:{}", port))
+            .await
+            .unwrap();
+        assert_eq!(client.list_dbs().await.unwrap(), vec!["_Server"]);
+        server.await.unwrap();
+    }
+}
@@ -0,0 +1,219 @@
+use crate::error::Error;
+use bytes::{Buf, BufMut, BytesMut};
+use ovsdb_common::jsonrpc::Message;
+use tokio_util::codec::{Decoder, Encoder};
+
+/// The longest message [`JsonCodec::default`] accepts, in bytes.
+pub const DEFAULT_MAX_LENGTH: usize = 64 * 1024 * 1024;
+
+/// Frames JSON-RPC messages on a byte stream.
+///
+/// RFC 7047 peers write JSON objects back to back without a length prefix,
+/// so a frame ends where the brackets of the object balance. The bytes of
+/// a message are scanned once, however many reads it arrives in, and a
+/// message longer than the limit is an error rather than a buffer that
+/// grows without bound.
+#[derive(Debug)]
+pub struct JsonCodec {
+    max_length: usize,
+    /// How much of the current message has been scanned.
+    scanned: usize,
+    /// The nesting of objects and arrays at `scanned`.
+    depth: usize,
+    in_string: bool,
+    escaped: bool,
+}
+
+impl JsonCodec {
+    /// A codec that fails on messages longer than `max_length` bytes.
+    pub fn with_max_length(max_length: usize) -> Self {
+        JsonCodec {
+            max_length,
+            scanned: 0,
+            depth: 0,
+            in_string: false,
+            escaped: false,
+        }
+    }
+
+    /// Scans on from `scanned`, and returns the length of the message if
+    /// it is complete.
+    fn scan(&mut self, src: &[u8]) -> Result<Option<usize>, Error> {
+        for (i, &byte) in src.iter().enumerate().skip(self.scanned) {
+            if self.in_string {
+                match byte {
+                    _ if self.escaped => self.escaped = false,
+                    b'\\' => self.escaped = true,
+                    b'"' => self.in_string = false,
+                    _ => {}
+                }
+                continue;
+            }
+            match byte {
+                // a message is an object
+                _ if self.depth == 0 && byte != b'{' => {
+                    return Err(Error::Json(serde::de::Error::custom(format!(
+                        "expected a JSON object, found {:?}",
+                        char::from(byte)
+                    ))))
+                }
+                b'"' => self.in_string = true,
+                b'{' | b'[' => self.depth += 1,
+                b'}' | b']' => {
+                    self.depth -= 1;
+                    if self.depth == 0 {
+                        return Ok(Some(i + 1));
+                    }
+                }
+                _ => {}
+            }
+        }
+        self.scanned = src.len();
+        Ok(None)
+    }
+}
+
+impl Default for JsonCodec {
+    fn default() -> Self {
+        JsonCodec::with_max_length(DEFAULT_MAX_LENGTH)
+    }
+}
+
+impl Decoder for JsonCodec {
+    type Item = Message;
+    type Error = Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Message>, Error> {
+        if self.scanned == 0 {
+            // whitespace between messages
+            let blank = src.iter().take_while(|b| b.is_ascii_whitespace()).count();
+            src.advance(blank);
+        }
+        let end = self.scan(src)?;
+        if end.unwrap_or(src.len()) > self.max_length {
+            return Err(Error::MessageTooLong(self.max_length));
+        }
+        let Some(end) = end else {
+            // the message is not complete yet, wait for more bytes
+            return Ok(None);
+        };
+        self.scanned = 0;
+        let frame = src.split_to(end);
+        Ok(Some(serde_json::from_slice(&frame)?))
+    }
+}
+
+impl Encoder<Message> for JsonCodec {
+    type Error = Error;
+
+    fn encode(&mut self, item: Message, dst: &mut BytesMut) -> Result<(), Error> {
+        serde_json::to_writer(dst.writer(), &item)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::codec::*;
+    use ovsdb_common::jsonrpc::{Notification, Request};
+    use serde_json::{json, Value};
+
+    #[test]
+    fn test_decode_concatenated_messages() {
+        let mut buf = BytesMut::from(
+            &br#"{"method":"echo","params":[],"id":1} {"method":"update","params":[null,{}],"id":null}"#[..],
+        );
+        let mut codec = JsonCodec::default();
+        assert_eq!(
+            codec.decode(&mut buf).unwrap(),
+            Some(Message::Request(Request::new("echo", json!([]), json!(1))))
+        );
+        assert_eq!(
+            codec.decode(&mut buf).unwrap(),
+            Some(Message::Notification(Notification::new(
+                "update",
+                json!([null, {}])
+            )))
+        );
+        assert_eq!(codec.decode(&mut buf).unwrap(), None);
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn test_decode_partial_message() {
+        let mut codec = JsonCodec::default();
+        let mut buf = BytesMut::from(&br#"{"result":["OVN_No"#[..]);
+        assert_eq!(codec.decode(&mut buf).unwrap(), None);
+        buf.extend_from_slice(br#"rthbound"],"error":null,"id":3}"#);
+        match codec.decode(&mut buf).unwrap() {
+            Some(Message::Response(response)) => {
+                assert_eq!(response.result, json!(["OVN_Northbound"]));
+                assert_eq!(response.id, json!(3));
+            }
+            other => panic!("Expected Message::Response, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_decode_byte_by_byte() {
+        // brackets and escaped quotes inside strings do not count
+        let text = br#" {"method":"echo","params":["}]\"{[", "\\"],"id":"a"}
+"#;
+        let mut codec = JsonCodec::default();
+        let mut buf = BytesMut::new();
+        let mut decoded = Vec::new();
+        for byte in text {
+            buf.extend_from_slice(&[*byte]);
+            decoded.extend(codec.decode(&mut buf).unwrap());
+        }
+        assert_eq!(
+            decoded,
+            vec![Message::Request(Request::new(
+                "echo",
+                json!(["}]\"{[", "\\"]),
+                json!("a")
+            ))]
+        );
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn test_decode_too_long() {
+        let mut codec = JsonCodec::with_max_length(16);
+        let mut buf = BytesMut::from(&br#"{"method":"#[..]);
+        assert_eq!(codec.decode(&mut buf).unwrap(), None);
+        buf.extend_from_slice(br#""echo","params":[],"id":1}"#);
+        assert!(matches!(
+            codec.decode(&mut buf),
+            Err(Error::MessageTooLong(16))
+        ));
+    }
+
+    #[test]
+    fn test_decode_garbage() {
+        let mut codec = JsonCodec::default();
+        let mut buf = BytesMut::from(&b"}{"[..]);
+        assert!(matches!(codec.decode(&mut buf), Err(Error::Json(_))));
+        let mut codec = JsonCodec::default();
+        let mut buf = BytesMut::from(&b"[1]"[..]);
+        let error = codec.decode(&mut buf).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "JSON error: expected a JSON object, found '['"
+        );
+    }
+
+    #[test]
+    fn test_encode() {
+        let mut codec = JsonCodec::default();
+        let mut buf = BytesMut::new();
+        codec
+            .encode(
+                Request::new("list_dbs", json!([]), json!(0)).into(),
+                &mut buf,
+            )
+            .unwrap();
+        let value: Value = serde_json::from_slice(&buf).unwrap();
+        assert_eq!(value, json!({"method": "list_dbs", "params": [], "id": 0}));
+    }
+}
@@ -0,0 +1,45 @@
+use std::sync::Arc;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("message longer than {0} bytes")]
+    MessageTooLong(usize),
+    #[error("invalid remote '{0}'")]
+    InvalidRemote(String),
+    #[error("SSL error: {0}")]
+    Ssl(String),
+    #[error("server error")]
+    Rpc(#[source] ovsdb_common::error::Error),
+    #[error("{op} operation #{index} failed")]
+    OperationFailed {
+        index: usize,
+        op: String,
+        #[source]
+        error: ovsdb_common::error::Error,
+    },
+    #[error("unexpected response: {0}")]
+    UnexpectedResponse(String),
+    #[error("update does not fit the cache: {0}")]
+    InconsistentUpdate(String),
+    #[error("invalid index: {0}")]
+    InvalidIndex(String),
+    #[error("lock {0} is not held")]
+    LockNotHeld(String),
+    #[error("lock {0} is already requested through this client")]
+    DuplicateLock(String),
+    #[error("unsuitable server {remote}: {reason}")]
+    UnsuitableServer { remote: String, reason: String },
+    #[error("connection closed")]
+    Disconnected,
+    /// The connection was closed because what the server sent could not be
+    /// read: an I/O error, a message that is not JSON, or one too long.
+    #[error("connection closed: reading from the server failed")]
+    ReadFailed(#[source] Arc<Error>),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,117 @@
+use ovsdb_common::datum::Datum;
+use ovsdb_common::row::Row;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::ops::RangeBounds;
+use uuid::Uuid;
+
+/// A secondary index over the rows of one [`TableCache`](crate::TableCache).
+///
+/// The key of a row is the value of each indexed column, in the order the
+/// columns were given. `_uuid` may be used as a column.
+#[derive(Debug)]
+pub(crate) struct Index {
+    columns: Vec<String>,
+    entries: Entries,
+}
+
+#[derive(Debug)]
+enum Entries {
+    /// Built from the schema's `indexes`; supports exact lookups only.
+    Hash(HashMap<Vec<Datum>, BTreeSet<Uuid>>),
+    /// Added by the user; also supports range lookups.
+    Ordered(BTreeMap<Vec<Datum>, BTreeSet<Uuid>>),
+}
+
+impl Index {
+    pub(crate) fn hash(columns: Vec<String>) -> Self {
+        Index {
+            columns,
+            entries: Entries::Hash(HashMap::new()),
+        }
+    }
+
+    pub(crate) fn ordered(columns: Vec<String>) -> Self {
+        Index {
+            columns,
+            entries: Entries::Ordered(BTreeMap::new()),
+        }
+    }
+
+    pub(crate) fn columns(&self) -> &[String] {
+        &self.columns
+    }
+
+    pub(crate) fn is_ordered(&self) -> bool {
+        matches!(self.entries, Entries::Ordered(_))
+    }
+
+    /// The key of `row`, or `None` if it lacks one of the indexed columns,
+    /// as happens when the monitor does not cover them.
+    fn key(&self, uuid: &Uuid, row: &Row) -> Option<Vec<Datum>> {
+        self.columns
+            .iter()
+            .map(|column| match column.as_str() {
+                "_uuid" => Some(Datum::from(*uuid)),
+                _ => row.get(column).cloned(),
+            })
+            .collect()
+    }
+
+    pub(crate) fn insert(&mut self, uuid: &Uuid, row: &Row) {
+        let Some(key) = self.key(uuid, row) else {
+            return;
+        };
+        match &mut self.entries {
+            Entries::Hash(map) => map.entry(key).or_default().insert(*uuid),
+            Entries::Ordered(map) => map.entry(key).or_default().insert(*uuid),
+        };
+    }
+
+    pub(crate) fn remove(&mut self, uuid: &Uuid, row: &Row) {
+        let Some(key) = self.key(uuid, row) else {
+            return;
+        };
+        let uuids = match &mut self.entries {
+            Entries::Hash(map) => map.get_mut(&key),
+            Entries::Ordered(map) => map.get_mut(&key),
+        };
+        let Some(uuids) = uuids else {
+            return;
+        };
+        uuids.remove(uuid);
+        if uuids.is_empty() {
+            match &mut self.entries {
+                Entries::Hash(map) => map.remove(&key),
+                Entries::Ordered(map) => map.remove(&key),
+            };
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        match &mut self.entries {
+            Entries::Hash(map) => map.clear(),
+            Entries::Ordered(map) => map.clear(),
+        }
+    }
+
+    pub(crate) fn get(&self, key: &[Datum]) -> impl Iterator<Item = &Uuid> {
+        let uuids = match &self.entries {
+            Entries::Hash(map) => map.get(key),
+            Entries::Ordered(map) => map.get(key),
+        };
+        uuids.into_iter().flatten()
+    }
+
+    /// The rows whose key falls in `range`, in key order. Always empty for
+    /// a hash index.
+    pub(crate) fn range<R>(&self, range: R) -> impl Iterator<Item = &Uuid>
+    where
+        R: RangeBounds<Vec<Datum>>,
+    {
+        let entries = match &self.entries {
+            Entries::Hash(_) => None,
+            Entries::Ordered(map) => Some(map.range(range)),
+        };
+        entries.into_iter().flatten().flat_map(|(_, uuids)| uuids)
+    }
+}
@@ -1,14 +1,25 @@
-pub fn add(left: u64, right: u64) -> u64 {
-    left + right
-}
+pub mod cache;
+pub mod client;
+pub mod codec;
+pub mod error;
+mod index;
+pub mod listener;
+pub mod lock;
+pub mod monitor;
+pub mod operations;
+pub mod remote;
+pub mod server;
+pub mod session;
+pub mod ssl;
+pub mod transaction;
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn it_works() {
-        let result = add(2, 2);
-        assert_eq!(result, 4);
-    }
-}
+pub use cache::{Cache, RowChange, TableCache};
+pub use client::{Client, Notifications, Probe};
+pub use error::{Error, Result};
+pub use listener::{Incoming, Listener};
+pub use lock::{Lock, LockEvent};
+pub use monitor::{Monitor, MonitorRequest, MonitorRequests, RowUpdate, TableUpdates};
+pub use remote::{PassiveRemote, Remote};
+pub use session::{Event, Session};
+pub use ssl::SslOptions;
+pub use transaction::{Outcome, Transaction};
@@ -0,0 +1,225 @@
+use crate::client::{Client, Notifications};
+use crate::error::{Error, Result};
+use crate::remote::{Connection, PassiveRemote};
+use crate::ssl::SslOptions;
+use std::fmt;
+use std::time::Duration;
+use tokio::net::TcpListener;
+#[cfg(unix)]
+use tokio::net::UnixListener;
+
+/// How long a `pssl:` peer gets to complete the TLS handshake.
+pub const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Waits on a passive remote for database servers to connect, and runs a
+/// JSON-RPC session on each connection, for reversed connections where
+/// `ovsdb-server` dials out to its clients.
+///
+/// ```no_run
+/// # async fn example() -> ovsdb_client::Result<()> {
+/// use ovsdb_client::listener::Listener;
+///
+/// let listener = Listener::bind("ptcp:6640:127.0.0.1", None).await?;
+/// loop {
+///     let (client, _notifications) = listener.accept().await?;
+///     tokio::spawn(async move {
+///         println!("{:?}", client.list_dbs().await);
+///     });
+/// }
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Listener {
+    remote: PassiveRemote,
+    socket: Socket,
+    ssl: Option<SslOptions>,
+}
+
+#[derive(Debug)]
+enum Socket {
+    Tcp(TcpListener),
+    #[cfg(unix)]
+    Unix(UnixListener),
+}
+
+impl Listener {
+    /// Starts listening on a passive remote such as `ptcp:6640` or
+    /// `punix:/run/agent.sock`. `pssl:` remotes need `ssl`; the others
+    /// ignore it.
+    ///
+    /// As `ovsdb-server` does, a stale socket file left at a `punix:` path
+    /// is replaced.
+    pub async fn bind(remote: &str, ssl: Option<&SslOptions>) -> Result<Listener> {
+        let remote: PassiveRemote = remote.parse()?;
+        let socket = match &remote {
+            PassiveRemote::Ptcp { port, host } | PassiveRemote::Pssl { port, host } => {
+                if matches!(remote, PassiveRemote::Pssl { .. }) && ssl.is_none() {
+                    return Err(Error::Ssl(format!(
+                        "{} needs a private key, a certificate and a CA certificate",
+                        remote
+                    )));
+                }
+                let host = host.as_deref().unwrap_or("0.0.0.0");
+                Socket::Tcp(TcpListener::bind((host, *port)).await?)
+            }
+            #[cfg(unix)]
+            PassiveRemote::Punix(path) => {
+                match std::fs::remove_file(path) {
+                    Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+                    _ => {}
+                }
+                Socket::Unix(UnixListener::bind(path)?)
+            }
+            #[cfg(not(unix))]
+            PassiveRemote::Punix(_) => return Err(Error::InvalidRemote(remote.to_string())),
+        };
+        Ok(Listener {
+            remote,
+            socket,
+            ssl: ssl.cloned(),
+        })
+    }
+
+    /// The remote listened on, as given to [`Listener::bind`].
+    pub fn remote(&self) -> &PassiveRemote {
+        &self.remote
+    }
+
+    /// The TCP port listened on, which tells the port the system picked
+    /// for port 0. `None` for `punix:` remotes.
+    pub fn port(&self) -> Option<u16> {
+        match &self.socket {
+            Socket::Tcp(listener) => listener.local_addr().ok().map(|addr| addr.port()),
+            #[cfg(unix)]
+            Socket::Unix(_) => None,
+        }
+    }
+
+    /// Waits for the next connection and runs a session on it. For `pssl:`
+    /// remotes, this includes the TLS handshake, which runs before `accept`
+    /// returns: a peer that stays silent holds up every caller for up to
+    /// [`HANDSHAKE_TIMEOUT`], and one that fails it makes `accept` fail,
+    /// though the listener can go on accepting. To accept while handshakes
+    /// are under way, use [`Listener::accept_incoming`].
+    pub async fn accept(&self) -> Result<(Client, Notifications)> {
+        let stream = self.accept_incoming().await?.establish().await?;
+        Ok(Client::from_stream(stream))
+    }
+
+    /// Waits for the next connection, and leaves it to the caller, such as
+    /// a database server answering the requests itself. The TLS handshake
+    /// of `pssl:` remotes is left to [`Incoming::establish`] too, so that a
+    /// peer that never completes it does not hold up accepting.
+    pub async fn accept_incoming(&self) -> Result<Incoming> {
+        let (stream, ssl): (Box<dyn Connection>, _) = match &self.socket {
+            Socket::Tcp(listener) => {
+                let (stream, _) = listener.accept().await?;
+                stream.set_nodelay(true)?;
+                let ssl = match &self.remote {
+                    PassiveRemote::Pssl { .. } => self.ssl.clone(),
+                    _ => None,
+                };
+                (Box::new(stream), ssl)
+            }
+            #[cfg(unix)]
+            Socket::Unix(listener) => (Box::new(listener.accept().await?.0), None),
+        };
+        Ok(Incoming { stream, ssl })
+    }
+}
+
+/// A connection accepted by a [`Listener`], before the TLS handshake of
+/// `pssl:` remotes.
+pub struct Incoming {
+    stream: Box<dyn Connection>,
+    ssl: Option<SslOptions>,
+}
+
+impl fmt::Debug for Incoming {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Incoming")
+            .field("ssl", &self.ssl)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Incoming {
+    /// Runs the TLS handshake, if any, giving up after
+    /// [`HANDSHAKE_TIMEOUT`], and returns the connection.
+    pub async fn establish(self) -> Result<Box<dyn Connection>> {
+        let Some(ssl) = self.ssl else {
+            return Ok(self.stream);
+        };
+        match tokio::time::timeout(HANDSHAKE_TIMEOUT, ssl.accept(self.stream)).await {
+            Ok(stream) => Ok(Box::new(stream?)),
+            Err(_) => Err(Error::Ssl("TLS handshake timed out".to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::codec::JsonCodec;
+    use crate::listener::*;
+    use futures::{SinkExt, StreamExt};
+    use ovsdb_common::jsonrpc::{Message, Response};
+    use serde_json::json;
+    use tokio::net::TcpStream;
+    use tokio_util::codec::Framed;
+
+    /// The database server's side: answers one `list_dbs`.
+    async fn serve<S: Connection>(stream: S) {
+        let mut server = Framed::new(stream, JsonCodec::default());
+        let request = match server.next().await.unwrap().unwrap() {
+            Message::Request(request) => request,
+            other => panic!("Expected Message::Request, got {:?}", other),
+        };
+        assert_eq!(request.method, "list_dbs");
+        server
+            .send(Response::ok(request.id, json!(["OVN_Northbound"])).into())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_ptcp() {
+        let listener = Listener::bind("ptcp:0:127.0.0.1", None).await.unwrap();
+        let port = listener.port().unwrap();
+        assert_ne!(port, 0);
+
+        for _ in 0..2 {
+            let server = tokio::spawn(async move {
+                serve(TcpStream::connect(("127.0.0.1", port)).await.unwrap()).await;
+            });
+            let (client, _notifications) = listener.accept().await.unwrap();
+            assert_eq!(client.list_dbs().await.unwrap(), vec!["OVN_Northbound"]);
+            server.await.unwrap();
+        }
+
+        assert!(matches!(
+            Listener::bind("pssl:0:127.0.0.1", None).await,
+            Err(Error::Ssl(_))
+        ));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_punix() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("agent.sock");
+        // a socket file left behind by a previous run
+        std::fs::write(&path, "").unwrap();
+
+        let remote = format!("punix:{}", path.display());
+        let listener = Listener::bind(&remote, None).await.unwrap();
+        assert_eq!(listener.remote().to_string(), remote);
+        assert_eq!(listener.port(), None);
+
+        let server = tokio::spawn(async move {
+            serve(tokio::net::UnixStream::connect(path).await.unwrap()).await;
+        });
+        let (client, _notifications) = listener.accept().await.unwrap();
+        assert_eq!(client.list_dbs().await.unwrap(), vec!["OVN_Northbound"]);
+        server.await.unwrap();
+    }
+}
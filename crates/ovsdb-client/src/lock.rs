@@ -0,0 +1,379 @@
+use crate::client::Client;
+use crate::error::{Error, Result};
+use futures::Stream;
+use ovsdb_common::jsonrpc::{Notification, Response};
+use serde_json::{json, Value};
+use std::collections::hash_map::{Entry, HashMap};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use tokio::sync::{mpsc, watch};
+
+/// The locks requested through a client, keyed by lock name.
+pub(crate) type Locks = Arc<Mutex<HashMap<String, LockState>>>;
+
+#[derive(Debug)]
+pub(crate) struct LockState {
+    /// The id of the `lock` or `steal` request, which also tells this
+    /// state from one registered later under the same name.
+    id: u64,
+    replied: bool,
+    held: watch::Sender<bool>,
+    events: mpsc::UnboundedSender<LockEvent>,
+}
+
+impl LockState {
+    fn set(&self, event: LockEvent) {
+        self.held.send_replace(event == LockEvent::Acquired);
+        let _ = self.events.send(event);
+    }
+
+    /// Marks the lock as lost with the connection.
+    pub(crate) fn release(&self) {
+        self.held.send_replace(false);
+    }
+}
+
+/// A change in the ownership of a [`Lock`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockEvent {
+    Acquired,
+    /// Another client stole the lock. The request stays queued, so the
+    /// lock may be acquired again once the thief releases it.
+    Stolen,
+}
+
+/// A lock requested with `lock` or `steal`, and a [`Stream`] of changes in
+/// its ownership.
+///
+/// Dropping the handle releases the lock, or withdraws the request if the
+/// lock was never acquired. The stream ends when the connection closes,
+/// which releases every lock the session held.
+///
+/// ```no_run
+/// # async fn example(client: &ovsdb_client::Client) -> ovsdb_client::Result<()> {
+/// use futures::StreamExt;
+/// use ovsdb_client::lock::LockEvent;
+///
+/// let mut lock = client.lock("ovn_northd").await?;
+/// lock.acquired().await?;
+/// println!("active");
+/// while let Some(event) = lock.next().await {
+///     if event == LockEvent::Stolen {
+///         println!("standby");
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Lock {
+    client: Client,
+    name: String,
+    id: u64,
+    held: watch::Receiver<bool>,
+    events: mpsc::UnboundedReceiver<LockEvent>,
+    released: bool,
+}
+
+impl Lock {
+    pub(crate) async fn request(client: &Client, method: &str, name: &str) -> Result<Lock> {
+        let id = client.next_id();
+        let (held_tx, held) = watch::channel(false);
+        let (events_tx, events) = mpsc::unbounded_channel();
+        let state = LockState {
+            id,
+            replied: false,
+            held: held_tx,
+            events: events_tx,
+        };
+        // register first: "locked" may follow the reply immediately
+        match client.locks().lock().unwrap().entry(name.to_string()) {
+            Entry::Occupied(_) => return Err(Error::DuplicateLock(name.to_string())),
+            Entry::Vacant(entry) => {
+                entry.insert(state);
+            }
+        }
+        let mut lock = Lock {
+            client: client.clone(),
+            name: name.to_string(),
+            id,
+            held,
+            events,
+            released: false,
+        };
+
+        // the session applies {"locked": true} itself, see `reply`
+        let reply = client.request_with_id(id, method, json!([name])).await;
+        let checked = reply.and_then(|reply| match reply.get("locked") {
+            Some(Value::Bool(_)) => Ok(()),
+            _ => Err(Error::UnexpectedResponse(reply.to_string())),
+        });
+        if let Err(e) = checked {
+            // the server never took the request: nothing to unlock
+            lock.released = true;
+            lock.unregister();
+            return Err(e);
+        }
+        Ok(lock)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Whether this session owns the lock right now.
+    pub fn is_held(&self) -> bool {
+        *self.held.borrow()
+    }
+
+    /// Waits until the lock is held, which is right away if it already is.
+    pub async fn acquired(&mut self) -> Result<()> {
+        self.held
+            .wait_for(|held| *held)
+            .await
+            .map(|_| ())
+            .map_err(|_| self.client.closed_error())
+    }
+
+    /// Releases the lock, or withdraws the request, and waits for the
+    /// server to confirm.
+    // https://tools.ietf.org/html/rfc7047#section-4.1.10
+    pub async fn unlock(mut self) -> Result<()> {
+        self.released = true;
+        self.unregister();
+        self.client.request("unlock", json!([self.name])).await?;
+        Ok(())
+    }
+
+    pub(crate) fn held(&self) -> watch::Receiver<bool> {
+        self.held.clone()
+    }
+
+    /// Forgets the state this handle registered, and returns whether it
+    /// was still there.
+    fn unregister(&self) -> bool {
+        let mut locks = self.client.locks().lock().unwrap();
+        match locks.entry(self.name.clone()) {
+            Entry::Occupied(entry) if entry.get().id == self.id => {
+                entry.remove();
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Stream for Lock {
+    type Item = LockEvent;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<LockEvent>> {
+        self.get_mut().events.poll_recv(cx)
+    }
+}
+
+impl Drop for Lock {
+    fn drop(&mut self) {
+        if !self.released && self.unregister() {
+            self.client.request_detached("unlock", json!([self.name]));
+        }
+    }
+}
+
+/// Applies the reply to a `lock` or `steal` request before the caller gets
+/// it, so that an acquisition it reports comes before any notification the
+/// server sent after it.
+pub(crate) fn reply(locks: &Locks, response: &Response) {
+    let Some(id) = response.id.as_u64() else {
+        return;
+    };
+    let mut locks = locks.lock().unwrap();
+    let Some(state) = locks
+        .values_mut()
+        .find(|state| state.id == id && !state.replied)
+    else {
+        return;
+    };
+    state.replied = true;
+    if response.error.is_none() && response.result.get("locked") == Some(&Value::Bool(true)) {
+        state.set(LockEvent::Acquired);
+    }
+}
+
+/// Hands a `locked` or `stolen` notification to its lock. Anything else,
+/// including notifications for unknown locks, is given back.
+pub(crate) fn dispatch(locks: &Locks, notification: Notification) -> Option<Notification> {
+    let event = match notification.method.as_str() {
+        "locked" => LockEvent::Acquired,
+        "stolen" => LockEvent::Stolen,
+        _ => return Some(notification),
+    };
+    let Some(name) = notification.params.get(0).and_then(Value::as_str) else {
+        return Some(notification);
+    };
+    match locks.lock().unwrap().get(name) {
+        Some(state) => {
+            state.set(event);
+            None
+        }
+        None => Some(notification),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::codec::JsonCodec;
+    use crate::lock::*;
+    use crate::Transaction;
+    use futures::{SinkExt, StreamExt};
+    use ovsdb_common::jsonrpc::{Message, Request, Response};
+    use tokio_util::codec::Framed;
+
+    type Server = Framed<tokio::io::DuplexStream, JsonCodec>;
+
+    fn pair() -> (Client, Server) {
+        let (client_side, server_side) = tokio::io::duplex(4096);
+        let (client, _notifications) = Client::from_stream(client_side);
+        (client, Framed::new(server_side, JsonCodec::default()))
+    }
+
+    async fn reply(server: &mut Server, method: &str, result: Value) -> Request {
+        let request = match server.next().await.unwrap().unwrap() {
+            Message::Request(request) => request,
+            other => panic!("Expected Message::Request, got {:?}", other),
+        };
+        assert_eq!(request.method, method);
+        server
+            .send(Response::ok(request.id.clone(), result).into())
+            .await
+            .unwrap();
+        request
+    }
+
+    async fn notify(server: &mut Server, method: &str, lock: &str) {
+        server
+            .send(Notification::new(method, json!([lock])).into())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_lock_stolen_and_released() {
+        let (client, mut server) = pair();
+        let call = tokio::spawn({
+            let client = client.clone();
+            async move { client.lock("controller").await }
+        });
+        let request = reply(&mut server, "lock", json!({"locked": false})).await;
+        assert_eq!(request.params, json!(["controller"]));
+        let mut lock = call.await.unwrap().unwrap();
+        assert!(!lock.is_held());
+
+        notify(&mut server, "locked", "controller").await;
+        lock.acquired().await.unwrap();
+        assert!(lock.is_held());
+        assert_eq!(lock.next().await, Some(LockEvent::Acquired));
+
+        notify(&mut server, "stolen", "controller").await;
+        assert_eq!(lock.next().await, Some(LockEvent::Stolen));
+        assert!(!lock.is_held());
+
+        drop(lock);
+        let request = match server.next().await.unwrap().unwrap() {
+            Message::Request(request) => request,
+            other => panic!("Expected Message::Request, got {:?}", other),
+        };
+        assert_eq!(request.method, "unlock");
+        assert_eq!(request.params, json!(["controller"]));
+    }
+
+    #[tokio::test]
+    async fn test_steal_and_require_lock() {
+        let (client, mut server) = pair();
+        let call = tokio::spawn({
+            let client = client.clone();
+            async move { client.steal("controller").await }
+        });
+        reply(&mut server, "steal", json!({"locked": true})).await;
+        let lock = call.await.unwrap().unwrap();
+        assert!(lock.is_held());
+
+        let mut txn = Transaction::new("OVN_Northbound");
+        txn.require_lock(&lock).comment("active");
+        assert_eq!(
+            txn.to_params().unwrap()[0],
+            json!({"op": "assert", "lock": "controller"})
+        );
+
+        notify(&mut server, "stolen", "controller").await;
+        let mut lock = lock;
+        assert_eq!(lock.next().await, Some(LockEvent::Acquired));
+        assert_eq!(lock.next().await, Some(LockEvent::Stolen));
+        match txn.execute(&client).await {
+            Err(Error::LockNotHeld(name)) => assert_eq!(name, "controller"),
+            other => panic!("Expected Error::LockNotHeld, got {:?}", other),
+        }
+
+        let unlock = tokio::spawn(lock.unlock());
+        reply(&mut server, "unlock", json!({})).await;
+        unlock.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_stolen_right_after_reply() {
+        let (client, mut server) = pair();
+        let call = tokio::spawn({
+            let client = client.clone();
+            async move { client.steal("controller").await }
+        });
+        // the notification may well arrive before the caller runs again
+        reply(&mut server, "steal", json!({"locked": true})).await;
+        notify(&mut server, "stolen", "controller").await;
+        let mut lock = call.await.unwrap().unwrap();
+        assert_eq!(lock.next().await, Some(LockEvent::Acquired));
+        assert_eq!(lock.next().await, Some(LockEvent::Stolen));
+        assert!(!lock.is_held());
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_lock() {
+        let (client, mut server) = pair();
+        let call = tokio::spawn({
+            let client = client.clone();
+            async move { client.lock("controller").await }
+        });
+        reply(&mut server, "lock", json!({"locked": true})).await;
+        let mut lock = call.await.unwrap().unwrap();
+
+        match client.steal("controller").await {
+            Err(Error::DuplicateLock(name)) => assert_eq!(name, "controller"),
+            other => panic!("Expected Error::DuplicateLock, got {:?}", other),
+        }
+        // the first handle is untouched
+        notify(&mut server, "stolen", "controller").await;
+        assert_eq!(lock.next().await, Some(LockEvent::Acquired));
+        assert_eq!(lock.next().await, Some(LockEvent::Stolen));
+
+        // once it is released, the name is free again
+        let unlock = tokio::spawn(lock.unlock());
+        reply(&mut server, "unlock", json!({})).await;
+        unlock.await.unwrap().unwrap();
+        let call = tokio::spawn({
+            let client = client.clone();
+            async move { client.lock("controller").await }
+        });
+        reply(&mut server, "lock", json!({"locked": false})).await;
+        assert!(!call.await.unwrap().unwrap().is_held());
+    }
+
+    #[tokio::test]
+    async fn test_disconnect_loses_lock() {
+        let (client, mut server) = pair();
+        let call = tokio::spawn(async move { client.lock("controller").await });
+        reply(&mut server, "lock", json!({"locked": false})).await;
+        let mut lock = call.await.unwrap().unwrap();
+        drop(server);
+        assert!(matches!(lock.acquired().await, Err(Error::Disconnected)));
+        assert_eq!(lock.next().await, None);
+    }
+}
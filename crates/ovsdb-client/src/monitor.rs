@@ -0,0 +1,581 @@
+use crate::client::Client;
+use crate::error::Result;
+use futures::Stream;
+use ovsdb_common::condition::Condition;
+use ovsdb_common::jsonrpc::Notification;
+use ovsdb_common::row::Row;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+/// Update channels of the live monitors, keyed by the JSON text of their id.
+pub(crate) type Monitors = Arc<Mutex<HashMap<String, mpsc::UnboundedSender<Result<TableUpdates>>>>>;
+
+/// What to monitor in one table.
+///
+/// `where_` is only sent by `monitor_cond` and `monitor_cond_since`, and is
+/// what `monitor_cond_change` replaces.
+// https://tools.ietf.org/html/rfc7047#section-4.1.5
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct MonitorRequest {
+    /// Columns to report; all of them when `None`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub columns: Option<Vec<String>>,
+    #[serde(rename = "where")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub where_: Option<Vec<Condition>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub select: Option<Select>,
+}
+
+/// Monitor requests keyed by table name.
+pub type MonitorRequests = BTreeMap<String, MonitorRequest>;
+
+/// Which kinds of changes a monitor reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Select {
+    pub initial: bool,
+    pub insert: bool,
+    pub delete: bool,
+    pub modify: bool,
+}
+
+impl Default for Select {
+    fn default() -> Self {
+        Select {
+            initial: true,
+            insert: true,
+            delete: true,
+            modify: true,
+        }
+    }
+}
+
+/// A change to one row.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RowUpdate {
+    /// The row existed when the monitor was set up.
+    Initial(Row),
+    Insert(Row),
+    /// `update2`/`update3` modification: the changed columns in diff form,
+    /// i.e. the new value of scalars, and the elements or pairs to toggle
+    /// for sets and maps.
+    Modify(Row),
+    /// `update` modification: the old value of every changed column, and
+    /// the new row.
+    Update {
+        old: Row,
+        new: Row,
+    },
+    /// The deleted row, if the server sent it.
+    Delete(Option<Row>),
+}
+
+/// One batch of changes, as delivered by a single notification.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TableUpdates {
+    /// `update3` only: the transaction the database is at after this batch.
+    pub last_txn_id: Option<Uuid>,
+    pub tables: BTreeMap<String, BTreeMap<Uuid, RowUpdate>>,
+}
+
+impl TableUpdates {
+    pub fn is_empty(&self) -> bool {
+        self.tables.values().all(BTreeMap::is_empty)
+    }
+
+    /// Iterates over `(table, uuid, update)`.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Uuid, &RowUpdate)> {
+        self.tables.iter().flat_map(|(table, rows)| {
+            rows.iter()
+                .map(move |(uuid, update)| (table.as_str(), uuid, update))
+        })
+    }
+
+    /// Parses RFC 7047 `<table-updates>`.
+    fn from_v1(value: Value, initial: bool) -> Result<Self> {
+        #[derive(Deserialize)]
+        struct Raw {
+            old: Option<Row>,
+            new: Option<Row>,
+        }
+
+        let raw: BTreeMap<String, BTreeMap<Uuid, Raw>> = serde_json::from_value(value)?;
+        let mut updates = TableUpdates::default();
+        for (table, rows) in raw {
+            let rows = rows
+                .into_iter()
+                .map(|(uuid, raw)| {
+                    let update = match (raw.old, raw.new) {
+                        (None, Some(new)) if initial => RowUpdate::Initial(new),
+                        (None, Some(new)) => RowUpdate::Insert(new),
+                        (Some(old), Some(new)) => RowUpdate::Update { old, new },
+                        (old, None) => RowUpdate::Delete(old),
+                    };
+                    (uuid, update)
+                })
+                .collect();
+            updates.tables.insert(table, rows);
+        }
+        Ok(updates)
+    }
+
+    /// Parses `<table-updates2>`, used by `update2` and `update3`.
+    fn from_v2(value: Value) -> Result<Self> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "lowercase")]
+        enum Raw {
+            Initial(Row),
+            Insert(Row),
+            Modify(Row),
+            Delete(Option<Row>),
+        }
+
+        let raw: BTreeMap<String, BTreeMap<Uuid, Raw>> = serde_json::from_value(value)?;
+        let mut updates = TableUpdates::default();
+        for (table, rows) in raw {
+            let rows = rows
+                .into_iter()
+                .map(|(uuid, raw)| {
+                    let update = match raw {
+                        Raw::Initial(row) => RowUpdate::Initial(row),
+                        Raw::Insert(row) => RowUpdate::Insert(row),
+                        Raw::Modify(diff) => RowUpdate::Modify(diff),
+                        Raw::Delete(row) => RowUpdate::Delete(row),
+                    };
+                    (uuid, update)
+                })
+                .collect();
+            updates.tables.insert(table, rows);
+        }
+        Ok(updates)
+    }
+}
+
+/// The notification method and table-updates format of a monitor flavour.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MonitorKind {
+    /// `monitor`, notified with `update`.
+    Monitor,
+    /// `monitor_cond`, notified with `update2`.
+    Cond,
+    /// `monitor_cond_since`, notified with `update3`.
+    CondSince,
+}
+
+impl MonitorKind {
+    pub fn method(&self) -> &'static str {
+        match self {
+            MonitorKind::Monitor => "monitor",
+            MonitorKind::Cond => "monitor_cond",
+            MonitorKind::CondSince => "monitor_cond_since",
+        }
+    }
+}
+
+/// A live monitor: the initial contents, and a [`Stream`] of later changes.
+///
+/// The stream ends when the monitor is cancelled or the connection closes,
+/// or right after an update it could not parse, which it yields as an error:
+/// the changes missed can only be had by setting the monitor up again.
+/// Dropping the monitor stops delivery but leaves it running on the
+/// server; use [`Monitor::cancel`] to tear it down.
+#[derive(Debug)]
+pub struct Monitor {
+    client: Client,
+    id: Value,
+    kind: MonitorKind,
+    initial: TableUpdates,
+    found: bool,
+    last_txn_id: Option<Uuid>,
+    updates: mpsc::UnboundedReceiver<Result<TableUpdates>>,
+}
+
+impl Monitor {
+    pub(crate) async fn start(
+        client: &Client,
+        kind: MonitorKind,
+        db: &str,
+        requests: &MonitorRequests,
+        last_txn_id: Option<Uuid>,
+    ) -> Result<Monitor> {
+        let id = json!(client.next_id());
+        let (tx, rx) = mpsc::unbounded_channel();
+        // register first: updates may follow the reply immediately
+        client.monitors().lock().unwrap().insert(id.to_string(), tx);
+
+        let params = match kind {
+            MonitorKind::CondSince => {
+                json!([db, id, requests, last_txn_id.unwrap_or_default()])
+            }
+            _ => json!([db, id, requests]),
+        };
+        let reply = async {
+            let reply = client.request(kind.method(), params).await?;
+            match kind {
+                MonitorKind::Monitor => Ok((TableUpdates::from_v1(reply, true)?, true, None)),
+                MonitorKind::Cond => Ok((TableUpdates::from_v2(reply)?, true, None)),
+                MonitorKind::CondSince => parse_cond_since_reply(reply),
+            }
+        };
+        let (initial, found, last_txn_id) = match reply.await {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                client.monitors().lock().unwrap().remove(&id.to_string());
+                return Err(e);
+            }
+        };
+        Ok(Monitor {
+            client: client.clone(),
+            id,
+            kind,
+            initial,
+            found,
+            last_txn_id,
+            updates: rx,
+        })
+    }
+
+    /// The monitor id used on the wire.
+    pub fn id(&self) -> &Value {
+        &self.id
+    }
+
+    pub fn kind(&self) -> MonitorKind {
+        self.kind
+    }
+
+    /// The rows that matched when the monitor was set up. For
+    /// `monitor_cond_since` that found the requested transaction, the
+    /// changes since then instead.
+    pub fn initial(&self) -> &TableUpdates {
+        &self.initial
+    }
+
+    /// Whether `monitor_cond_since` could resume from the requested
+    /// transaction id. Always true for the other monitor flavours.
+    pub fn found(&self) -> bool {
+        self.found
+    }
+
+    /// The last transaction id seen, for resuming with `monitor_cond_since`.
+    pub fn last_txn_id(&self) -> Option<Uuid> {
+        self.last_txn_id
+    }
+
+    /// Replaces the conditions (and optionally columns) of a
+    /// `monitor_cond`/`monitor_cond_since` monitor. Rows that start or stop
+    /// matching arrive as inserts and deletes on the stream.
+    // https://docs.openvswitch.org/en/latest/ref/ovsdb-server.7/#monitor-cond-change
+    pub async fn cond_change(&self, requests: &MonitorRequests) -> Result<()> {
+        self.client
+            .request("monitor_cond_change", json!([self.id, self.id, requests]))
+            .await?;
+        Ok(())
+    }
+
+    // https://tools.ietf.org/html/rfc7047#section-4.1.7
+    pub async fn cancel(self) -> Result<()> {
+        self.client
+            .request("monitor_cancel", json!([self.id]))
+            .await?;
+        Ok(())
+    }
+}
+
+impl Stream for Monitor {
+    type Item = Result<TableUpdates>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let poll = this.updates.poll_recv(cx);
+        if let Poll::Ready(Some(Ok(updates))) = &poll {
+            if updates.last_txn_id.is_some() {
+                this.last_txn_id = updates.last_txn_id;
+            }
+        }
+        poll
+    }
+}
+
+impl Drop for Monitor {
+    fn drop(&mut self) {
+        self.client
+            .monitors()
+            .lock()
+            .unwrap()
+            .remove(&self.id.to_string());
+    }
+}
+
+/// `[found, last-txn-id, table-updates2]`
+fn parse_cond_since_reply(reply: Value) -> Result<(TableUpdates, bool, Option<Uuid>)> {
+    let (found, last_txn_id, updates): (bool, Uuid, Value) = serde_json::from_value(reply)?;
+    let mut updates = TableUpdates::from_v2(updates)?;
+    updates.last_txn_id = Some(last_txn_id);
+    Ok((updates, found, Some(last_txn_id)))
+}
+
+/// Hands an `update`, `update2` or `update3` notification to its monitor.
+/// Anything else, including updates for unknown monitors, is given back. An
+/// update that does not parse ends the stream of its monitor with the error.
+pub(crate) fn dispatch(monitors: &Monitors, notification: Notification) -> Option<Notification> {
+    let Some(params) = notification.params.as_array() else {
+        return Some(notification);
+    };
+    let (id, txn, updates) = match (notification.method.as_str(), params.as_slice()) {
+        ("update" | "update2", [id, updates]) => (id, None, updates),
+        ("update3", [id, txn, updates]) => (id, Some(txn), updates),
+        _ => return Some(notification),
+    };
+    let id = id.to_string();
+    let mut monitors = monitors.lock().unwrap();
+    let Some(monitor) = monitors.get(&id) else {
+        return Some(notification);
+    };
+    let parsed = match txn {
+        None if notification.method == "update" => TableUpdates::from_v1(updates.clone(), false),
+        None => TableUpdates::from_v2(updates.clone()),
+        Some(txn) => TableUpdates::from_v2(updates.clone()).and_then(|mut updates| {
+            updates.last_txn_id = Some(serde_json::from_value(txn.clone())?);
+            Ok(updates)
+        }),
+    };
+    let failed = parsed.is_err();
+    let _ = monitor.send(parsed);
+    if failed {
+        monitors.remove(&id);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::Notifications;
+    use crate::codec::JsonCodec;
+    use crate::monitor::*;
+    use futures::{SinkExt, StreamExt};
+    use ovsdb_common::datum::Datum;
+    use ovsdb_common::jsonrpc::{Message, Request, Response};
+    use tokio_util::codec::Framed;
+
+    type Server = Framed<tokio::io::DuplexStream, JsonCodec>;
+
+    const SW0: &str = "6ea8b1ab-7ea1-4d54-9b56-0a2de2e5e1b0";
+    const TXN: &str = "9a2fb0ce-02ee-4a7a-a4b4-1bd2b1a2bd2f";
+
+    fn pair() -> (Client, Notifications, Server) {
+        let (client_side, server_side) = tokio::io::duplex(4096);
+        let (client, notifications) = Client::from_stream(client_side);
+        (
+            client,
+            notifications,
+            Framed::new(server_side, JsonCodec::default()),
+        )
+    }
+
+    async fn next_request(server: &mut Server) -> Request {
+        match server.next().await.unwrap().unwrap() {
+            Message::Request(request) => request,
+            other => panic!("Expected Message::Request, got {:?}", other),
+        }
+    }
+
+    fn switches() -> MonitorRequests {
+        let mut requests = MonitorRequests::new();
+        requests.insert(
+            "Logical_Switch".to_string(),
+            MonitorRequest {
+                columns: Some(vec!["name".to_string()]),
+                ..Default::default()
+            },
+        );
+        requests
+    }
+
+    fn row(name: &str) -> Row {
+        Row::from([("name".to_string(), Datum::from(name))])
+    }
+
+    #[tokio::test]
+    async fn test_monitor() {
+        let (client, _notifications, mut server) = pair();
+        let call = tokio::spawn({
+            let client = client.clone();
+            async move { client.monitor("OVN_Northbound", &switches()).await }
+        });
+        let request = next_request(&mut server).await;
+        assert_eq!(request.method, "monitor");
+        let id = request.params[1].clone();
+        assert_eq!(
+            request.params,
+            json!(["OVN_Northbound", id, {"Logical_Switch": {"columns": ["name"]}}])
+        );
+        let reply = json!({"Logical_Switch": {SW0: {"new": {"name": "sw0"}}}});
+        server
+            .send(Response::ok(request.id, reply).into())
+            .await
+            .unwrap();
+        let mut monitor = call.await.unwrap().unwrap();
+        let sw0: Uuid = SW0.parse().unwrap();
+        assert_eq!(
+            monitor.initial().tables["Logical_Switch"][&sw0],
+            RowUpdate::Initial(row("sw0"))
+        );
+
+        let update =
+            json!({"Logical_Switch": {SW0: {"old": {"name": "sw0"}, "new": {"name": "sw1"}}}});
+        server
+            .send(Notification::new("update", json!([id, update])).into())
+            .await
+            .unwrap();
+        let updates = monitor.next().await.unwrap().unwrap();
+        assert_eq!(
+            updates.iter().collect::<Vec<_>>(),
+            vec![(
+                "Logical_Switch",
+                &sw0,
+                &RowUpdate::Update {
+                    old: row("sw0"),
+                    new: row("sw1")
+                }
+            )]
+        );
+
+        // the stream ends with the connection
+        drop(server);
+        assert!(monitor.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_monitor_cond_and_change() {
+        let (client, mut notifications, mut server) = pair();
+        let call = tokio::spawn({
+            let client = client.clone();
+            async move { client.monitor_cond("OVN_Northbound", &switches()).await }
+        });
+        let request = next_request(&mut server).await;
+        assert_eq!(request.method, "monitor_cond");
+        let id = request.params[1].clone();
+        let reply = json!({"Logical_Switch": {SW0: {"initial": {"name": "sw0"}}}});
+        server
+            .send(Response::ok(request.id, reply).into())
+            .await
+            .unwrap();
+        let mut monitor = call.await.unwrap().unwrap();
+        assert_eq!(monitor.initial().iter().count(), 1);
+
+        // updates for other monitors fall through to the notifications
+        let stray = json!(["other", {"Logical_Switch": {SW0: {"delete": null}}}]);
+        server
+            .send(Notification::new("update2", stray.clone()).into())
+            .await
+            .unwrap();
+        let diff = json!({"Logical_Switch": {SW0: {"modify": {"name": "sw1"}}}});
+        server
+            .send(Notification::new("update2", json!([id, diff])).into())
+            .await
+            .unwrap();
+        let updates = monitor.next().await.unwrap().unwrap();
+        let sw0: Uuid = SW0.parse().unwrap();
+        assert_eq!(
+            updates.tables["Logical_Switch"][&sw0],
+            RowUpdate::Modify(row("sw1"))
+        );
+        assert_eq!(notifications.recv().await.unwrap().params, stray);
+
+        let mut requests = switches();
+        requests.get_mut("Logical_Switch").unwrap().where_ =
+            Some(vec![Condition::eq("name", "sw1")]);
+        let change = tokio::spawn(async move {
+            monitor.cond_change(&requests).await.unwrap();
+            monitor
+        });
+        let request = next_request(&mut server).await;
+        assert_eq!(request.method, "monitor_cond_change");
+        assert_eq!(request.params[0], id);
+        assert_eq!(request.params[1], id);
+        assert_eq!(
+            request.params[2]["Logical_Switch"]["where"],
+            json!([["name", "==", "sw1"]])
+        );
+        server
+            .send(Response::ok(request.id, json!({})).into())
+            .await
+            .unwrap();
+        let monitor = change.await.unwrap();
+
+        let cancel = tokio::spawn(monitor.cancel());
+        let request = next_request(&mut server).await;
+        assert_eq!(request.method, "monitor_cancel");
+        assert_eq!(request.params, json!([id]));
+        server
+            .send(Response::ok(request.id, json!({})).into())
+            .await
+            .unwrap();
+        cancel.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_monitor_cond_since() {
+        let (client, mut notifications, mut server) = pair();
+        let txn: Uuid = TXN.parse().unwrap();
+        let call = tokio::spawn({
+            let client = client.clone();
+            async move {
+                client
+                    .monitor_cond_since("OVN_Northbound", &switches(), Some(txn))
+                    .await
+            }
+        });
+        let request = next_request(&mut server).await;
+        assert_eq!(request.method, "monitor_cond_since");
+        assert_eq!(request.params[3], json!(TXN));
+        let id = request.params[1].clone();
+        server
+            .send(Response::ok(request.id, json!([true, TXN, {}])).into())
+            .await
+            .unwrap();
+        let mut monitor = call.await.unwrap().unwrap();
+        assert!(monitor.found());
+        assert!(monitor.initial().is_empty());
+        assert_eq!(monitor.last_txn_id(), Some(txn));
+
+        let next_txn = Uuid::from_u128(7);
+        let update = json!({"Logical_Switch": {SW0: {"delete": null}}});
+        server
+            .send(Notification::new("update3", json!([id, next_txn, update])).into())
+            .await
+            .unwrap();
+        let updates = monitor.next().await.unwrap().unwrap();
+        assert_eq!(updates.last_txn_id, Some(next_txn));
+        assert_eq!(updates.iter().next().unwrap().2, &RowUpdate::Delete(None));
+        assert_eq!(monitor.last_txn_id(), Some(next_txn));
+
+        // an update that does not parse ends the stream rather than being
+        // skipped, and is not handed to the notifications either
+        let bad = json!({"Logical_Switch": {SW0: {"rename": {"name": "sw1"}}}});
+        let later = json!({"Logical_Switch": {SW0: {"delete": null}}});
+        for (txn, update) in [(8, bad), (9, later)] {
+            server
+                .send(
+                    Notification::new("update3", json!([id, Uuid::from_u128(txn), update])).into(),
+                )
+                .await
+                .unwrap();
+        }
+        assert!(monitor.next().await.unwrap().is_err());
+        assert!(monitor.next().await.is_none());
+        assert_eq!(monitor.last_txn_id(), Some(next_txn));
+        assert_eq!(
+            notifications.recv().await.unwrap().params[1],
+            json!(Uuid::from_u128(9))
+        );
+        assert!(notifications.try_recv().is_err());
+    }
+}
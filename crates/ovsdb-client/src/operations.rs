@@ -0,0 +1,246 @@
+use crate::error::{Error, Result};
+use ovsdb_common::condition::Condition;
+use ovsdb_common::mutation::Mutation;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use uuid::Uuid;
+
+/// A row as sent to or received from the server: column name to JSON value.
+pub type Row = Map<String, Value>;
+
+// database operations
+// https://tools.ietf.org/html/rfc7047#section-5.2
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum Operation {
+    Insert {
+        table: String,
+        row: Row,
+        #[serde(rename = "uuid-name")]
+        #[serde(skip_serializing_if = "Option::is_none")]
+        uuid_name: Option<String>,
+    },
+    Select {
+        table: String,
+        #[serde(rename = "where")]
+        where_: Vec<Condition>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        columns: Option<Vec<String>>,
+    },
+    Update {
+        table: String,
+        #[serde(rename = "where")]
+        where_: Vec<Condition>,
+        row: Row,
+    },
+    Mutate {
+        table: String,
+        #[serde(rename = "where")]
+        where_: Vec<Condition>,
+        mutations: Vec<Mutation>,
+    },
+    Delete {
+        table: String,
+        #[serde(rename = "where")]
+        where_: Vec<Condition>,
+    },
+    Wait {
+        table: String,
+        #[serde(rename = "where")]
+        where_: Vec<Condition>,
+        columns: Vec<String>,
+        until: WaitUntil,
+        rows: Vec<Row>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        timeout: Option<u64>,
+    },
+    Commit {
+        durable: bool,
+    },
+    Abort,
+    Comment {
+        comment: String,
+    },
+    Assert {
+        lock: String,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum WaitUntil {
+    #[serde(rename = "==")]
+    Equal,
+    #[serde(rename = "!=")]
+    NotEqual,
+}
+
+impl Operation {
+    /// The "op" member of the operation on the wire.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Operation::Insert { .. } => "insert",
+            Operation::Select { .. } => "select",
+            Operation::Update { .. } => "update",
+            Operation::Mutate { .. } => "mutate",
+            Operation::Delete { .. } => "delete",
+            Operation::Wait { .. } => "wait",
+            Operation::Commit { .. } => "commit",
+            Operation::Abort => "abort",
+            Operation::Comment { .. } => "comment",
+            Operation::Assert { .. } => "assert",
+        }
+    }
+}
+
+/// Refers to the row inserted with the given "uuid-name" in the same transaction.
+// https://tools.ietf.org/html/rfc7047#section-5.1
+pub fn named_uuid(name: &str) -> Value {
+    json!(["named-uuid", name])
+}
+
+// operation results
+// https://tools.ietf.org/html/rfc7047#section-5.2
+#[derive(Debug, Clone, PartialEq)]
+pub enum OperationResult {
+    /// Result of "insert": the UUID assigned to the new row.
+    Insert { uuid: Uuid },
+    /// Result of "select".
+    Select { rows: Vec<Row> },
+    /// Result of "update", "mutate" and "delete": the number of matched rows.
+    Count(u64),
+    /// Result of "wait", "commit", "abort", "comment" and "assert".
+    Empty,
+}
+
+impl OperationResult {
+    /// Interprets the reply to `op`. The caller has already checked that
+    /// `value` is not an error.
+    pub(crate) fn from_value(op: &Operation, value: Value) -> Result<Self> {
+        let unexpected =
+            |value: &Value| Error::UnexpectedResponse(format!("{} result: {}", op.name(), value));
+        match op {
+            Operation::Insert { .. } => {
+                let uuid = value
+                    .get("uuid")
+                    .and_then(|uuid| uuid.as_array())
+                    .filter(|pair| pair.len() == 2 && pair[0] == "uuid")
+                    .and_then(|pair| pair[1].as_str())
+                    .and_then(|s| Uuid::parse_str(s).ok())
+                    .ok_or_else(|| unexpected(&value))?;
+                Ok(OperationResult::Insert { uuid })
+            }
+            Operation::Select { .. } => {
+                let rows = value.get("rows").ok_or_else(|| unexpected(&value))?;
+                let rows = serde_json::from_value(rows.clone()).map_err(|_| unexpected(&value))?;
+                Ok(OperationResult::Select { rows })
+            }
+            Operation::Update { .. } | Operation::Mutate { .. } | Operation::Delete { .. } => {
+                let count = value
+                    .get("count")
+                    .and_then(|count| count.as_u64())
+                    .ok_or_else(|| unexpected(&value))?;
+                Ok(OperationResult::Count(count))
+            }
+            _ => Ok(OperationResult::Empty),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::operations::*;
+
+    #[test]
+    fn test_serialize_insert() {
+        let mut row = Row::new();
+        row.insert("name".to_string(), json!("sw0"));
+        let op = Operation::Insert {
+            table: "Logical_Switch".to_string(),
+            row,
+            uuid_name: Some("sw0".to_string()),
+        };
+        assert_eq!(
+            serde_json::to_value(&op).unwrap(),
+            json!({
+                "op": "insert",
+                "table": "Logical_Switch",
+                "row": {"name": "sw0"},
+                "uuid-name": "sw0"
+            })
+        );
+    }
+
+    #[test]
+    fn test_serialize_wait() {
+        let op = Operation::Wait {
+            table: "NB_Global".to_string(),
+            where_: vec![],
+            columns: vec!["nb_cfg".to_string()],
+            until: WaitUntil::NotEqual,
+            rows: vec![],
+            timeout: Some(0),
+        };
+        assert_eq!(
+            serde_json::to_value(&op).unwrap(),
+            json!({
+                "op": "wait",
+                "table": "NB_Global",
+                "where": [],
+                "columns": ["nb_cfg"],
+                "until": "!=",
+                "rows": [],
+                "timeout": 0
+            })
+        );
+    }
+
+    #[test]
+    fn test_serialize_mutate() {
+        let op = Operation::Mutate {
+            table: "Port_Group".to_string(),
+            where_: vec![Condition::eq("name", "pg0")],
+            mutations: vec![Mutation::insert(
+                "acls",
+                ovsdb_common::datum::Atom::NamedUuid("acl0".to_string()),
+            )],
+        };
+        assert_eq!(
+            serde_json::to_value(&op).unwrap(),
+            json!({
+                "op": "mutate",
+                "table": "Port_Group",
+                "where": [["name", "==", "pg0"]],
+                "mutations": [["acls", "insert", ["named-uuid", "acl0"]]]
+            })
+        );
+    }
+
+    #[test]
+    fn test_serialize_abort() {
+        assert_eq!(
+            serde_json::to_value(&Operation::Abort).unwrap(),
+            json!({"op": "abort"})
+        );
+    }
+
+    #[test]
+    fn test_insert_result() {
+        let op = Operation::Insert {
+            table: "Logical_Switch".to_string(),
+            row: Row::new(),
+            uuid_name: None,
+        };
+        let result = OperationResult::from_value(
+            &op,
+            json!({"uuid": ["uuid", "36c4b7d2-1a2d-4b8c-9f3e-2c1b7a0e5d11"]}),
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            OperationResult::Insert {
+                uuid: Uuid::parse_str("36c4b7d2-1a2d-4b8c-9f3e-2c1b7a0e5d11").unwrap()
+            }
+        );
+        assert!(OperationResult::from_value(&op, json!({"count": 1})).is_err());
+    }
+}
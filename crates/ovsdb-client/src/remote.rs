@@ -0,0 +1,344 @@
+use crate::error::{Error, Result};
+use crate::ssl::SslOptions;
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpStream;
+#[cfg(unix)]
+use tokio::net::UnixStream;
+
+/// Port used when a `tcp:` or `ssl:` remote does not name one.
+pub const DEFAULT_PORT: u16 = 6640;
+
+/// A byte stream the JSON-RPC session can run over.
+pub trait Connection: AsyncRead + AsyncWrite + Send + Unpin {}
+
+impl<T: AsyncRead + AsyncWrite + Send + Unpin> Connection for T {}
+
+/// An OVSDB connection method, as accepted by `ovsdb-client` and friends.
+///
+/// - `tcp:host[:port]`, with IPv6 hosts written as `tcp:[::1]:6641`
+/// - `ssl:host[:port]`, which needs [`SslOptions`]
+/// - `unix:/path/to/db.sock`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Remote {
+    Tcp { host: String, port: u16 },
+    Ssl { host: String, port: u16 },
+    Unix(PathBuf),
+}
+
+impl Remote {
+    /// Parses a comma-separated list of remotes, as used for the members of
+    /// a cluster: `tcp:10.0.0.1:6641,tcp:10.0.0.2:6641,tcp:10.0.0.3:6641`.
+    pub fn parse_list(s: &str) -> Result<Vec<Remote>> {
+        s.split(',').map(|remote| remote.trim().parse()).collect()
+    }
+
+    pub async fn connect(&self) -> Result<Box<dyn Connection>> {
+        self.connect_with(None).await
+    }
+
+    /// Connects, using `ssl` for `ssl:` remotes. Other remotes ignore it.
+    pub async fn connect_with(&self, ssl: Option<&SslOptions>) -> Result<Box<dyn Connection>> {
+        match self {
+            Remote::Tcp { host, port } => Ok(Box::new(tcp_connect(host, *port).await?)),
+            Remote::Ssl { host, port } => {
+                let ssl = ssl.ok_or_else(|| {
+                    Error::Ssl(format!(
+                        "{} needs a private key, a certificate and a CA certificate",
+                        self
+                    ))
+                })?;
+                let stream = tcp_connect(host, *port).await?;
+                Ok(Box::new(ssl.connect(stream, host).await?))
+            }
+            #[cfg(unix)]
+            Remote::Unix(path) => Ok(Box::new(UnixStream::connect(path).await?)),
+            #[cfg(not(unix))]
+            Remote::Unix(_) => Err(Error::InvalidRemote(self.to_string())),
+        }
+    }
+}
+
+async fn tcp_connect(host: &str, port: u16) -> Result<TcpStream> {
+    let stream = TcpStream::connect((host, port)).await?;
+    stream.set_nodelay(true)?;
+    Ok(stream)
+}
+
+/// Splits `host[:port]`, accepting bracketed IPv6 literals.
+fn parse_host_port(s: &str) -> Option<(String, Option<u16>)> {
+    if let Some(rest) = s.strip_prefix('[') {
+        let (host, rest) = rest.split_once(']')?;
+        let port = match rest {
+            "" => None,
+            _ => Some(rest.strip_prefix(':')?.parse().ok()?),
+        };
+        return Some((host.to_string(), port));
+    }
+    match s.split_once(':') {
+        Some((host, port)) => Some((host.to_string(), Some(port.parse().ok()?))),
+        None => Some((s.to_string(), None)),
+    }
+}
+
+impl FromStr for Remote {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || Error::InvalidRemote(s.to_string());
+        let (method, target) = s.split_once(':').ok_or_else(invalid)?;
+        match method {
+            "tcp" | "ssl" => {
+                let (host, port) = parse_host_port(target).ok_or_else(invalid)?;
+                if host.is_empty() {
+                    return Err(invalid());
+                }
+                let port = port.unwrap_or(DEFAULT_PORT);
+                match method {
+                    "tcp" => Ok(Remote::Tcp { host, port }),
+                    _ => Ok(Remote::Ssl { host, port }),
+                }
+            }
+            "unix" if !target.is_empty() => Ok(Remote::Unix(PathBuf::from(target))),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl fmt::Display for Remote {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Remote::Tcp { host, port } if host.contains(':') => {
+                write!(f, "tcp:[{}]:{}", host, port)
+            }
+            Remote::Tcp { host, port } => write!(f, "tcp:{}:{}", host, port),
+            Remote::Ssl { host, port } if host.contains(':') => {
+                write!(f, "ssl:[{}]:{}", host, port)
+            }
+            Remote::Ssl { host, port } => write!(f, "ssl:{}:{}", host, port),
+            Remote::Unix(path) => write!(f, "unix:{}", path.display()),
+        }
+    }
+}
+
+/// A passive connection method, on which a [`Listener`](crate::listener::Listener)
+/// waits for the server to connect, as with `ovsdb-server --remote` pointed
+/// at a listening client.
+///
+/// - `ptcp:[port][:host]`, listening on every address without a host, and
+///   on the port the system picks with port 0
+/// - `pssl:[port][:host]`, which needs [`SslOptions`]
+/// - `punix:/path/to/db.sock`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PassiveRemote {
+    Ptcp { port: u16, host: Option<String> },
+    Pssl { port: u16, host: Option<String> },
+    Punix(PathBuf),
+}
+
+/// Splits `[port][:host]`, accepting bracketed IPv6 literals.
+fn parse_port_host(s: &str) -> Option<(u16, Option<String>)> {
+    let (port, host) = match s.split_once(':') {
+        Some((port, host)) => (port, Some(host)),
+        None => (s, None),
+    };
+    let port = match port {
+        "" => DEFAULT_PORT,
+        _ => port.parse().ok()?,
+    };
+    let host = match host {
+        Some(host) => {
+            let host = host
+                .strip_prefix('[')
+                .map_or(Some(host), |host| host.strip_suffix(']'))?;
+            if host.is_empty() {
+                return None;
+            }
+            Some(host.to_string())
+        }
+        None => None,
+    };
+    Some((port, host))
+}
+
+impl FromStr for PassiveRemote {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || Error::InvalidRemote(s.to_string());
+        let (method, target) = s.split_once(':').ok_or_else(invalid)?;
+        match method {
+            "ptcp" => {
+                let (port, host) = parse_port_host(target).ok_or_else(invalid)?;
+                Ok(PassiveRemote::Ptcp { port, host })
+            }
+            "pssl" => {
+                let (port, host) = parse_port_host(target).ok_or_else(invalid)?;
+                Ok(PassiveRemote::Pssl { port, host })
+            }
+            "punix" if !target.is_empty() => Ok(PassiveRemote::Punix(PathBuf::from(target))),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl fmt::Display for PassiveRemote {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (method, port, host) = match self {
+            PassiveRemote::Ptcp { port, host } => ("ptcp", port, host),
+            PassiveRemote::Pssl { port, host } => ("pssl", port, host),
+            PassiveRemote::Punix(path) => return write!(f, "punix:{}", path.display()),
+        };
+        match host {
+            Some(host) if host.contains(':') => write!(f, "{}:{}:[{}]", method, port, host),
+            Some(host) => write!(f, "{}:{}:{}", method, port, host),
+            None => write!(f, "{}:{}", method, port),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::remote::*;
+
+    #[test]
+    fn test_parse_tcp() {
+        let remote: Remote = "tcp:127.0.0.1:6641".parse().unwrap();
+        assert_eq!(
+            remote,
+            Remote::Tcp {
+                host: "127.0.0.1".to_string(),
+                port: 6641
+            }
+        );
+        assert_eq!(remote.to_string(), "tcp:127.0.0.1:6641");
+    }
+
+    #[test]
+    fn test_parse_tcp_default_port() {
+        let remote: Remote = "tcp:ovsdb.example.com".parse().unwrap();
+        assert_eq!(
+            remote,
+            Remote::Tcp {
+                host: "ovsdb.example.com".to_string(),
+                port: DEFAULT_PORT
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_tcp_ipv6() {
+        let remote: Remote = "tcp:[::1]:6642".parse().unwrap();
+        assert_eq!(
+            remote,
+            Remote::Tcp {
+                host: "::1".to_string(),
+                port: 6642
+            }
+        );
+        assert_eq!(remote.to_string(), "tcp:[::1]:6642");
+    }
+
+    #[test]
+    fn test_parse_ssl() {
+        let remote: Remote = "ssl:[fd00::1]".parse().unwrap();
+        assert_eq!(
+            remote,
+            Remote::Ssl {
+                host: "fd00::1".to_string(),
+                port: DEFAULT_PORT
+            }
+        );
+        assert_eq!(remote.to_string(), "ssl:[fd00::1]:6640");
+        assert_eq!(
+            "ssl:10.0.0.1:6641".parse::<Remote>().unwrap().to_string(),
+            "ssl:10.0.0.1:6641"
+        );
+    }
+
+    #[test]
+    fn test_parse_unix() {
+        let remote: Remote = "unix:/var/run/ovn/ovnnb_db.sock".parse().unwrap();
+        assert_eq!(
+            remote,
+            Remote::Unix(PathBuf::from("/var/run/ovn/ovnnb_db.sock"))
+        );
+    }
+
+    #[test]
+    fn test_parse_list() {
+        let remotes = Remote::parse_list("tcp:10.0.0.1:6641, unix:/run/db.sock").unwrap();
+        assert_eq!(
+            remotes,
+            vec![
+                Remote::Tcp {
+                    host: "10.0.0.1".to_string(),
+                    port: 6641
+                },
+                Remote::Unix(PathBuf::from("/run/db.sock")),
+            ]
+        );
+        assert!(Remote::parse_list("tcp:10.0.0.1:6641,").is_err());
+    }
+
+    #[test]
+    fn test_parse_passive() {
+        let remote: PassiveRemote = "ptcp:6641:127.0.0.1".parse().unwrap();
+        assert_eq!(
+            remote,
+            PassiveRemote::Ptcp {
+                port: 6641,
+                host: Some("127.0.0.1".to_string())
+            }
+        );
+        assert_eq!(remote.to_string(), "ptcp:6641:127.0.0.1");
+
+        let remote: PassiveRemote = "pssl::[::1]".parse().unwrap();
+        assert_eq!(
+            remote,
+            PassiveRemote::Pssl {
+                port: DEFAULT_PORT,
+                host: Some("::1".to_string())
+            }
+        );
+        assert_eq!(remote.to_string(), "pssl:6640:[::1]");
+
+        assert_eq!(
+            "ptcp:".parse::<PassiveRemote>().unwrap(),
+            PassiveRemote::Ptcp {
+                port: DEFAULT_PORT,
+                host: None
+            }
+        );
+        assert_eq!(
+            "punix:/run/ovn-agent.sock"
+                .parse::<PassiveRemote>()
+                .unwrap(),
+            PassiveRemote::Punix(PathBuf::from("/run/ovn-agent.sock"))
+        );
+        for s in ["tcp:1.2.3.4:5", "ptcp", "ptcp:port", "ptcp:6641:", "punix:"] {
+            assert!(
+                s.parse::<PassiveRemote>().is_err(),
+                "{} should not parse",
+                s
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        for s in [
+            "",
+            "tcp",
+            "tcp:",
+            "tcp:host:port",
+            "ssl:",
+            "unix:",
+            "udp:1.2.3.4:5",
+        ] {
+            assert!(s.parse::<Remote>().is_err(), "{} should not parse", s);
+        }
+    }
+}
@@ -0,0 +1,169 @@
+//! The `_Server` database every `ovsdb-server` serves about itself, which
+//! tells clustered members apart from standalone servers.
+// https://docs.openvswitch.org/en/latest/ref/ovsdb-server.7/#database-the-server-database
+
+use crate::monitor::{MonitorRequest, MonitorRequests};
+use ovsdb_common::condition::Condition;
+use ovsdb_common::row::{column, ColumnError, Row, TableRow, ToDatum};
+use ovsdb_schema::Schema;
+use uuid::Uuid;
+
+/// The text of the `_Server` schema, so that monitoring it does not take a
+/// `get_schema` round trip.
+pub const SCHEMA: &str = include_str!("../schemas/_server.ovsschema");
+
+pub const DB: &str = "_Server";
+
+/// The parsed [`SCHEMA`].
+pub fn schema() -> Schema {
+    serde_json::from_str(SCHEMA).expect("the bundled _Server schema is valid")
+}
+
+/// A row of `_Server.Database`: what one server knows about one of its
+/// databases.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Database {
+    pub uuid: Uuid,
+    pub name: String,
+    /// `standalone`, `clustered` or `relay`.
+    pub model: String,
+    /// Whether a clustered member is part of a majority of its cluster.
+    /// Always true for the other models.
+    pub connected: bool,
+    /// Whether this member is the cluster leader. Always true for the
+    /// other models.
+    pub leader: bool,
+    pub schema: Option<String>,
+    /// The cluster id, once known.
+    pub cid: Option<Uuid>,
+    /// This member's server id.
+    pub sid: Option<Uuid>,
+    /// The index of the last Raft log entry this member has seen. It only
+    /// grows, so a member reporting less than another one is behind.
+    pub index: Option<i64>,
+}
+
+impl Database {
+    pub fn is_clustered(&self) -> bool {
+        self.model == "clustered"
+    }
+
+    /// Why a session should look for another member rather than use this
+    /// one, if it should. `min_index` is the highest index seen so far.
+    pub(crate) fn unsuitable(&self, leader_only: bool, min_index: Option<i64>) -> Option<String> {
+        if !self.is_clustered() {
+            return None;
+        }
+        if !self.connected {
+            return Some(format!(
+                "database {} is not connected to its cluster",
+                self.name
+            ));
+        }
+        if leader_only && !self.leader {
+            return Some(format!("database {} is not the cluster leader", self.name));
+        }
+        match (self.index, min_index) {
+            (Some(index), Some(min_index)) if index < min_index => Some(format!(
+                "database {} is stale: index {} is behind {}",
+                self.name, index, min_index
+            )),
+            _ => None,
+        }
+    }
+}
+
+impl TableRow for Database {
+    const TABLE: &'static str = "Database";
+
+    fn uuid(&self) -> Uuid {
+        self.uuid
+    }
+
+    fn from_row(uuid: Uuid, row: &Row) -> Result<Self, ColumnError> {
+        Ok(Database {
+            uuid,
+            name: column(row, Self::TABLE, "name")?,
+            model: column(row, Self::TABLE, "model")?,
+            connected: column(row, Self::TABLE, "connected")?,
+            leader: column(row, Self::TABLE, "leader")?,
+            schema: column(row, Self::TABLE, "schema")?,
+            cid: column(row, Self::TABLE, "cid")?,
+            sid: column(row, Self::TABLE, "sid")?,
+            index: column(row, Self::TABLE, "index")?,
+        })
+    }
+
+    fn to_row(&self) -> Row {
+        Row::from([
+            ("name".to_string(), self.name.to_datum()),
+            ("model".to_string(), self.model.to_datum()),
+            ("connected".to_string(), self.connected.to_datum()),
+            ("leader".to_string(), self.leader.to_datum()),
+            ("schema".to_string(), self.schema.to_datum()),
+            ("cid".to_string(), self.cid.to_datum()),
+            ("sid".to_string(), self.sid.to_datum()),
+            ("index".to_string(), self.index.to_datum()),
+        ])
+    }
+}
+
+/// Monitor requests for the `_Server.Database` row of `db`.
+pub(crate) fn monitor_requests(db: &str) -> MonitorRequests {
+    let request = MonitorRequest {
+        where_: Some(vec![Condition::eq("name", db)]),
+        ..Default::default()
+    };
+    MonitorRequests::from([(Database::TABLE.to_string(), request)])
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::server::*;
+
+    fn member(leader: bool, index: i64) -> Database {
+        Database {
+            uuid: Uuid::from_u128(1),
+            name: "OVN_Northbound".to_string(),
+            model: "clustered".to_string(),
+            connected: true,
+            leader,
+            schema: None,
+            cid: Some(Uuid::from_u128(2)),
+            sid: Some(Uuid::from_u128(3)),
+            index: Some(index),
+        }
+    }
+
+    #[test]
+    fn test_bundled_schema() {
+        let schema = schema();
+        assert_eq!(schema.name, DB);
+        assert_eq!(schema.verify_cksum(SCHEMA), Ok(()));
+        assert!(schema.validate().is_empty());
+        let row = member(true, 7).to_row();
+        assert_eq!(
+            Database::from_row(Uuid::from_u128(1), &row),
+            Ok(member(true, 7))
+        );
+    }
+
+    #[test]
+    fn test_unsuitable() {
+        assert_eq!(member(false, 7).unsuitable(false, None), None);
+        assert!(member(false, 7).unsuitable(true, None).is_some());
+        assert_eq!(member(true, 7).unsuitable(true, Some(7)), None);
+        assert!(member(true, 6).unsuitable(false, Some(7)).is_some());
+
+        let disconnected = Database {
+            connected: false,
+            ..member(true, 7)
+        };
+        assert!(disconnected.unsuitable(false, None).is_some());
+        let standalone = Database {
+            model: "standalone".to_string(),
+            ..disconnected
+        };
+        assert_eq!(standalone.unsuitable(true, Some(100)), None);
+    }
+}
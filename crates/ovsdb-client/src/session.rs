@@ -0,0 +1,777 @@
+use crate::cache::Cache;
+use crate::client::{Client, Probe};
+use crate::error::{Error, Result};
+use crate::monitor::{Monitor, MonitorKind, MonitorRequests};
+use crate::remote::Remote;
+use crate::server;
+use crate::ssl::SslOptions;
+use futures::StreamExt;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Delays between reconnection attempts: `initial`, doubling up to `max`.
+/// Each delay is randomly shortened by up to half, so that many clients
+/// losing the same server do not come back in lockstep.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Backoff {
+    pub initial: Duration,
+    pub max: Duration,
+}
+
+impl Default for Backoff {
+    /// The defaults of OVS's `reconnect` module: 1 s up to 8 s.
+    fn default() -> Self {
+        Backoff {
+            initial: Duration::from_secs(1),
+            max: Duration::from_secs(8),
+        }
+    }
+}
+
+impl Backoff {
+    /// The delay before reconnection attempt `attempt`, counting from 0.
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = 1u32.checked_shl(attempt).unwrap_or(u32::MAX);
+        let base = self.initial.saturating_mul(factor).min(self.max);
+        let half = base / 2;
+        half + half.mul_f64(fastrand::f64())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SessionOptions {
+    pub backoff: Backoff,
+    /// Only use the leader of a clustered database, so that transactions
+    /// go straight to it instead of being forwarded by a follower.
+    pub leader_only: bool,
+    /// Needed for `ssl:` remotes.
+    pub ssl: Option<SslOptions>,
+    /// The inactivity probe of each connection; a connection it finds dead
+    /// is reconnected like a lost one. On by default, as in OVS.
+    pub probe: Option<Probe>,
+}
+
+impl Default for SessionOptions {
+    fn default() -> Self {
+        SessionOptions {
+            backoff: Backoff::default(),
+            leader_only: false,
+            ssl: None,
+            probe: Some(Probe::default()),
+        }
+    }
+}
+
+/// What [`Session::next_event`] did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    /// A batch of changes was applied to the cache; see [`Cache::changes`].
+    Update,
+    /// The connection was lost, or the server stopped being suitable. The
+    /// cache keeps its contents, and the next call to
+    /// [`Session::next_event`] reconnects.
+    Disconnected,
+    /// The connection and the monitor are back, and [`Cache::changes`] holds
+    /// what changed in the meantime. With `full_resync`, the server could
+    /// not resume from the last transaction seen, so the cache was
+    /// reconciled against the full contents of the database.
+    Reconnected { full_resync: bool },
+}
+
+/// A monitor on one database that survives reconnections, feeding a
+/// [`Cache`], much like OVS's `ovsdb-idl`.
+///
+/// After a reconnect the monitor is re-established with
+/// `monitor_cond_since` and the last transaction id seen, so that only the
+/// changes missed are sent. Servers without `monitor_cond_since` get
+/// `monitor_cond` and a full resync every time.
+///
+/// A session may be given several remotes, such as the members of a
+/// cluster. It watches the database's row in `_Server.Database` on the
+/// member it uses, and moves on to the next remote when that member loses
+/// touch with its cluster, falls behind a member already seen, or, with
+/// [`SessionOptions::leader_only`], is not the leader.
+///
+/// ```no_run
+/// # async fn example() -> ovsdb_client::Result<()> {
+/// use ovsdb_client::session::{Event, Session, SessionOptions};
+/// use ovsdb_client::MonitorRequests;
+///
+/// let mut requests = MonitorRequests::new();
+/// requests.insert("Logical_Switch".to_string(), Default::default());
+/// let options = SessionOptions {
+///     leader_only: true,
+///     ..Default::default()
+/// };
+/// let mut session = Session::connect(
+///     "tcp:10.0.0.1:6641,tcp:10.0.0.2:6641,tcp:10.0.0.3:6641",
+///     "OVN_Northbound",
+///     requests,
+///     options,
+/// )
+/// .await?;
+/// loop {
+///     match session.next_event().await? {
+///         Event::Update | Event::Reconnected { .. } => {
+///             println!("{} switches", session.cache().table("Logical_Switch").unwrap().len());
+///         }
+///         Event::Disconnected => println!("reconnecting"),
+///     }
+/// }
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Session {
+    remotes: Vec<Remote>,
+    /// Index into `remotes` of the one in use, or last tried.
+    current: usize,
+    db: String,
+    requests: MonitorRequests,
+    options: SessionOptions,
+    cache: Cache,
+    client: Option<Client>,
+    monitor: Option<Monitor>,
+    last_txn_id: Option<Uuid>,
+    /// Cleared once the server turns out not to know `monitor_cond_since`.
+    cond_since: bool,
+    /// The `_Server.Database` row of `db` on the current server. `None` for
+    /// servers without a `_Server` database.
+    server: Option<Monitor>,
+    server_cache: Cache,
+    /// The highest Raft index seen on any member.
+    min_index: Option<i64>,
+}
+
+impl Session {
+    /// Connects to the first suitable server of `remotes`, a comma-separated
+    /// list, fetches the schema of `db` and sets up the monitor. Each remote
+    /// is tried once; if none works, the last error is returned.
+    pub async fn connect(
+        remotes: &str,
+        db: &str,
+        requests: MonitorRequests,
+        options: SessionOptions,
+    ) -> Result<Session> {
+        let remotes = Remote::parse_list(remotes)?;
+        let mut last_error = None;
+        for current in 0..remotes.len() {
+            let fetched = async {
+                let stream = remotes[current].connect_with(options.ssl.as_ref()).await?;
+                let (client, _notifications) = Client::from_stream(stream);
+                client.set_probe(options.probe);
+                let schema = client.get_schema(db).await?;
+                Ok::<_, Error>((client, schema))
+            };
+            let (client, schema) = match fetched.await {
+                Ok(fetched) => fetched,
+                Err(e) => {
+                    last_error = Some(e);
+                    continue;
+                }
+            };
+            let cache = Cache::for_requests(schema, &requests);
+            let mut session = Session {
+                remotes,
+                current,
+                db: db.to_string(),
+                requests,
+                options,
+                cache,
+                client: None,
+                monitor: None,
+                last_txn_id: None,
+                cond_since: true,
+                server: None,
+                server_cache: Cache::new(server::schema()),
+                min_index: None,
+            };
+            let mut resumed = session.resume(client).await;
+            for _ in 1..session.remotes.len() - current {
+                if resumed.is_ok() {
+                    break;
+                }
+                session.current += 1;
+                resumed = session.connect_current().await;
+            }
+            return resumed.map(|_| session);
+        }
+        Err(last_error.expect("a remote list is never empty"))
+    }
+
+    pub fn cache(&self) -> &Cache {
+        &self.cache
+    }
+
+    /// Mutable access to the cache, e.g. to [`Cache::add_index`].
+    pub fn cache_mut(&mut self) -> &mut Cache {
+        &mut self.cache
+    }
+
+    /// The current connection, for transactions. `None` while disconnected.
+    /// With [`SessionOptions::leader_only`], this is the cluster leader.
+    pub fn client(&self) -> Option<&Client> {
+        self.client.as_ref()
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.monitor.is_some()
+    }
+
+    /// The remote in use, or the one last tried while disconnected.
+    pub fn remote(&self) -> &Remote {
+        &self.remotes[self.current]
+    }
+
+    /// What the current server reports about the database, if it has a
+    /// `_Server` database.
+    pub fn server_database(&self) -> Option<server::Database> {
+        self.server.as_ref()?;
+        self.server_cache
+            .rows::<server::Database>()
+            .find_map(|row| row.ok())
+    }
+
+    /// The last transaction id seen, which a reconnect resumes from.
+    pub fn last_txn_id(&self) -> Option<Uuid> {
+        self.last_txn_id
+    }
+
+    /// Waits for the next batch of changes, or reconnects if disconnected.
+    /// Reconnection attempts go round the remotes, with
+    /// [`SessionOptions::backoff`] growing after each full round, until one
+    /// succeeds or fails in a way that trying again would not fix, such as
+    /// the server refusing the monitor request. That error is returned, and
+    /// the next call starts over.
+    ///
+    /// An update that does not parse or does not fit the cache drops the
+    /// connection and is returned as an error, as is a message from the
+    /// server that cannot be read at all; the next call reconnects with a
+    /// full resync.
+    pub async fn next_event(&mut self) -> Result<Event> {
+        loop {
+            let Some(monitor) = &mut self.monitor else {
+                return self.reconnect().await;
+            };
+            let (updates, from_server) = match &mut self.server {
+                // data first: it is still buffered when the connection drops
+                Some(server) => tokio::select! {
+                    biased;
+                    updates = monitor.next() => (updates, false),
+                    updates = server.next() => (updates, true),
+                },
+                None => (monitor.next().await, false),
+            };
+            let updates = match updates {
+                Some(Ok(updates)) => updates,
+                // a change the cache missed: like one that does not fit it
+                Some(Err(e)) if !from_server => {
+                    self.disconnect();
+                    self.last_txn_id = None;
+                    return Err(e);
+                }
+                _ => {
+                    self.disconnect();
+                    return Ok(Event::Disconnected);
+                }
+            };
+            if from_server {
+                let suitable = match self.server_cache.apply(&updates) {
+                    Ok(()) => self.check_server().is_ok(),
+                    Err(_) => false,
+                };
+                if !suitable {
+                    self.disconnect();
+                    return Ok(Event::Disconnected);
+                }
+                continue;
+            }
+            if let Err(e) = self.cache.apply(&updates) {
+                self.disconnect();
+                self.last_txn_id = None;
+                return Err(e);
+            }
+            self.last_txn_id = updates.last_txn_id.or(self.last_txn_id);
+            return Ok(Event::Update);
+        }
+    }
+
+    fn disconnect(&mut self) {
+        self.monitor = None;
+        self.server = None;
+        self.client = None;
+    }
+
+    async fn reconnect(&mut self) -> Result<Event> {
+        let rounds = self.remotes.len() as u32;
+        let mut attempt = 0;
+        loop {
+            tokio::time::sleep(self.options.backoff.delay(attempt / rounds)).await;
+            self.current = (self.current + 1) % self.remotes.len();
+            match self.connect_current().await {
+                Ok(full_resync) => return Ok(Event::Reconnected { full_resync }),
+                Err(e) if is_transient(&e) => attempt = attempt.saturating_add(1),
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    async fn connect_current(&mut self) -> Result<bool> {
+        let stream = self.remotes[self.current]
+            .connect_with(self.options.ssl.as_ref())
+            .await?;
+        let (client, _notifications) = Client::from_stream(stream);
+        client.set_probe(self.options.probe);
+        self.resume(client).await
+    }
+
+    /// Checks the server, then sets up the monitor on a fresh connection and
+    /// brings the cache up to date. Returns whether that took a full resync.
+    async fn resume(&mut self, client: Client) -> Result<bool> {
+        let resumed = self.try_resume(client).await;
+        if let Err(e) = &resumed {
+            // closes the connection
+            self.disconnect();
+            if matches!(e, Error::InconsistentUpdate(_)) {
+                self.last_txn_id = None;
+            }
+        }
+        resumed
+    }
+
+    async fn try_resume(&mut self, client: Client) -> Result<bool> {
+        self.watch_server(&client).await?;
+        let monitor = match self.cond_since {
+            true => {
+                let since = client
+                    .monitor_cond_since(&self.db, &self.requests, self.last_txn_id)
+                    .await;
+                match since {
+                    Err(Error::Rpc(e)) if e.error() == "unknown method" => {
+                        self.cond_since = false;
+                        client.monitor_cond(&self.db, &self.requests).await?
+                    }
+                    other => other?,
+                }
+            }
+            false => client.monitor_cond(&self.db, &self.requests).await?,
+        };
+
+        let full_resync = monitor.kind() != MonitorKind::CondSince || !monitor.found();
+        match full_resync {
+            true => self.cache.resync(monitor.initial())?,
+            false => self.cache.apply(monitor.initial())?,
+        }
+        self.last_txn_id = monitor.last_txn_id();
+        self.client = Some(client);
+        self.monitor = Some(monitor);
+        Ok(full_resync)
+    }
+
+    /// Monitors the `_Server.Database` row of `db` and checks it. Servers
+    /// without a `_Server` database are taken as standalone.
+    async fn watch_server(&mut self, client: &Client) -> Result<()> {
+        self.server = None;
+        let requests = server::monitor_requests(&self.db);
+        let monitor = match client.monitor_cond(server::DB, &requests).await {
+            Ok(monitor) => monitor,
+            Err(Error::Rpc(_)) => return Ok(()),
+            Err(e) => return Err(e),
+        };
+        self.server_cache.resync(monitor.initial())?;
+        self.server = Some(monitor);
+        self.check_server()
+    }
+
+    fn check_server(&mut self) -> Result<()> {
+        let unsuitable = |reason: String| Error::UnsuitableServer {
+            remote: self.remotes[self.current].to_string(),
+            reason,
+        };
+        let Some(database) = self.server_database() else {
+            return Err(unsuitable(format!("server has no database {}", self.db)));
+        };
+        if let Some(reason) = database.unsuitable(self.options.leader_only, self.min_index) {
+            return Err(unsuitable(reason));
+        }
+        self.min_index = self.min_index.max(database.index);
+        Ok(())
+    }
+}
+
+/// Whether the error may go away by trying again, or another remote: lost
+/// connections, servers that are not suitable yet, and garbled messages.
+fn is_transient(error: &Error) -> bool {
+    matches!(
+        error,
+        Error::Io(_)
+            | Error::Json(_)
+            | Error::MessageTooLong(_)
+            | Error::Ssl(_)
+            | Error::UnexpectedResponse(_)
+            | Error::UnsuitableServer { .. }
+            | Error::Disconnected
+            | Error::ReadFailed(_)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::codec::JsonCodec;
+    use crate::session::*;
+    use futures::SinkExt;
+    use ovsdb_common::datum::Datum;
+    use ovsdb_common::jsonrpc::{Message, Notification, Request, Response};
+    use serde_json::{json, Value};
+    use tokio::net::{TcpListener, TcpStream};
+    use tokio_util::codec::Framed;
+
+    type Server = Framed<TcpStream, JsonCodec>;
+
+    const SW0: &str = "00000000-0000-0000-0000-000000000001";
+    const SW1: &str = "00000000-0000-0000-0000-000000000002";
+
+    fn txn(n: u128) -> Uuid {
+        Uuid::from_u128(0x100 + n)
+    }
+
+    fn schema() -> Value {
+        json!({
+            "name": "Test",
+            "tables": {"Logical_Switch": {"columns": {"name": {"type": "string"}}}}
+        })
+    }
+
+    fn requests() -> MonitorRequests {
+        let mut requests = MonitorRequests::new();
+        requests.insert("Logical_Switch".to_string(), Default::default());
+        requests
+    }
+
+    fn options() -> SessionOptions {
+        SessionOptions {
+            backoff: Backoff {
+                initial: Duration::from_millis(10),
+                max: Duration::from_millis(20),
+            },
+            leader_only: false,
+            ssl: None,
+            probe: None,
+        }
+    }
+
+    async fn accept(listener: &TcpListener) -> Server {
+        let (stream, _) = listener.accept().await.unwrap();
+        Framed::new(stream, JsonCodec::default())
+    }
+
+    async fn next_request(server: &mut Server) -> Request {
+        match server.next().await.unwrap().unwrap() {
+            Message::Request(request) => request,
+            other => panic!("Expected Message::Request, got {:?}", other),
+        }
+    }
+
+    /// Answers the `_Server.Database` monitor with `database`, or with an
+    /// error if `None`. Returns the monitor id.
+    async fn server_database(server: &mut Server, database: Option<Value>) -> Value {
+        let request = next_request(server).await;
+        assert_eq!(request.method, "monitor_cond");
+        assert_eq!(request.params[0], json!("_Server"));
+        let id = request.params[1].clone();
+        let response = match database {
+            Some(row) => Response::ok(request.id, json!({"Database": {SW0: {"initial": row}}})),
+            None => {
+                let error = ovsdb_common::error::Error::new("unknown database", None);
+                Response::err(request.id, error)
+            }
+        };
+        server.send(response.into()).await.unwrap();
+        id
+    }
+
+    fn member(leader: bool, index: i64) -> Value {
+        json!({"name": "Test", "model": "clustered", "connected": true,
+               "leader": leader, "index": index})
+    }
+
+    /// Answers `monitor_cond_since`, checking the transaction id it resumes from.
+    async fn monitor_cond_since(server: &mut Server, since: Option<Uuid>, reply: Value) -> Value {
+        let request = next_request(server).await;
+        assert_eq!(request.method, "monitor_cond_since");
+        assert_eq!(request.params[3], json!(since.unwrap_or_default()));
+        let id = request.params[1].clone();
+        server
+            .send(Response::ok(request.id, reply).into())
+            .await
+            .unwrap();
+        id
+    }
+
+    fn names(session: &Session) -> Vec<Datum> {
+        let table = session.cache().table("Logical_Switch").unwrap();
+        let mut names: Vec<Datum> = table.rows().map(|(_, row)| row["name"].clone()).collect();
+        names.sort();
+        names
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_resumes_monitor() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = tokio::spawn(async move {
+            let mut server = accept(&listener).await;
+            let request = next_request(&mut server).await;
+            assert_eq!(request.method, "get_schema");
+            server
+                .send(Response::ok(request.id, schema()).into())
+                .await
+                .unwrap();
+            let standalone = json!({"name": "Test", "model": "standalone",
+                                    "connected": true, "leader": true});
+            server_database(&mut server, Some(standalone)).await;
+            let initial = json!({"Logical_Switch": {SW0: {"initial": {"name": "sw0"}}}});
+            let id = monitor_cond_since(&mut server, None, json!([false, txn(1), initial])).await;
+            let insert = json!({"Logical_Switch": {SW1: {"insert": {"name": "sw1"}}}});
+            server
+                .send(Notification::new("update3", json!([id, txn(2), insert])).into())
+                .await
+                .unwrap();
+            drop(server);
+
+            // resumes from the last update3
+            let mut server = accept(&listener).await;
+            server_database(&mut server, None).await;
+            let missed = json!({"Logical_Switch": {SW0: {"modify": {"name": "sw9"}}}});
+            monitor_cond_since(&mut server, Some(txn(2)), json!([true, txn(3), missed])).await;
+            drop(server);
+
+            // the server lost its history: full contents, without sw1
+            let mut server = accept(&listener).await;
+            server_database(&mut server, None).await;
+            let full = json!({"Logical_Switch": {SW0: {"initial": {"name": "sw9"}}}});
+            monitor_cond_since(&mut server, Some(txn(3)), json!([false, txn(4), full])).await;
+            server
+        });
+
+        let remote = format!("tcp:127.0.0.1:{}", port);
+        let mut session = Session::connect(&remote, "Test", requests(), options())
+            .await
+            .unwrap();
+        assert_eq!(names(&session), vec![Datum::from("sw0")]);
+        assert_eq!(session.next_event().await.unwrap(), Event::Update);
+        assert_eq!(session.last_txn_id(), Some(txn(2)));
+
+        assert_eq!(session.next_event().await.unwrap(), Event::Disconnected);
+        assert!(session.client().is_none());
+        assert_eq!(
+            session.next_event().await.unwrap(),
+            Event::Reconnected { full_resync: false }
+        );
+        assert_eq!(
+            names(&session),
+            vec![Datum::from("sw1"), Datum::from("sw9")]
+        );
+
+        assert_eq!(session.next_event().await.unwrap(), Event::Disconnected);
+        assert_eq!(
+            session.next_event().await.unwrap(),
+            Event::Reconnected { full_resync: true }
+        );
+        assert_eq!(names(&session), vec![Datum::from("sw9")]);
+        let changes: Vec<_> = session.cache().changes().collect();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].1, &Uuid::parse_str(SW1).unwrap());
+        assert_eq!(session.last_txn_id(), Some(txn(4)));
+        assert!(session.is_connected());
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_falls_back_to_monitor_cond() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = tokio::spawn(async move {
+            let mut server = accept(&listener).await;
+            let request = next_request(&mut server).await;
+            server
+                .send(Response::ok(request.id, schema()).into())
+                .await
+                .unwrap();
+            server_database(&mut server, None).await;
+            let request = next_request(&mut server).await;
+            assert_eq!(request.method, "monitor_cond_since");
+            let error = ovsdb_common::error::Error::new("unknown method", None);
+            server
+                .send(Response::err(request.id, error).into())
+                .await
+                .unwrap();
+            let request = next_request(&mut server).await;
+            assert_eq!(request.method, "monitor_cond");
+            let initial = json!({"Logical_Switch": {SW0: {"initial": {"name": "sw0"}}}});
+            server
+                .send(Response::ok(request.id, initial).into())
+                .await
+                .unwrap();
+            drop(server);
+
+            let mut server = accept(&listener).await;
+            server_database(&mut server, None).await;
+            let request = next_request(&mut server).await;
+            assert_eq!(request.method, "monitor_cond");
+            server
+                .send(Response::ok(request.id, json!({})).into())
+                .await
+                .unwrap();
+            server
+        });
+
+        let remote = format!("tcp:127.0.0.1:{}", port);
+        let mut session = Session::connect(&remote, "Test", requests(), options())
+            .await
+            .unwrap();
+        assert_eq!(names(&session), vec![Datum::from("sw0")]);
+        assert_eq!(session.next_event().await.unwrap(), Event::Disconnected);
+        assert_eq!(
+            session.next_event().await.unwrap(),
+            Event::Reconnected { full_resync: true }
+        );
+        assert!(names(&session).is_empty());
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_on_lasting_errors() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = tokio::spawn(async move {
+            let mut server = accept(&listener).await;
+            let request = next_request(&mut server).await;
+            server
+                .send(Response::ok(request.id, schema()).into())
+                .await
+                .unwrap();
+            server_database(&mut server, None).await;
+            let initial = json!({"Logical_Switch": {SW0: {"initial": {"name": "sw0"}}}});
+            monitor_cond_since(&mut server, None, json!([false, txn(1), initial])).await;
+            drop(server);
+
+            // an update of a table the session does not know
+            let mut server = accept(&listener).await;
+            server_database(&mut server, None).await;
+            let missed = json!({"Port": {SW1: {"insert": {}}}});
+            monitor_cond_since(&mut server, Some(txn(1)), json!([true, txn(2), missed])).await;
+            assert!(server.next().await.is_none());
+
+            // an error other than "unknown method" does not fall back
+            let mut server = accept(&listener).await;
+            server_database(&mut server, None).await;
+            let request = next_request(&mut server).await;
+            assert_eq!(request.method, "monitor_cond_since");
+            assert_eq!(request.params[3], json!(Uuid::default()));
+            let error = ovsdb_common::error::Error::new("syntax error", None);
+            server
+                .send(Response::err(request.id, error).into())
+                .await
+                .unwrap();
+            assert!(server.next().await.is_none());
+        });
+
+        let remote = format!("tcp:127.0.0.1:{}", port);
+        let mut session = Session::connect(&remote, "Test", requests(), options())
+            .await
+            .unwrap();
+        assert_eq!(session.next_event().await.unwrap(), Event::Disconnected);
+        assert!(matches!(
+            session.next_event().await,
+            Err(Error::InconsistentUpdate(_))
+        ));
+        assert!(!session.is_connected());
+        assert!(matches!(session.next_event().await, Err(Error::Rpc(_))));
+        assert!(!session.is_connected());
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_leader_only() {
+        let a = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let b = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let remotes = format!(
+            "tcp:127.0.0.1:{},tcp:127.0.0.1:{}",
+            a.local_addr().unwrap().port(),
+            b.local_addr().unwrap().port()
+        );
+        let server = tokio::spawn(async move {
+            // a follower: the schema comes from it, the data does not
+            let mut server = accept(&a).await;
+            let request = next_request(&mut server).await;
+            server
+                .send(Response::ok(request.id, schema()).into())
+                .await
+                .unwrap();
+            server_database(&mut server, Some(member(false, 5))).await;
+            assert!(server.next().await.is_none());
+
+            let mut server = accept(&b).await;
+            let id = server_database(&mut server, Some(member(true, 6))).await;
+            let initial = json!({"Logical_Switch": {SW0: {"initial": {"name": "sw0"}}}});
+            monitor_cond_since(&mut server, None, json!([false, txn(1), initial])).await;
+            // b loses the leadership
+            let modify = json!({"Database": {SW0: {"modify": {"leader": false}}}});
+            server
+                .send(Notification::new("update2", json!([id, modify])).into())
+                .await
+                .unwrap();
+            assert!(server.next().await.is_none());
+
+            // a took over, but has not caught up with what b had
+            let mut server = accept(&a).await;
+            server_database(&mut server, Some(member(true, 5))).await;
+            assert!(server.next().await.is_none());
+            let mut server = accept(&b).await;
+            server_database(&mut server, Some(member(false, 6))).await;
+            assert!(server.next().await.is_none());
+
+            let mut server = accept(&a).await;
+            server_database(&mut server, Some(member(true, 7))).await;
+            monitor_cond_since(&mut server, Some(txn(1)), json!([true, txn(2), {}])).await;
+            server
+        });
+
+        let options = SessionOptions {
+            leader_only: true,
+            ..options()
+        };
+        let mut session = Session::connect(&remotes, "Test", requests(), options)
+            .await
+            .unwrap();
+        assert_eq!(
+            session.remote().to_string(),
+            remotes.split(',').nth(1).unwrap()
+        );
+        assert_eq!(session.server_database().unwrap().index, Some(6));
+        assert_eq!(names(&session), vec![Datum::from("sw0")]);
+
+        assert_eq!(session.next_event().await.unwrap(), Event::Disconnected);
+        assert_eq!(
+            session.next_event().await.unwrap(),
+            Event::Reconnected { full_resync: false }
+        );
+        assert_eq!(
+            session.remote().to_string(),
+            remotes.split(',').next().unwrap()
+        );
+        assert_eq!(session.server_database().unwrap().index, Some(7));
+        assert_eq!(names(&session), vec![Datum::from("sw0")]);
+        server.await.unwrap();
+    }
+
+    #[test]
+    fn test_backoff() {
+        let backoff = Backoff::default();
+        for (attempt, base) in [(0, 1), (1, 2), (2, 4), (3, 8), (4, 8), (40, 8)] {
+            let delay = backoff.delay(attempt);
+            let base = Duration::from_secs(base);
+            assert!(delay >= base / 2 && delay <= base, "{:?}", delay);
+        }
+    }
+}
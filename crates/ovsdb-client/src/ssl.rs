@@ -0,0 +1,433 @@
+//! TLS for `ssl:` remotes and `pssl:` listeners, set up like the `--private-key`,
+//! `--certificate` and `--ca-cert` options of the OVS tools.
+// https://docs.openvswitch.org/en/latest/howto/ssl/
+
+use crate::error::{Error, Result};
+use crate::remote::Connection;
+use base64::Engine;
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::client::WebPkiServerVerifier;
+use rustls::crypto::{ring, CryptoProvider};
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName, UnixTime};
+use rustls::server::WebPkiClientVerifier;
+use rustls::{
+    CertificateError, ClientConfig, DigitallySignedStruct, RootCertStore, ServerConfig,
+    SignatureScheme,
+};
+use std::fs::{File, OpenOptions};
+use std::io::{BufReader, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tokio_rustls::{client, server, TlsAcceptor, TlsConnector};
+
+/// The key, certificate and CA certificate of this end of `ssl:` and
+/// `pssl:` connections, all PEM files.
+///
+/// Both ends must present a certificate signed by the CA. As with OVS, the
+/// certificate of the server need not name the host connected to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SslOptions {
+    pub private_key: PathBuf,
+    /// Our certificate, optionally followed by the chain up to its CA.
+    pub certificate: PathBuf,
+    pub ca_cert: PathBuf,
+    /// When `ca_cert` does not exist yet, trust the CA certificate the
+    /// server sends at the top of its chain, and save it to `ca_cert` for
+    /// the next connections. This is OVS's `--bootstrap-ca-cert`: it only
+    /// protects the connections after the first one. `pssl:` listeners
+    /// always need `ca_cert`.
+    pub bootstrap_ca_cert: bool,
+}
+
+impl SslOptions {
+    pub fn new(
+        private_key: impl Into<PathBuf>,
+        certificate: impl Into<PathBuf>,
+        ca_cert: impl Into<PathBuf>,
+    ) -> Self {
+        SslOptions {
+            private_key: private_key.into(),
+            certificate: certificate.into(),
+            ca_cert: ca_cert.into(),
+            bootstrap_ca_cert: false,
+        }
+    }
+
+    /// Runs the TLS handshake with `host` over `stream`.
+    ///
+    /// The files are read again on every connection, so that renewed
+    /// certificates are picked up by the next reconnect.
+    pub(crate) async fn connect<S>(&self, stream: S, host: &str) -> Result<client::TlsStream<S>>
+    where
+        S: Connection,
+    {
+        let provider = Arc::new(ring::default_provider());
+        let roots = match self.ca_cert.exists() || !self.bootstrap_ca_cert {
+            true => Some(read_roots(&self.ca_cert)?),
+            false => None,
+        };
+        let verifier = Arc::new(Verifier {
+            provider: provider.clone(),
+            roots,
+            bootstrapped: Mutex::new(None),
+        });
+        let certificate = read_certs(&self.certificate)?;
+        let private_key = read_private_key(&self.private_key)?;
+        let config = ClientConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()
+            .map_err(|e| Error::Ssl(e.to_string()))?
+            .dangerous()
+            .with_custom_certificate_verifier(verifier.clone())
+            .with_client_auth_cert(certificate, private_key)
+            .map_err(|e| Error::Ssl(format!("{}: {}", self.certificate.display(), e)))?;
+        let name = ServerName::try_from(host.to_string())
+            .map_err(|_| Error::Ssl(format!("invalid server name '{}'", host)))?;
+        let stream = TlsConnector::from(Arc::new(config))
+            .connect(name, stream)
+            .await?;
+
+        if let Some(ca_cert) = verifier.bootstrapped.lock().unwrap().take() {
+            save_ca_cert(&self.ca_cert, &ca_cert)?;
+        }
+        Ok(stream)
+    }
+
+    /// Runs the server side of the TLS handshake over an accepted `stream`,
+    /// requiring a client certificate signed by the CA.
+    pub(crate) async fn accept<S>(&self, stream: S) -> Result<server::TlsStream<S>>
+    where
+        S: Connection,
+    {
+        let provider = Arc::new(ring::default_provider());
+        let roots = Arc::new(read_roots(&self.ca_cert)?);
+        let verifier = WebPkiClientVerifier::builder_with_provider(roots, provider.clone())
+            .build()
+            .map_err(|e| Error::Ssl(format!("{}: {}", self.ca_cert.display(), e)))?;
+        let certificate = read_certs(&self.certificate)?;
+        let private_key = read_private_key(&self.private_key)?;
+        let config = ServerConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()
+            .map_err(|e| Error::Ssl(e.to_string()))?
+            .with_client_cert_verifier(verifier)
+            .with_single_cert(certificate, private_key)
+            .map_err(|e| Error::Ssl(format!("{}: {}", self.certificate.display(), e)))?;
+        Ok(TlsAcceptor::from(Arc::new(config)).accept(stream).await?)
+    }
+}
+
+/// Checks the server's chain against the CA, or, with no CA yet, against
+/// the top of the chain itself, which is then kept to be saved.
+#[derive(Debug)]
+struct Verifier {
+    provider: Arc<CryptoProvider>,
+    /// `None` while bootstrapping the CA certificate.
+    roots: Option<RootCertStore>,
+    bootstrapped: Mutex<Option<CertificateDer<'static>>>,
+}
+
+impl Verifier {
+    fn webpki(
+        &self,
+        roots: RootCertStore,
+    ) -> std::result::Result<Arc<WebPkiServerVerifier>, rustls::Error> {
+        WebPkiServerVerifier::builder_with_provider(Arc::new(roots), self.provider.clone())
+            .build()
+            .map_err(|e| rustls::Error::General(e.to_string()))
+    }
+}
+
+impl ServerCertVerifier for Verifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> std::result::Result<ServerCertVerified, rustls::Error> {
+        let (roots, bootstrap) = match &self.roots {
+            Some(roots) => (roots.clone(), None),
+            None => {
+                let ca_cert = intermediates.last().ok_or_else(|| {
+                    rustls::Error::General(
+                        "the server sent no CA certificate to bootstrap from".to_string(),
+                    )
+                })?;
+                let mut roots = RootCertStore::empty();
+                roots.add(ca_cert.clone().into_owned())?;
+                (roots, Some(ca_cert.clone().into_owned()))
+            }
+        };
+        let verified = self.webpki(roots)?.verify_server_cert(
+            end_entity,
+            intermediates,
+            server_name,
+            ocsp_response,
+            now,
+        );
+        // the name is only checked once the chain is known to be good
+        let verified = match verified {
+            Err(rustls::Error::InvalidCertificate(
+                CertificateError::NotValidForName | CertificateError::NotValidForNameContext { .. },
+            )) => Ok(ServerCertVerified::assertion()),
+            verified => verified,
+        }?;
+        *self.bootstrapped.lock().unwrap() = bootstrap;
+        Ok(verified)
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> std::result::Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> std::result::Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.provider
+            .signature_verification_algorithms
+            .supported_schemes()
+    }
+}
+
+fn open(path: &Path) -> Result<BufReader<File>> {
+    File::open(path)
+        .map(BufReader::new)
+        .map_err(|e| Error::Ssl(format!("{}: {}", path.display(), e)))
+}
+
+fn read_certs(path: &Path) -> Result<Vec<CertificateDer<'static>>> {
+    let certs = rustls_pemfile::certs(&mut open(path)?)
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|e| Error::Ssl(format!("{}: {}", path.display(), e)))?;
+    if certs.is_empty() {
+        return Err(Error::Ssl(format!(
+            "{}: no certificate found",
+            path.display()
+        )));
+    }
+    Ok(certs)
+}
+
+fn read_roots(path: &Path) -> Result<RootCertStore> {
+    let mut roots = RootCertStore::empty();
+    for cert in read_certs(path)? {
+        roots
+            .add(cert)
+            .map_err(|e| Error::Ssl(format!("{}: {}", path.display(), e)))?;
+    }
+    Ok(roots)
+}
+
+fn read_private_key(path: &Path) -> Result<PrivateKeyDer<'static>> {
+    rustls_pemfile::private_key(&mut open(path)?)
+        .map_err(|e| Error::Ssl(format!("{}: {}", path.display(), e)))?
+        .ok_or_else(|| Error::Ssl(format!("{}: no private key found", path.display())))
+}
+
+/// Writes a bootstrapped CA certificate as PEM, unless another connection
+/// got there first.
+fn save_ca_cert(path: &Path, cert: &CertificateDer<'_>) -> Result<()> {
+    let encoded = base64::engine::general_purpose::STANDARD.encode(cert);
+    let mut pem = String::from("-----BEGIN CERTIFICATE-----\n");
+    for line in encoded.as_bytes().chunks(64) {
+        pem.push_str(std::str::from_utf8(line).expect("base64 is ASCII"));
+        pem.push('\n');
+    }
+    pem.push_str("-----END CERTIFICATE-----\n");
+
+    match OpenOptions::new().write(true).create_new(true).open(path) {
+        Ok(mut file) => file
+            .write_all(pem.as_bytes())
+            .map_err(|e| Error::Ssl(format!("{}: {}", path.display(), e))),
+        Err(e) if e.kind() == ErrorKind::AlreadyExists => Ok(()),
+        Err(e) => Err(Error::Ssl(format!("{}: {}", path.display(), e))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::codec::JsonCodec;
+    use crate::listener::Listener;
+    use crate::ssl::*;
+    use crate::Client;
+    use futures::{SinkExt, StreamExt};
+    use ovsdb_common::jsonrpc::{Message, Response};
+    use rcgen::{BasicConstraints, CertificateParams, CertifiedKey, IsCa, KeyPair};
+    use serde_json::json;
+    use tempfile::TempDir;
+    use tokio::net::{TcpListener, TcpStream};
+    use tokio_util::codec::Framed;
+
+    fn new_ca() -> CertifiedKey {
+        let mut params = CertificateParams::new(Vec::new()).unwrap();
+        params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        let key_pair = KeyPair::generate().unwrap();
+        let cert = params.self_signed(&key_pair).unwrap();
+        CertifiedKey { cert, key_pair }
+    }
+
+    /// Writes a certificate for `name` signed by `ca`, followed by `ca`, its
+    /// key and `ca` itself to `dir`.
+    fn files(dir: &TempDir, name: &str, ca: &CertifiedKey) -> SslOptions {
+        let params = CertificateParams::new(vec![name.to_string()]).unwrap();
+        let key_pair = KeyPair::generate().unwrap();
+        let cert = params.signed_by(&key_pair, &ca.cert, &ca.key_pair).unwrap();
+        let options = SslOptions::new(
+            dir.path().join(format!("{}-privkey.pem", name)),
+            dir.path().join(format!("{}-cert.pem", name)),
+            dir.path().join("cacert.pem"),
+        );
+        std::fs::write(&options.private_key, key_pair.serialize_pem()).unwrap();
+        std::fs::write(&options.certificate, cert.pem() + &ca.cert.pem()).unwrap();
+        std::fs::write(&options.ca_cert, ca.cert.pem()).unwrap();
+        options
+    }
+
+    /// Answers `list_dbs` until the peer goes away.
+    async fn serve<S: Connection>(stream: S) {
+        let mut server = Framed::new(stream, JsonCodec::default());
+        while let Some(Ok(Message::Request(request))) = server.next().await {
+            assert_eq!(request.method, "list_dbs");
+            let response = Response::ok(request.id, json!(["OVN_Southbound"]));
+            server.send(response.into()).await.unwrap();
+        }
+    }
+
+    /// A TLS listener that requires client certificates signed by `ca`,
+    /// and serves every connection it accepts.
+    async fn listen(ca: &CertifiedKey) -> String {
+        let dir = TempDir::new().unwrap();
+        // deliberately not named after 127.0.0.1
+        let options = files(&dir, "ovsdb.example.com", ca);
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let remote = format!("ssl:{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let _dir = dir;
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                if let Ok(stream) = options.accept(stream).await {
+                    assert!(stream.get_ref().1.peer_certificates().is_some());
+                    tokio::spawn(serve(stream));
+                }
+            }
+        });
+        remote
+    }
+
+    #[tokio::test]
+    async fn test_mutual_tls() {
+        let ca = new_ca();
+        let remote = listen(&ca).await;
+        let dir = TempDir::new().unwrap();
+        let options = files(&dir, "ovn-controller", &ca);
+
+        let (client, _notifications) = Client::connect_with(&remote, Some(&options)).await.unwrap();
+        assert_eq!(client.list_dbs().await.unwrap(), vec!["OVN_Southbound"]);
+
+        assert!(matches!(Client::connect(&remote).await, Err(Error::Ssl(_))));
+    }
+
+    #[tokio::test]
+    async fn test_untrusted_server() {
+        let remote = listen(&new_ca()).await;
+        let dir = TempDir::new().unwrap();
+        let options = files(&dir, "ovn-controller", &new_ca());
+        assert!(Client::connect_with(&remote, Some(&options)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_bootstrap_ca_cert() {
+        let ca = new_ca();
+        let remote = listen(&ca).await;
+        let dir = TempDir::new().unwrap();
+        let mut options = files(&dir, "ovn-controller", &ca);
+        std::fs::remove_file(&options.ca_cert).unwrap();
+
+        // without bootstrapping, a missing CA certificate is an error
+        assert!(matches!(
+            Client::connect_with(&remote, Some(&options)).await,
+            Err(Error::Ssl(_))
+        ));
+
+        options.bootstrap_ca_cert = true;
+        let (client, _notifications) = Client::connect_with(&remote, Some(&options)).await.unwrap();
+        assert_eq!(client.list_dbs().await.unwrap(), vec!["OVN_Southbound"]);
+        assert_eq!(
+            read_certs(&options.ca_cert).unwrap(),
+            vec![ca.cert.der().clone()]
+        );
+
+        // the saved CA certificate now rules out other servers
+        let other = listen(&new_ca()).await;
+        assert!(Client::connect_with(&other, Some(&options)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_pssl() {
+        let ca = new_ca();
+        let dir = TempDir::new().unwrap();
+        let options = files(&dir, "hv1", &ca);
+        let listener = Listener::bind("pssl:0:127.0.0.1", Some(&options))
+            .await
+            .unwrap();
+        let port = listener.port().unwrap();
+
+        // the database server dials out to the listening client
+        let server = files(&dir, "ovsdb.example.com", &ca);
+        tokio::spawn(async move {
+            let stream = TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+            serve(server.connect(stream, "127.0.0.1").await.unwrap()).await;
+        });
+        let (client, _notifications) = listener.accept().await.unwrap();
+        assert_eq!(client.list_dbs().await.unwrap(), vec!["OVN_Southbound"]);
+    }
+
+    #[tokio::test]
+    async fn test_pssl_idle_peer() {
+        let ca = new_ca();
+        let dir = TempDir::new().unwrap();
+        let options = files(&dir, "ovsdb-server", &ca);
+        let listener = Listener::bind("pssl:0:127.0.0.1", Some(&options))
+            .await
+            .unwrap();
+        let port = listener.port().unwrap();
+
+        // a peer that never starts the handshake holds up only itself
+        let _idle = TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+        let idle = listener.accept_incoming().await.unwrap();
+        let handshake = tokio::spawn(idle.establish());
+
+        let client = files(&dir, "ovn-controller", &ca);
+        tokio::spawn(async move {
+            let stream = TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+            let (client, _notifications) =
+                Client::from_stream(client.connect(stream, "127.0.0.1").await.unwrap());
+            client.list_dbs().await
+        });
+        let stream = listener.accept_incoming().await.unwrap();
+        serve(stream.establish().await.unwrap()).await;
+        assert!(!handshake.is_finished());
+    }
+}
@@ -0,0 +1,358 @@
+use crate::client::Client;
+use crate::error::{Error, Result};
+use crate::lock::Lock;
+use crate::operations::{Operation, OperationResult, Row, WaitUntil};
+use ovsdb_common::condition::Condition;
+use ovsdb_common::mutation::Mutation;
+use serde_json::Value;
+use std::collections::HashMap;
+use tokio::sync::watch;
+use uuid::Uuid;
+
+/// Collects operations to be executed atomically by one "transact" request.
+///
+/// ```no_run
+/// # async fn example(client: &ovsdb_client::Client) -> ovsdb_client::Result<()> {
+/// use ovsdb_client::operations::Row;
+/// use ovsdb_client::Transaction;
+/// use ovsdb_common::condition::Condition;
+/// use ovsdb_common::datum::Atom;
+/// use ovsdb_common::mutation::Mutation;
+/// use serde_json::json;
+///
+/// let mut switch = Row::new();
+/// switch.insert("name".to_string(), json!("sw0"));
+/// let mut port = Row::new();
+/// port.insert("name".to_string(), json!("sw0-port0"));
+///
+/// let mut txn = Transaction::new("OVN_Northbound");
+/// txn.insert_named("Logical_Switch_Port", "port0", port)
+///     .insert_named("Logical_Switch", "sw0", switch)
+///     .mutate(
+///         "Logical_Switch",
+///         vec![Condition::eq("_uuid", Atom::NamedUuid("sw0".to_string()))],
+///         vec![Mutation::insert("ports", Atom::NamedUuid("port0".to_string()))],
+///     );
+/// let outcome = txn.execute(client).await?;
+/// println!("created switch {}", outcome.uuid("sw0").unwrap());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Transaction {
+    db: String,
+    operations: Vec<Operation>,
+    /// Locks from [`Transaction::require_lock`], with whether they are held.
+    required_locks: Vec<(String, watch::Receiver<bool>)>,
+}
+
+impl Transaction {
+    pub fn new(db: impl Into<String>) -> Self {
+        Transaction {
+            db: db.into(),
+            operations: Vec::new(),
+            required_locks: Vec::new(),
+        }
+    }
+
+    pub fn db(&self) -> &str {
+        &self.db
+    }
+
+    pub fn operations(&self) -> &[Operation] {
+        &self.operations
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+
+    pub fn add(&mut self, operation: Operation) -> &mut Self {
+        self.operations.push(operation);
+        self
+    }
+
+    pub fn insert(&mut self, table: &str, row: Row) -> &mut Self {
+        self.add(Operation::Insert {
+            table: table.to_string(),
+            row,
+            uuid_name: None,
+        })
+    }
+
+    /// Inserts a row that later operations can refer to as `named_uuid(uuid_name)`.
+    pub fn insert_named(&mut self, table: &str, uuid_name: &str, row: Row) -> &mut Self {
+        self.add(Operation::Insert {
+            table: table.to_string(),
+            row,
+            uuid_name: Some(uuid_name.to_string()),
+        })
+    }
+
+    pub fn select(
+        &mut self,
+        table: &str,
+        where_: Vec<Condition>,
+        columns: Option<Vec<String>>,
+    ) -> &mut Self {
+        self.add(Operation::Select {
+            table: table.to_string(),
+            where_,
+            columns,
+        })
+    }
+
+    pub fn update(&mut self, table: &str, where_: Vec<Condition>, row: Row) -> &mut Self {
+        self.add(Operation::Update {
+            table: table.to_string(),
+            where_,
+            row,
+        })
+    }
+
+    pub fn mutate(
+        &mut self,
+        table: &str,
+        where_: Vec<Condition>,
+        mutations: Vec<Mutation>,
+    ) -> &mut Self {
+        self.add(Operation::Mutate {
+            table: table.to_string(),
+            where_,
+            mutations,
+        })
+    }
+
+    pub fn delete(&mut self, table: &str, where_: Vec<Condition>) -> &mut Self {
+        self.add(Operation::Delete {
+            table: table.to_string(),
+            where_,
+        })
+    }
+
+    pub fn wait(
+        &mut self,
+        table: &str,
+        where_: Vec<Condition>,
+        columns: Vec<String>,
+        until: WaitUntil,
+        rows: Vec<Row>,
+        timeout: Option<u64>,
+    ) -> &mut Self {
+        self.add(Operation::Wait {
+            table: table.to_string(),
+            where_,
+            columns,
+            until,
+            rows,
+            timeout,
+        })
+    }
+
+    pub fn commit(&mut self, durable: bool) -> &mut Self {
+        self.add(Operation::Commit { durable })
+    }
+
+    pub fn abort(&mut self) -> &mut Self {
+        self.add(Operation::Abort)
+    }
+
+    pub fn comment(&mut self, comment: &str) -> &mut Self {
+        self.add(Operation::Comment {
+            comment: comment.to_string(),
+        })
+    }
+
+    pub fn assert(&mut self, lock: &str) -> &mut Self {
+        self.add(Operation::Assert {
+            lock: lock.to_string(),
+        })
+    }
+
+    /// Makes the transaction fail unless this session holds `lock` when the
+    /// server runs it, by adding an "assert" operation. [`execute`] also
+    /// fails early, with [`Error::LockNotHeld`], while the lock is known not
+    /// to be held.
+    ///
+    /// [`execute`]: Transaction::execute
+    pub fn require_lock(&mut self, lock: &Lock) -> &mut Self {
+        self.required_locks
+            .push((lock.name().to_string(), lock.held()));
+        self.assert(lock.name())
+    }
+
+    /// The operations as "transact" params, without the leading database name.
+    pub fn to_params(&self) -> Result<Vec<Value>> {
+        self.operations
+            .iter()
+            .map(|op| serde_json::to_value(op).map_err(Error::from))
+            .collect()
+    }
+
+    /// Sends the transaction and matches each reply to its operation.
+    ///
+    /// Fails with [`Error::OperationFailed`] for the first operation the
+    /// server rejected, or for the commit itself when every operation
+    /// succeeded but the result could not be committed.
+    pub async fn execute(&self, client: &Client) -> Result<Outcome> {
+        if let Some((name, _)) = self.required_locks.iter().find(|(_, held)| !*held.borrow()) {
+            return Err(Error::LockNotHeld(name.clone()));
+        }
+        let results = client.transact(&self.db, self.to_params()?).await?;
+        self.match_results(results)
+    }
+
+    fn match_results(&self, results: Vec<Value>) -> Result<Outcome> {
+        // https://tools.ietf.org/html/rfc7047#section-4.1.3
+        // on failure, the failed operation's slot holds an <error> and the
+        // remaining slots are null; if the commit fails, there is one more
+        // slot than there are operations.
+        let mut outcome = Outcome::default();
+        for (index, value) in results.into_iter().enumerate() {
+            if value.get("error").is_some() {
+                let op = self.operations.get(index).map_or("commit", Operation::name);
+                return Err(Error::OperationFailed {
+                    index,
+                    op: op.to_string(),
+                    error: serde_json::from_value(value)?,
+                });
+            }
+            let op = self
+                .operations
+                .get(index)
+                .ok_or_else(|| Error::UnexpectedResponse(format!("extra result {}", value)))?;
+            let result = OperationResult::from_value(op, value)?;
+            if let (
+                Operation::Insert {
+                    uuid_name: Some(name),
+                    ..
+                },
+                OperationResult::Insert { uuid },
+            ) = (op, &result)
+            {
+                outcome.named_uuids.insert(name.clone(), *uuid);
+            }
+            outcome.results.push(result);
+        }
+        if outcome.results.len() != self.operations.len() {
+            return Err(Error::UnexpectedResponse(format!(
+                "expected {} results, got {}",
+                self.operations.len(),
+                outcome.results.len()
+            )));
+        }
+        Ok(outcome)
+    }
+}
+
+/// The results of a successful transaction, one per operation.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Outcome {
+    results: Vec<OperationResult>,
+    named_uuids: HashMap<String, Uuid>,
+}
+
+impl Outcome {
+    pub fn results(&self) -> &[OperationResult] {
+        &self.results
+    }
+
+    pub fn get(&self, index: usize) -> Option<&OperationResult> {
+        self.results.get(index)
+    }
+
+    /// The real UUID of the row inserted with the given "uuid-name".
+    pub fn uuid(&self, uuid_name: &str) -> Option<Uuid> {
+        self.named_uuids.get(uuid_name).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::transaction::*;
+    use serde_json::json;
+
+    const UUID: &str = "36c4b7d2-1a2d-4b8c-9f3e-2c1b7a0e5d11";
+
+    fn sample() -> Transaction {
+        let mut row = Row::new();
+        row.insert("name".to_string(), json!("sw0"));
+        let mut txn = Transaction::new("OVN_Northbound");
+        txn.insert_named("Logical_Switch", "sw0", row)
+            .delete("Logical_Switch", vec![Condition::eq("name", "sw1")])
+            .comment("ovsdb-rs");
+        txn
+    }
+
+    #[test]
+    fn test_to_params() {
+        let params = sample().to_params().unwrap();
+        assert_eq!(
+            params,
+            vec![
+                json!({"op": "insert", "table": "Logical_Switch", "row": {"name": "sw0"}, "uuid-name": "sw0"}),
+                json!({"op": "delete", "table": "Logical_Switch", "where": [["name", "==", "sw1"]]}),
+                json!({"op": "comment", "comment": "ovsdb-rs"}),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_match_results() {
+        let outcome = sample()
+            .match_results(vec![
+                json!({"uuid": ["uuid", UUID]}),
+                json!({"count": 0}),
+                json!({}),
+            ])
+            .unwrap();
+        assert_eq!(outcome.uuid("sw0"), Some(Uuid::parse_str(UUID).unwrap()));
+        assert_eq!(outcome.get(1), Some(&OperationResult::Count(0)));
+        assert_eq!(outcome.get(2), Some(&OperationResult::Empty));
+    }
+
+    #[test]
+    fn test_first_failed_operation() {
+        let err = sample()
+            .match_results(vec![
+                json!({"uuid": ["uuid", UUID]}),
+                json!({"error": "constraint violation", "details": "bad name"}),
+                json!(null),
+            ])
+            .unwrap_err();
+        match err {
+            Error::OperationFailed { index, op, error } => {
+                assert_eq!(index, 1);
+                assert_eq!(op, "delete");
+                assert_eq!(error.error(), "constraint violation");
+                assert_eq!(error.details(), Some("bad name"));
+                assert!(matches!(
+                    error,
+                    ovsdb_common::error::Error::ConstraintViolation(_)
+                ));
+            }
+            other => panic!("Expected Error::OperationFailed, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_commit_failure() {
+        let err = sample()
+            .match_results(vec![
+                json!({"uuid": ["uuid", UUID]}),
+                json!({"count": 1}),
+                json!({}),
+                json!({"error": "referential integrity violation"}),
+            ])
+            .unwrap_err();
+        assert!(matches!(err, Error::OperationFailed { index: 3, ref op, .. } if op == "commit"));
+    }
+
+    #[test]
+    fn test_missing_results() {
+        let err = sample()
+            .match_results(vec![json!({"uuid": ["uuid", UUID]})])
+            .unwrap_err();
+        assert!(matches!(err, Error::UnexpectedResponse(_)));
+    }
+}
@@ -0,0 +1,343 @@
+use crate::{load_schema, track_file};
+use ovsdb_common::common::AtomicType;
+use ovsdb_schema::{BaseType, ColumnType, ConstrainedBaseType};
+use proc_macro2::TokenStream;
+use quote::quote;
+use std::collections::BTreeSet;
+use syn::{Data, DeriveInput, Fields, GenericArgument, Ident, LitStr, PathArguments, Type};
+
+/// What a single atom of a column may be in Rust.
+struct AtomSpec {
+    atomic: AtomicType,
+    string_enum: bool,
+}
+
+impl AtomSpec {
+    fn new(base: &BaseType) -> Self {
+        AtomSpec {
+            atomic: base.atomic_type(),
+            string_enum: matches!(
+                base,
+                BaseType::Constrained(ConstrainedBaseType::BaseTypeString { enum_: Some(_), .. })
+            ),
+        }
+    }
+
+    fn describe(&self) -> &'static str {
+        match self.atomic {
+            AtomicType::Integer => "i64",
+            AtomicType::Real => "f64",
+            AtomicType::Boolean => "bool",
+            AtomicType::String if self.string_enum => "String | <string enum>",
+            AtomicType::String => "String",
+            AtomicType::Uuid => "Uuid | Ref<_>",
+        }
+    }
+
+    fn matches(&self, ty: &Type) -> bool {
+        let Some(ident) = last_ident(ty) else {
+            return false;
+        };
+        let ident = ident.to_string();
+        match self.atomic {
+            AtomicType::Integer => ident == "i64",
+            AtomicType::Real => ident == "f64",
+            AtomicType::Boolean => ident == "bool",
+            AtomicType::String if self.string_enum => {
+                // any type that is not obviously something else is taken to
+                // be a user enum; FromAtom bounds catch the rest
+                ident == "String"
+                    || ![
+                        "i64", "f64", "bool", "Uuid", "Ref", "Option", "BTreeSet", "BTreeMap",
+                        "Vec", "Datum", "str",
+                    ]
+                    .contains(&ident.as_str())
+            }
+            AtomicType::String => ident == "String",
+            AtomicType::Uuid => ident == "Uuid" || ident == "Ref",
+        }
+    }
+}
+
+fn last_ident(ty: &Type) -> Option<&Ident> {
+    match ty {
+        Type::Path(path) => path.path.segments.last().map(|segment| &segment.ident),
+        _ => None,
+    }
+}
+
+fn generic_args(ty: &Type) -> Vec<&Type> {
+    let Type::Path(path) = ty else {
+        return Vec::new();
+    };
+    let Some(segment) = path.path.segments.last() else {
+        return Vec::new();
+    };
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) => args
+            .args
+            .iter()
+            .filter_map(|arg| match arg {
+                GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Checks a field type against a column type, returning the expected type
+/// on mismatch.
+fn check_field(ty: &Type, column: &ColumnType) -> Result<(), String> {
+    if last_ident(ty).is_some_and(|ident| ident == "Datum") {
+        return Ok(());
+    }
+    let (key, value) = match column {
+        ColumnType::Atomic(atomic) => (AtomSpec::new(&BaseType::Atomic(*atomic)), None),
+        ColumnType::Complex(complex) => (
+            AtomSpec::new(&complex.key),
+            complex.value.as_ref().map(AtomSpec::new),
+        ),
+    };
+    let wrapped = |wrapper: &str| {
+        last_ident(ty).is_some_and(|ident| ident == wrapper) && generic_args(ty).len() == 1
+    };
+
+    let (ok, expected) = if let Some(value) = value {
+        let args = generic_args(ty);
+        let ok = last_ident(ty).is_some_and(|ident| ident == "BTreeMap")
+            && args.len() == 2
+            && key.matches(args[0])
+            && value.matches(args[1]);
+        (
+            ok,
+            format!("BTreeMap<{}, {}>", key.describe(), value.describe()),
+        )
+    } else if column.is_scalar() {
+        (key.matches(ty), key.describe().to_string())
+    } else if column.is_optional() {
+        let ok = wrapped("Option") && key.matches(generic_args(ty)[0]);
+        (ok, format!("Option<{}>", key.describe()))
+    } else {
+        let ok = (wrapped("BTreeSet") || wrapped("Vec")) && key.matches(generic_args(ty)[0]);
+        (
+            ok,
+            format!("BTreeSet<{}> or Vec<{}>", key.describe(), key.describe()),
+        )
+    };
+    match ok {
+        true => Ok(()),
+        false => Err(expected),
+    }
+}
+
+#[derive(Default)]
+struct StructAttrs {
+    schema: Option<LitStr>,
+    table: Option<LitStr>,
+}
+
+#[derive(Default)]
+struct FieldAttrs {
+    uuid: bool,
+    column: Option<LitStr>,
+}
+
+pub(crate) fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let mut attrs = StructAttrs::default();
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("ovsdb"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("schema") {
+                attrs.schema = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("table") {
+                attrs.table = Some(meta.value()?.parse()?);
+            } else {
+                return Err(meta.error("expected `schema` or `table`"));
+            }
+            Ok(())
+        })?;
+    }
+    let missing = |what: &str| {
+        syn::Error::new_spanned(
+            &input.ident,
+            format!("missing #[ovsdb({} = \"...\")] attribute", what),
+        )
+    };
+    let schema_path = attrs.schema.ok_or_else(|| missing("schema"))?;
+    let table_name = attrs.table.ok_or_else(|| missing("table"))?;
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &input.ident,
+                    "OvsdbTable needs a struct with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "OvsdbTable can only be derived for structs",
+            ))
+        }
+    };
+
+    let (schema, resolved) = load_schema(&schema_path)?;
+    let table = schema.get_table(&table_name.value()).ok_or_else(|| {
+        syn::Error::new(
+            table_name.span(),
+            format!(
+                "schema {} has no table \"{}\"",
+                schema.name,
+                table_name.value()
+            ),
+        )
+    })?;
+
+    let mut errors: Vec<syn::Error> = Vec::new();
+    let mut uuid_field = None;
+    let mut columns = Vec::new();
+    let mut seen = BTreeSet::new();
+    for field in fields {
+        let ident = field.ident.as_ref().unwrap();
+        let mut field_attrs = FieldAttrs::default();
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("ovsdb"))
+        {
+            let parsed = attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("uuid") {
+                    field_attrs.uuid = true;
+                } else if meta.path.is_ident("column") {
+                    field_attrs.column = Some(meta.value()?.parse()?);
+                } else {
+                    return Err(meta.error("expected `uuid` or `column`"));
+                }
+                Ok(())
+            });
+            if let Err(e) = parsed {
+                errors.push(e);
+            }
+        }
+
+        let name = ident.to_string();
+        let name = name.strip_prefix("r#").unwrap_or(&name);
+        if field_attrs.uuid || (name == "_uuid" && field_attrs.column.is_none()) {
+            if uuid_field.replace(ident).is_some() {
+                errors.push(syn::Error::new_spanned(ident, "more than one uuid field"));
+            }
+            if last_ident(&field.ty).is_none_or(|ty| ty != "Uuid") {
+                errors.push(syn::Error::new_spanned(
+                    &field.ty,
+                    "the uuid field must be an ovsdb_common::datum::Uuid",
+                ));
+            }
+            continue;
+        }
+
+        let column = field_attrs
+            .column
+            .as_ref()
+            .map(LitStr::value)
+            .unwrap_or_else(|| name.to_string());
+        let Some(column_def) = table.columns.get(&column) else {
+            let span = field_attrs
+                .column
+                .as_ref()
+                .map(LitStr::span)
+                .unwrap_or_else(|| ident.span());
+            errors.push(syn::Error::new(
+                span,
+                format!(
+                    "table \"{}\" has no column \"{}\"",
+                    table_name.value(),
+                    column
+                ),
+            ));
+            continue;
+        };
+        if !seen.insert(column.clone()) {
+            errors.push(syn::Error::new_spanned(
+                ident,
+                format!("column \"{}\" is mapped more than once", column),
+            ));
+        }
+        if let Err(expected) = check_field(&field.ty, &column_def.type_) {
+            errors.push(syn::Error::new_spanned(
+                &field.ty,
+                format!(
+                    "type does not match column \"{}\" of table \"{}\": expected {}",
+                    column,
+                    table_name.value(),
+                    expected
+                ),
+            ));
+        }
+        columns.push((ident, LitStr::new(&column, ident.span())));
+    }
+    let Some(uuid_field) = uuid_field else {
+        errors.push(syn::Error::new_spanned(
+            &input.ident,
+            "missing uuid field: name it `_uuid` or mark it with #[ovsdb(uuid)]",
+        ));
+        return Err(combine(errors));
+    };
+    if !errors.is_empty() {
+        return Err(combine(errors));
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let field_idents: Vec<_> = columns.iter().map(|(ident, _)| ident).collect();
+    let column_names: Vec<_> = columns.iter().map(|(_, column)| column).collect();
+    let track = track_file(&resolved);
+
+    Ok(quote! {
+        impl #impl_generics ::ovsdb_common::row::TableRow for #name #ty_generics #where_clause {
+            const TABLE: &'static str = #table_name;
+
+            fn uuid(&self) -> ::ovsdb_common::datum::Uuid {
+                self.#uuid_field
+            }
+
+            #[allow(unused_variables)]
+            fn from_row(
+                uuid: ::ovsdb_common::datum::Uuid,
+                row: &::ovsdb_common::row::Row,
+            ) -> ::std::result::Result<Self, ::ovsdb_common::row::ColumnError> {
+                ::std::result::Result::Ok(Self {
+                    #uuid_field: uuid,
+                    #( #field_idents: ::ovsdb_common::row::column(row, #table_name, #column_names)?, )*
+                })
+            }
+
+            #[allow(unused_mut)]
+            fn to_row(&self) -> ::ovsdb_common::row::Row {
+                let mut row = ::ovsdb_common::row::Row::new();
+                #(
+                    row.insert(
+                        ::std::string::ToString::to_string(#column_names),
+                        ::ovsdb_common::row::ToDatum::to_datum(&self.#field_idents),
+                    );
+                )*
+                row
+            }
+        }
+
+        #track
+    })
+}
+
+fn combine(errors: Vec<syn::Error>) -> syn::Error {
+    let mut errors = errors.into_iter();
+    let mut first = errors.next().unwrap();
+    errors.for_each(|e| first.combine(e));
+    first
+}
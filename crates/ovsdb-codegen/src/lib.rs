@@ -1 +1,114 @@
+//! Compile-time code generation from `.ovsschema` files.
+//!
+//! - `ovsdb_schema!("path/to.ovsschema")` expands to the same row structs
+//!   and enums as the `code-gen` CLI subcommand.
+//! - `#[derive(OvsdbTable)]` maps a hand-written struct onto one table of a
+//!   schema and fails to compile when a field does not fit its column.
+//!
+//! Schema paths are resolved like `include_str!`: relative to the file that
+//! contains the macro call.
 
+mod derive;
+
+use ovsdb_schema::Schema;
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use std::path::PathBuf;
+use syn::{parse_macro_input, DeriveInput, LitStr};
+
+/// Expands to typed row structs for every table of a schema.
+///
+/// ```ignore
+/// mod nb {
+///     ovsdb_codegen::ovsdb_schema!("ovn-nb.ovsschema");
+/// }
+/// ```
+#[proc_macro]
+pub fn ovsdb_schema(input: TokenStream) -> TokenStream {
+    let path = parse_macro_input!(input as LitStr);
+    let (schema, resolved) = match load_schema(&path) {
+        Ok(loaded) => loaded,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let code: proc_macro2::TokenStream = match ovsdb_schema::codegen::generate(&schema).parse() {
+        Ok(code) => code,
+        Err(e) => {
+            return syn::Error::new(path.span(), format!("generated invalid code: {}", e))
+                .to_compile_error()
+                .into()
+        }
+    };
+    let track = track_file(&resolved);
+    quote!(#code #track).into()
+}
+
+/// Implements `ovsdb_common::row::TableRow` for a struct with named fields.
+///
+/// ```ignore
+/// #[derive(OvsdbTable)]
+/// #[ovsdb(schema = "ovn-nb.ovsschema", table = "Logical_Switch")]
+/// struct Switch {
+///     #[ovsdb(uuid)]
+///     id: Uuid,
+///     name: String,
+///     #[ovsdb(column = "ports")]
+///     port_refs: BTreeSet<Ref<Port>>,
+/// }
+/// ```
+///
+/// Fields map to the column of the same name unless renamed with
+/// `#[ovsdb(column = "...")]`. The row UUID goes to the field marked
+/// `#[ovsdb(uuid)]`, or the one named `_uuid`. Columns without a field
+/// are ignored.
+#[proc_macro_derive(OvsdbTable, attributes(ovsdb))]
+pub fn derive_ovsdb_table(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    derive::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Reads and parses the schema a macro argument points to.
+pub(crate) fn load_schema(path: &LitStr) -> syn::Result<(Schema, PathBuf)> {
+    let resolved = resolve(&path.value());
+    let contents = std::fs::read_to_string(&resolved).map_err(|e| {
+        syn::Error::new(
+            path.span(),
+            format!("couldn't read {}: {}", resolved.display(), e),
+        )
+    })?;
+    let schema = serde_json::from_str(&contents).map_err(|e| {
+        syn::Error::new(
+            path.span(),
+            format!("couldn't parse {}: {}", resolved.display(), e),
+        )
+    })?;
+    Ok((schema, resolved))
+}
+
+fn resolve(path: &str) -> PathBuf {
+    let path = PathBuf::from(path);
+    if path.is_absolute() {
+        return path;
+    }
+    let base = proc_macro::Span::call_site()
+        .local_file()
+        .and_then(|file| file.parent().map(|dir| dir.to_path_buf()))
+        .or_else(|| std::env::var_os("CARGO_MANIFEST_DIR").map(PathBuf::from))
+        .unwrap_or_default();
+    // local_file() is relative to rustc's working directory, include_str! in
+    // track_file() would resolve it against the calling file instead
+    std::env::current_dir()
+        .unwrap_or_default()
+        .join(base)
+        .join(path)
+}
+
+/// Makes cargo rebuild the caller when the schema file changes.
+pub(crate) fn track_file(path: &std::path::Path) -> proc_macro2::TokenStream {
+    let path = LitStr::new(&path.to_string_lossy(), Span::call_site());
+    quote!(
+        const _: &str = include_str!(#path);
+    )
+}
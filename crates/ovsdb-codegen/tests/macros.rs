@@ -0,0 +1,77 @@
+use ovsdb_codegen::OvsdbTable;
+use ovsdb_common::datum::{Datum, Uuid};
+use ovsdb_common::row::{Ref, TableRow};
+use std::collections::{BTreeMap, BTreeSet};
+
+#[allow(dead_code)]
+mod nb {
+    ovsdb_codegen::ovsdb_schema!("../../../apps/cli/tests/ovn-nb.ovsschema");
+}
+
+#[derive(Debug, PartialEq, OvsdbTable)]
+#[ovsdb(
+    schema = "../../../apps/cli/tests/ovn-nb.ovsschema",
+    table = "Logical_Switch"
+)]
+struct Switch {
+    #[ovsdb(uuid)]
+    id: Uuid,
+    name: String,
+    #[ovsdb(column = "ports")]
+    port_refs: BTreeSet<Ref<nb::LogicalSwitchPort>>,
+    other_config: BTreeMap<String, String>,
+    copp: Option<Uuid>,
+}
+
+#[derive(Debug, PartialEq, OvsdbTable)]
+#[ovsdb(schema = "../../../apps/cli/tests/ovn-nb.ovsschema", table = "ACL")]
+struct Acl {
+    _uuid: Uuid,
+    action: nb::AclAction,
+    priority: i64,
+    r#match: String,
+    external_ids: Datum,
+}
+
+#[test]
+fn test_schema_macro() {
+    assert_eq!(nb::SCHEMA_NAME, "OVN_Northbound");
+    assert_eq!(<nb::LogicalSwitch as TableRow>::TABLE, "Logical_Switch");
+}
+
+#[test]
+fn test_derive_round_trip() {
+    let switch = Switch {
+        id: Uuid::from_u128(1),
+        name: "sw0".to_string(),
+        port_refs: [Ref::new(Uuid::from_u128(2))].into_iter().collect(),
+        other_config: [("subnet".to_string(), "10.0.0.0/24".to_string())].into(),
+        copp: None,
+    };
+    assert_eq!(Switch::TABLE, "Logical_Switch");
+    let row = switch.to_row();
+    assert_eq!(row.len(), 4);
+    assert_eq!(row["ports"], Datum::from(Uuid::from_u128(2)));
+    assert_eq!(Switch::from_row(switch.uuid(), &row).unwrap(), switch);
+}
+
+#[test]
+fn test_derive_enum_column() {
+    let acl = Acl {
+        _uuid: Uuid::from_u128(3),
+        action: nb::AclAction::AllowRelated,
+        priority: 1000,
+        r#match: "ip4".to_string(),
+        external_ids: Datum::empty_map(),
+    };
+    let row = acl.to_row();
+    assert_eq!(row["action"], Datum::from("allow-related"));
+    assert!(row.contains_key("match"));
+    assert_eq!(Acl::from_row(acl.uuid(), &row).unwrap(), acl);
+}
+
+#[test]
+fn test_compile_errors() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,13 @@
+use ovsdb_codegen::OvsdbTable;
+use ovsdb_common::datum::Uuid;
+
+#[derive(OvsdbTable)]
+#[ovsdb(schema = "../../../../apps/cli/tests/ovn-nb.ovsschema", table = "Logical_Switch")]
+struct Switch {
+    _uuid: Uuid,
+    nmae: String,
+    #[ovsdb(column = "port")]
+    ports: Vec<Uuid>,
+}
+
+fn main() {}
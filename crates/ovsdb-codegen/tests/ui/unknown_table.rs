@@ -0,0 +1,10 @@
+use ovsdb_codegen::OvsdbTable;
+use ovsdb_common::datum::Uuid;
+
+#[derive(OvsdbTable)]
+#[ovsdb(schema = "../../../../apps/cli/tests/ovn-nb.ovsschema", table = "Logical_Swtich")]
+struct Switch {
+    _uuid: Uuid,
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use ovsdb_codegen::OvsdbTable;
+use ovsdb_common::datum::Uuid;
+
+#[derive(OvsdbTable)]
+#[ovsdb(schema = "../../../../apps/cli/tests/ovn-nb.ovsschema", table = "Logical_Switch")]
+struct Switch {
+    _uuid: Uuid,
+    name: i64,
+    ports: Vec<String>,
+}
+
+fn main() {}
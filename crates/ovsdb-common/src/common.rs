@@ -4,7 +4,7 @@ const SET_HEADER: &str = "set";
 
 // ovsdb atomic types
 // https://tools.ietf.org/html/rfc7047#section-3.1.1
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum AtomicType {
     Integer,
@@ -14,12 +14,46 @@ pub enum AtomicType {
     Uuid,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
+impl AtomicType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AtomicType::Integer => "integer",
+            AtomicType::String => "string",
+            AtomicType::Real => "real",
+            AtomicType::Boolean => "boolean",
+            AtomicType::Uuid => "uuid",
+        }
+    }
+}
+
+impl std::fmt::Display for AtomicType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum Set<T> {
     One(T),
     Many(Vec<T>),
 }
 
+impl<T> Set<T> {
+    pub fn contains(&self, value: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        self.iter().any(|v| v == value)
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        match self {
+            Set::One(v) => std::slice::from_ref(v).iter(),
+            Set::Many(values) => values.iter(),
+        }
+    }
+}
+
 pub fn serialize_set<T, S>(set: &Option<Set<T>>, serializer: S) -> Result<S::Ok, S::Error>
 where
     T: serde::Serialize,
@@ -0,0 +1,359 @@
+use crate::datum::{Atom, Datum, Uuid};
+use crate::row::Row;
+use serde::de::{self, Deserializer};
+use serde::{Deserialize, Serialize, Serializer};
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+// condition functions
+// https://tools.ietf.org/html/rfc7047#section-5.1
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Function {
+    Lt,
+    Le,
+    Eq,
+    Ne,
+    Ge,
+    Gt,
+    Includes,
+    Excludes,
+}
+
+impl Function {
+    pub const ALL: [Function; 8] = [
+        Function::Lt,
+        Function::Le,
+        Function::Eq,
+        Function::Ne,
+        Function::Ge,
+        Function::Gt,
+        Function::Includes,
+        Function::Excludes,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Function::Lt => "<",
+            Function::Le => "<=",
+            Function::Eq => "==",
+            Function::Ne => "!=",
+            Function::Ge => ">=",
+            Function::Gt => ">",
+            Function::Includes => "includes",
+            Function::Excludes => "excludes",
+        }
+    }
+
+    /// `<`, `<=`, `>=` and `>`, which only apply to single or optional
+    /// integers and reals.
+    pub fn is_inequality(&self) -> bool {
+        matches!(
+            self,
+            Function::Lt | Function::Le | Function::Ge | Function::Gt
+        )
+    }
+
+    /// `includes` and `excludes`, which only apply to sets and maps.
+    pub fn is_set_function(&self) -> bool {
+        matches!(self, Function::Includes | Function::Excludes)
+    }
+
+    /// Whether `column function value` holds, as ovsdb-server evaluates it.
+    ///
+    /// For maps, `includes` and `excludes` look at key-value pairs; for
+    /// sets, at elements. An inequality holds only between two numbers.
+    pub fn evaluate(&self, column: &Datum, value: &Datum) -> bool {
+        let ordering = || match (column.as_atom(), value.as_atom()) {
+            (Some(a), Some(b)) => compare_numbers(a, b),
+            _ => None,
+        };
+        match self {
+            Function::Eq => column == value,
+            Function::Ne => column != value,
+            Function::Includes => elements(value).all(|element| contains(column, element)),
+            Function::Excludes => !elements(value).any(|element| contains(column, element)),
+            Function::Lt => ordering() == Some(Ordering::Less),
+            Function::Le => matches!(ordering(), Some(Ordering::Less | Ordering::Equal)),
+            Function::Ge => matches!(ordering(), Some(Ordering::Greater | Ordering::Equal)),
+            Function::Gt => ordering() == Some(Ordering::Greater),
+        }
+    }
+}
+
+// an integer column may be compared with a real and the other way around
+fn compare_numbers(a: &Atom, b: &Atom) -> Option<Ordering> {
+    match (a, b) {
+        (Atom::Integer(a), Atom::Integer(b)) => Some(a.cmp(b)),
+        _ => a.as_real()?.partial_cmp(&b.as_real()?),
+    }
+}
+
+// the elements of a set, or the pairs of a map
+fn elements(datum: &Datum) -> Box<dyn Iterator<Item = (&Atom, Option<&Atom>)> + '_> {
+    match datum.as_map() {
+        Some(map) => Box::new(map.iter().map(|(key, value)| (key, Some(value)))),
+        None => Box::new(datum.keys().map(|key| (key, None))),
+    }
+}
+
+fn contains(datum: &Datum, (key, value): (&Atom, Option<&Atom>)) -> bool {
+    match (datum.as_map(), value) {
+        (Some(map), Some(value)) => map.get(key) == Some(value),
+        _ => datum.contains(key),
+    }
+}
+
+impl fmt::Display for Function {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Function {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Function::ALL
+            .into_iter()
+            .find(|function| function.as_str() == s)
+            .ok_or_else(|| format!("unknown condition function '{}'", s))
+    }
+}
+
+impl Serialize for Function {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Function {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+// ovsdb condition
+// https://tools.ietf.org/html/rfc7047#section-5.1
+// A <condition> is [<column>, <function>, <value>]. The conditions of
+// "monitor_cond" may also be the constants true and false.
+// https://docs.openvswitch.org/en/latest/ref/ovsdb-server.7/#monitor-cond
+//
+// Conditions are not checked against the column type here, as the schema
+// lives in ovsdb-schema; see `Table::check_condition` there.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Condition {
+    Clause {
+        column: String,
+        function: Function,
+        value: Datum,
+    },
+    Constant(bool),
+}
+
+impl Condition {
+    /// Matches every row.
+    pub const TRUE: Condition = Condition::Constant(true);
+    /// Matches no row.
+    pub const FALSE: Condition = Condition::Constant(false);
+
+    pub fn new(column: impl Into<String>, function: Function, value: impl Into<Datum>) -> Self {
+        Condition::Clause {
+            column: column.into(),
+            function,
+            value: value.into(),
+        }
+    }
+
+    pub fn lt(column: impl Into<String>, value: impl Into<Datum>) -> Self {
+        Condition::new(column, Function::Lt, value)
+    }
+
+    pub fn le(column: impl Into<String>, value: impl Into<Datum>) -> Self {
+        Condition::new(column, Function::Le, value)
+    }
+
+    pub fn eq(column: impl Into<String>, value: impl Into<Datum>) -> Self {
+        Condition::new(column, Function::Eq, value)
+    }
+
+    pub fn ne(column: impl Into<String>, value: impl Into<Datum>) -> Self {
+        Condition::new(column, Function::Ne, value)
+    }
+
+    pub fn ge(column: impl Into<String>, value: impl Into<Datum>) -> Self {
+        Condition::new(column, Function::Ge, value)
+    }
+
+    pub fn gt(column: impl Into<String>, value: impl Into<Datum>) -> Self {
+        Condition::new(column, Function::Gt, value)
+    }
+
+    pub fn includes(column: impl Into<String>, value: impl Into<Datum>) -> Self {
+        Condition::new(column, Function::Includes, value)
+    }
+
+    pub fn excludes(column: impl Into<String>, value: impl Into<Datum>) -> Self {
+        Condition::new(column, Function::Excludes, value)
+    }
+
+    /// Whether the row `uuid` with the values `row` satisfies the condition.
+    /// `_uuid` is the row's UUID; any other column missing from `row` never
+    /// matches.
+    pub fn evaluate(&self, uuid: &Uuid, row: &Row) -> bool {
+        let (column, function, value) = match self {
+            Condition::Constant(constant) => return *constant,
+            Condition::Clause {
+                column,
+                function,
+                value,
+            } => (column, function, value),
+        };
+        match column.as_str() {
+            "_uuid" => function.evaluate(&Datum::from(*uuid), value),
+            _ => row
+                .get(column)
+                .is_some_and(|datum| function.evaluate(datum, value)),
+        }
+    }
+}
+
+impl From<bool> for Condition {
+    fn from(value: bool) -> Self {
+        Condition::Constant(value)
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&serde_json::to_string(self).map_err(|_| fmt::Error)?)
+    }
+}
+
+impl Serialize for Condition {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Condition::Clause {
+                column,
+                function,
+                value,
+            } => (column, function, value).serialize(serializer),
+            Condition::Constant(constant) => serializer.serialize_bool(*constant),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Condition {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = Value::deserialize(deserializer)?;
+        match value {
+            Value::Bool(constant) => Ok(Condition::Constant(constant)),
+            Value::Array(_) => {
+                let (column, function, value): (String, Function, Datum) =
+                    serde_json::from_value(value).map_err(de::Error::custom)?;
+                Ok(Condition::Clause {
+                    column,
+                    function,
+                    value,
+                })
+            }
+            other => Err(de::Error::custom(format!(
+                "expected a condition, got {}",
+                other
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::condition::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_condition_round_trip() {
+        let cases = vec![
+            (json!(["name", "==", "sw0"]), Condition::eq("name", "sw0")),
+            (
+                json!(["external_ids", "includes", ["map", [["k", "v"]]]]),
+                Condition::includes("external_ids", Datum::map([("k", "v")])),
+            ),
+            (json!(["tag", "<", 4095]), Condition::lt("tag", 4095)),
+            (json!(true), Condition::TRUE),
+            (json!(false), Condition::FALSE),
+        ];
+        for (json, condition) in cases {
+            let parsed: Condition = serde_json::from_value(json.clone()).unwrap();
+            assert_eq!(parsed, condition);
+            assert_eq!(serde_json::to_value(&condition).unwrap(), json);
+        }
+        for json in [
+            json!(["name", "~=", "sw0"]),
+            json!(["name", "=="]),
+            json!(1),
+        ] {
+            assert!(serde_json::from_value::<Condition>(json).is_err());
+        }
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let uuid = Uuid::from_u128(1);
+        let row = Row::from([
+            ("name".to_string(), Datum::from("sw0")),
+            ("tag".to_string(), Datum::from(7)),
+            ("ports".to_string(), Datum::set(["p0", "p1"])),
+            (
+                "external_ids".to_string(),
+                Datum::map([("k0", "v0"), ("k1", "v1")]),
+            ),
+        ]);
+        let holds = |condition: Condition| condition.evaluate(&uuid, &row);
+
+        assert!(holds(Condition::eq("name", "sw0")));
+        assert!(holds(Condition::ne("name", "sw1")));
+        assert!(holds(Condition::eq("_uuid", uuid)));
+        assert!(!holds(Condition::eq("missing", "sw0")));
+        assert!(holds(Condition::lt("tag", 8)));
+        assert!(holds(Condition::ge("tag", 7.0)));
+        assert!(!holds(Condition::gt("tag", 7)));
+        assert!(!holds(Condition::lt("name", "z")));
+
+        assert!(holds(Condition::includes("ports", Datum::set(["p1"]))));
+        assert!(holds(Condition::includes("ports", Datum::empty_set())));
+        assert!(!holds(Condition::includes(
+            "ports",
+            Datum::set(["p1", "p2"])
+        )));
+        assert!(holds(Condition::excludes(
+            "ports",
+            Datum::set(["p2", "p3"])
+        )));
+        assert!(!holds(Condition::excludes("ports", Datum::set(["p0"]))));
+
+        // maps compare pairs, not just keys
+        assert!(holds(Condition::includes(
+            "external_ids",
+            Datum::map([("k1", "v1")])
+        )));
+        assert!(!holds(Condition::includes(
+            "external_ids",
+            Datum::map([("k1", "v0")])
+        )));
+        assert!(holds(Condition::excludes(
+            "external_ids",
+            Datum::map([("k1", "v0")])
+        )));
+
+        assert!(holds(Condition::TRUE));
+        assert!(!holds(Condition::FALSE));
+    }
+}
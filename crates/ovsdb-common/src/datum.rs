@@ -0,0 +1,572 @@
+use crate::common::AtomicType;
+use serde::de::{self, Deserializer};
+use serde::ser::{SerializeSeq, Serializer};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
+use std::hash::{Hash, Hasher};
+
+pub use uuid::Uuid;
+
+const SET_HEADER: &str = "set";
+const MAP_HEADER: &str = "map";
+const UUID_HEADER: &str = "uuid";
+const NAMED_UUID_HEADER: &str = "named-uuid";
+
+// ovsdb atom
+// https://tools.ietf.org/html/rfc7047#section-5.1
+// An <atom> is a scalar value of one of the atomic types. UUIDs travel as
+// ["uuid", "<uuid>"]. A <named-uuid> is a 2-element array ["named-uuid", "<id>"]
+// that stands for the UUID of the row inserted by an "insert" operation with
+// that "uuid-name" within the same transaction, e.g. ["named-uuid", "myrow"].
+#[derive(Debug, Clone)]
+pub enum Atom {
+    Integer(i64),
+    Real(f64),
+    Boolean(bool),
+    String(String),
+    Uuid(Uuid),
+    NamedUuid(String),
+}
+
+impl Atom {
+    /// The atomic type of the value. A named UUID is a `uuid`.
+    pub fn atomic_type(&self) -> AtomicType {
+        match self {
+            Atom::Integer(_) => AtomicType::Integer,
+            Atom::Real(_) => AtomicType::Real,
+            Atom::Boolean(_) => AtomicType::Boolean,
+            Atom::String(_) => AtomicType::String,
+            Atom::Uuid(_) | Atom::NamedUuid(_) => AtomicType::Uuid,
+        }
+    }
+
+    /// The value a column of type `ty` starts out with: zero, false, the
+    /// empty string or the all-zero UUID.
+    pub fn default_for(ty: AtomicType) -> Atom {
+        match ty {
+            AtomicType::Integer => Atom::Integer(0),
+            AtomicType::Real => Atom::Real(0.0),
+            AtomicType::Boolean => Atom::Boolean(false),
+            AtomicType::String => Atom::String(String::new()),
+            AtomicType::Uuid => Atom::Uuid(Uuid::nil()),
+        }
+    }
+
+    /// Converts the atom to `ty`, if it is a valid `ty`.
+    ///
+    /// JSON does not distinguish `1` from `1.0`, so an integer is accepted
+    /// where a real is expected; every other conversion fails.
+    pub fn coerce(self, ty: AtomicType) -> Option<Atom> {
+        match (self, ty) {
+            (Atom::Integer(i), AtomicType::Real) => Some(Atom::Real(i as f64)),
+            (atom, ty) if atom.atomic_type() == ty => Some(atom),
+            _ => None,
+        }
+    }
+
+    pub fn as_integer(&self) -> Option<i64> {
+        match self {
+            Atom::Integer(i) => Some(*i),
+            _ => None,
+        }
+    }
+
+    pub fn as_real(&self) -> Option<f64> {
+        match self {
+            Atom::Real(r) => Some(*r),
+            Atom::Integer(i) => Some(*i as f64),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Atom::Boolean(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Atom::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_uuid(&self) -> Option<&Uuid> {
+        match self {
+            Atom::Uuid(uuid) => Some(uuid),
+            _ => None,
+        }
+    }
+
+    // atoms of different types never compare equal, this only gives them a
+    // stable position relative to each other
+    fn rank(&self) -> u8 {
+        match self {
+            Atom::Integer(_) => 0,
+            Atom::Real(_) => 1,
+            Atom::Boolean(_) => 2,
+            Atom::String(_) => 3,
+            Atom::Uuid(_) => 4,
+            Atom::NamedUuid(_) => 5,
+        }
+    }
+}
+
+// reals compare numerically, so 0.0 and -0.0 are the same atom as in
+// ovsdb-server; total_cmp keeps the order total for the values JSON cannot carry
+fn cmp_real(a: f64, b: f64) -> Ordering {
+    if a == b {
+        Ordering::Equal
+    } else {
+        a.total_cmp(&b)
+    }
+}
+
+impl Ord for Atom {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Atom::Integer(a), Atom::Integer(b)) => a.cmp(b),
+            (Atom::Real(a), Atom::Real(b)) => cmp_real(*a, *b),
+            (Atom::Boolean(a), Atom::Boolean(b)) => a.cmp(b),
+            (Atom::String(a), Atom::String(b)) => a.cmp(b),
+            (Atom::Uuid(a), Atom::Uuid(b)) => a.cmp(b),
+            (Atom::NamedUuid(a), Atom::NamedUuid(b)) => a.cmp(b),
+            _ => self.rank().cmp(&other.rank()),
+        }
+    }
+}
+
+impl PartialOrd for Atom {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Atom {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Atom {}
+
+impl Hash for Atom {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.rank().hash(state);
+        match self {
+            Atom::Integer(i) => i.hash(state),
+            // 0.0 == -0.0, so both must hash alike
+            Atom::Real(r) if *r == 0.0 => 0u64.hash(state),
+            Atom::Real(r) => r.to_bits().hash(state),
+            Atom::Boolean(b) => b.hash(state),
+            Atom::String(s) | Atom::NamedUuid(s) => s.hash(state),
+            Atom::Uuid(uuid) => uuid.hash(state),
+        }
+    }
+}
+
+impl From<i64> for Atom {
+    fn from(value: i64) -> Self {
+        Atom::Integer(value)
+    }
+}
+
+impl From<f64> for Atom {
+    fn from(value: f64) -> Self {
+        Atom::Real(value)
+    }
+}
+
+impl From<bool> for Atom {
+    fn from(value: bool) -> Self {
+        Atom::Boolean(value)
+    }
+}
+
+impl From<&str> for Atom {
+    fn from(value: &str) -> Self {
+        Atom::String(value.to_string())
+    }
+}
+
+impl From<String> for Atom {
+    fn from(value: String) -> Self {
+        Atom::String(value)
+    }
+}
+
+impl From<Uuid> for Atom {
+    fn from(value: Uuid) -> Self {
+        Atom::Uuid(value)
+    }
+}
+
+impl Serialize for Atom {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Atom::Integer(i) => serializer.serialize_i64(*i),
+            Atom::Real(r) => serializer.serialize_f64(*r),
+            Atom::Boolean(b) => serializer.serialize_bool(*b),
+            Atom::String(s) => serializer.serialize_str(s),
+            Atom::Uuid(uuid) => (UUID_HEADER, uuid.hyphenated().to_string()).serialize(serializer),
+            Atom::NamedUuid(name) => (NAMED_UUID_HEADER, name).serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Atom {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = Value::deserialize(deserializer)?;
+        atom_from_value(value).map_err(de::Error::custom)
+    }
+}
+
+fn atom_from_value(value: Value) -> Result<Atom, String> {
+    match value {
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => Ok(Atom::Integer(i)),
+            None => n
+                .as_f64()
+                .map(Atom::Real)
+                .ok_or_else(|| format!("number {} out of range", n)),
+        },
+        Value::Bool(b) => Ok(Atom::Boolean(b)),
+        Value::String(s) => Ok(Atom::String(s)),
+        Value::Array(arr) => match tagged(&arr) {
+            Some((UUID_HEADER, Value::String(s))) => Uuid::parse_str(s)
+                .map(Atom::Uuid)
+                .map_err(|e| format!("invalid uuid '{}': {}", s, e)),
+            Some((NAMED_UUID_HEADER, Value::String(s))) => Ok(Atom::NamedUuid(s.clone())),
+            _ => Err(format!("expected an atom, got {}", Value::Array(arr))),
+        },
+        other => Err(format!("expected an atom, got {}", other)),
+    }
+}
+
+// splits ["<tag>", <payload>]
+fn tagged(arr: &[Value]) -> Option<(&str, &Value)> {
+    match arr {
+        [Value::String(tag), payload] => Some((tag.as_str(), payload)),
+        _ => None,
+    }
+}
+
+// ovsdb datum
+// https://tools.ietf.org/html/rfc7047#section-5.1
+// A <value> is an <atom>, a <set> ["set", [<atom>*]] or a <map>
+// ["map", [[<atom>, <atom>]*]]. A set with exactly one element may be sent as
+// the bare atom, so a scalar and a one-element set are the same datum.
+#[derive(Debug, Clone)]
+pub enum Datum {
+    Scalar(Atom),
+    Set(BTreeSet<Atom>),
+    Map(BTreeMap<Atom, Atom>),
+}
+
+impl Datum {
+    pub fn empty_set() -> Self {
+        Datum::Set(BTreeSet::new())
+    }
+
+    pub fn empty_map() -> Self {
+        Datum::Map(BTreeMap::new())
+    }
+
+    pub fn set<I, A>(atoms: I) -> Self
+    where
+        I: IntoIterator<Item = A>,
+        A: Into<Atom>,
+    {
+        Datum::Set(atoms.into_iter().map(Into::into).collect())
+    }
+
+    pub fn map<I, K, V>(pairs: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<Atom>,
+        V: Into<Atom>,
+    {
+        Datum::Map(
+            pairs
+                .into_iter()
+                .map(|(k, v)| (k.into(), v.into()))
+                .collect(),
+        )
+    }
+
+    /// Number of elements; a scalar has one.
+    pub fn len(&self) -> usize {
+        match self {
+            Datum::Scalar(_) => 1,
+            Datum::Set(set) => set.len(),
+            Datum::Map(map) => map.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn is_map(&self) -> bool {
+        matches!(self, Datum::Map(_))
+    }
+
+    /// The single atom of a scalar or one-element set.
+    pub fn as_atom(&self) -> Option<&Atom> {
+        match self {
+            Datum::Scalar(atom) => Some(atom),
+            Datum::Set(set) if set.len() == 1 => set.iter().next(),
+            _ => None,
+        }
+    }
+
+    pub fn as_map(&self) -> Option<&BTreeMap<Atom, Atom>> {
+        match self {
+            Datum::Map(map) => Some(map),
+            _ => None,
+        }
+    }
+
+    /// The elements of a scalar or set, or the keys of a map, in order.
+    pub fn keys(&self) -> Box<dyn Iterator<Item = &Atom> + '_> {
+        match self {
+            Datum::Scalar(atom) => Box::new(std::iter::once(atom)),
+            Datum::Set(set) => Box::new(set.iter()),
+            Datum::Map(map) => Box::new(map.keys()),
+        }
+    }
+
+    /// The values of a map, in key order; empty for scalars and sets.
+    pub fn values(&self) -> Box<dyn Iterator<Item = &Atom> + '_> {
+        match self {
+            Datum::Map(map) => Box::new(map.values()),
+            _ => Box::new(std::iter::empty()),
+        }
+    }
+
+    pub fn contains(&self, atom: &Atom) -> bool {
+        match self {
+            Datum::Scalar(a) => a == atom,
+            Datum::Set(set) => set.contains(atom),
+            Datum::Map(map) => map.contains_key(atom),
+        }
+    }
+}
+
+// Same order as ovsdb_datum_compare_3way(): fewer elements first, then
+// element-wise by key, then by value. Sets are kept sorted, so two sets with
+// the same elements are equal regardless of the order they arrived in.
+impl Ord for Datum {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.len()
+            .cmp(&other.len())
+            .then_with(|| self.keys().cmp(other.keys()))
+            .then_with(|| self.values().cmp(other.values()))
+    }
+}
+
+impl PartialOrd for Datum {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Datum {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Datum {}
+
+impl Hash for Datum {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+        self.keys().for_each(|atom| atom.hash(state));
+        self.values().for_each(|atom| atom.hash(state));
+    }
+}
+
+impl<T: Into<Atom>> From<T> for Datum {
+    fn from(value: T) -> Self {
+        Datum::Scalar(value.into())
+    }
+}
+
+struct Pairs<'a>(&'a BTreeMap<Atom, Atom>);
+
+impl Serialize for Pairs<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut seq = serializer.serialize_seq(Some(self.0.len()))?;
+        for pair in self.0.iter() {
+            seq.serialize_element(&pair)?;
+        }
+        seq.end()
+    }
+}
+
+impl Serialize for Datum {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Datum::Scalar(atom) => atom.serialize(serializer),
+            // like ovsdb-server, write one-element sets as the bare atom
+            Datum::Set(set) if set.len() == 1 => set.iter().next().unwrap().serialize(serializer),
+            Datum::Set(set) => (SET_HEADER, set).serialize(serializer),
+            Datum::Map(map) => (MAP_HEADER, Pairs(map)).serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Datum {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = Value::deserialize(deserializer)?;
+        datum_from_value(value).map_err(de::Error::custom)
+    }
+}
+
+fn datum_from_value(value: Value) -> Result<Datum, String> {
+    let arr = match value {
+        Value::Array(arr) => arr,
+        other => return atom_from_value(other).map(Datum::Scalar),
+    };
+    match tagged(&arr) {
+        Some((SET_HEADER, Value::Array(atoms))) => atoms
+            .iter()
+            .map(|atom| atom_from_value(atom.clone()))
+            .collect::<Result<_, _>>()
+            .map(Datum::Set),
+        Some((MAP_HEADER, Value::Array(pairs))) => pairs
+            .iter()
+            .map(|pair| match pair.as_array().map(Vec::as_slice) {
+                Some([k, v]) => Ok((atom_from_value(k.clone())?, atom_from_value(v.clone())?)),
+                _ => Err(format!("expected a [key, value] pair, got {}", pair)),
+            })
+            .collect::<Result<_, _>>()
+            .map(Datum::Map),
+        _ => atom_from_value(Value::Array(arr)).map(Datum::Scalar),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::datum::*;
+    use serde_json::json;
+
+    const UUID: &str = "36c4b7d2-1a2d-4b8c-9f3e-2c1b7a0e5d11";
+
+    #[test]
+    fn test_atom_round_trip() {
+        let uuid = Uuid::parse_str(UUID).unwrap();
+        let cases = vec![
+            (json!(42), Atom::Integer(42)),
+            (json!(1.5), Atom::Real(1.5)),
+            (json!(true), Atom::Boolean(true)),
+            (json!("sw0"), Atom::from("sw0")),
+            (json!(["uuid", UUID]), Atom::Uuid(uuid)),
+            (
+                json!(["named-uuid", "row0"]),
+                Atom::NamedUuid("row0".to_string()),
+            ),
+        ];
+        for (json, atom) in cases {
+            let parsed: Atom = serde_json::from_value(json.clone()).unwrap();
+            assert_eq!(parsed, atom);
+            assert_eq!(serde_json::to_value(&atom).unwrap(), json);
+        }
+    }
+
+    #[test]
+    fn test_atom_invalid() {
+        for json in [
+            json!(null),
+            json!({"a": 1}),
+            json!(["uuid", "not-a-uuid"]),
+            json!(["set", []]),
+        ] {
+            assert!(serde_json::from_value::<Atom>(json).is_err());
+        }
+    }
+
+    #[test]
+    fn test_atom_coerce() {
+        assert_eq!(
+            Atom::Integer(2).coerce(AtomicType::Real),
+            Some(Atom::Real(2.0))
+        );
+        assert_eq!(Atom::Real(2.0).coerce(AtomicType::Integer), None);
+        assert_eq!(
+            Atom::NamedUuid("row0".to_string()).coerce(AtomicType::Uuid),
+            Some(Atom::NamedUuid("row0".to_string()))
+        );
+        assert_eq!(Atom::from("1").coerce(AtomicType::Integer), None);
+    }
+
+    #[test]
+    fn test_set_order_independent() {
+        let a: Datum = serde_json::from_value(json!(["set", ["b", "a", "c"]])).unwrap();
+        let b: Datum = serde_json::from_value(json!(["set", ["c", "b", "a"]])).unwrap();
+        assert_eq!(a, b);
+        assert_eq!(
+            serde_json::to_value(&a).unwrap(),
+            json!(["set", ["a", "b", "c"]])
+        );
+    }
+
+    #[test]
+    fn test_single_element_set_is_scalar() {
+        let set = Datum::set([7]);
+        assert_eq!(set, Datum::from(7));
+        assert_eq!(serde_json::to_value(&set).unwrap(), json!(7));
+        assert_eq!(
+            serde_json::to_value(Datum::empty_set()).unwrap(),
+            json!(["set", []])
+        );
+    }
+
+    #[test]
+    fn test_map_round_trip() {
+        let json = json!(["map", [["k1", "v1"], ["k0", "v0"]]]);
+        let datum: Datum = serde_json::from_value(json).unwrap();
+        assert_eq!(datum, Datum::map([("k0", "v0"), ("k1", "v1")]));
+        assert_eq!(
+            serde_json::to_value(&datum).unwrap(),
+            json!(["map", [["k0", "v0"], ["k1", "v1"]]])
+        );
+        assert!(serde_json::from_value::<Datum>(json!(["map", [["k0"]]])).is_err());
+    }
+
+    #[test]
+    fn test_uuid_datum() {
+        let datum: Datum = serde_json::from_value(json!(["uuid", UUID])).unwrap();
+        assert_eq!(datum, Datum::from(Uuid::parse_str(UUID).unwrap()));
+    }
+
+    #[test]
+    fn test_datum_ordering() {
+        // fewer elements sort first, then element-wise
+        assert!(Datum::empty_set() < Datum::from(0));
+        assert!(Datum::set([5]) < Datum::set([1, 2]));
+        assert!(Datum::set([1, 2]) < Datum::set([1, 3]));
+        assert!(Datum::map([("a", 1)]) < Datum::map([("a", 2)]));
+        assert_eq!(Datum::from(0.0), Datum::from(-0.0));
+    }
+}
@@ -1,9 +1,369 @@
+use crate::mutation::MutationError;
+use serde::de::{self, Deserializer};
+use serde::ser::{SerializeMap, Serializer};
 use serde::{Deserialize, Serialize};
-// use serde_json;
+use serde_json::{Map, Value};
+use std::error::Error as _;
+use std::fmt;
+use std::sync::Arc;
 
-// error
-#[derive(Debug, Serialize, Deserialize)]
-pub struct Error {
-    error: String,
-    details: Option<String>,
+/// The members of an `<error>` besides "error" itself, kept as they were
+/// sent.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Payload {
+    pub details: Option<String>,
+    /// Any other members, such as the "syntax" ovsdb-server adds to syntax
+    /// errors or the "io-error" it adds to I/O errors.
+    pub extra: Map<String, Value>,
+}
+
+impl Payload {
+    pub fn with_details(details: impl Into<String>) -> Self {
+        Payload {
+            details: Some(details.into()),
+            extra: Map::new(),
+        }
+    }
+}
+
+// errors
+// https://tools.ietf.org/html/rfc7047#section-3.1
+// An <error> is {"error": <string>, "details": <string>, ...}. The "error"
+// strings below are the ones RFC 7047 defines for transactions and
+// locking, plus "not leader" from clustered ovsdb-server.
+// https://docs.openvswitch.org/en/latest/ref/ovsdb-server.7/
+#[derive(Debug, Clone)]
+pub enum Error {
+    /// "referential integrity violation": a strong reference to a row that
+    /// does not exist, or a delete that would leave one behind.
+    ReferentialIntegrityViolation(Payload),
+    /// "constraint violation": a value breaks the column type, a table its
+    /// `maxRows` or an index, or a row got left unreferenced.
+    ConstraintViolation(Payload),
+    /// "resources exhausted": the server ran out of memory or the like.
+    ResourcesExhausted(Payload),
+    /// "I/O error": the server could not write the database.
+    IoError(Payload),
+    /// "duplicate uuid-name": two inserts used the same "uuid-name".
+    DuplicateUuidName(Payload),
+    /// "domain error": a mutation divided by zero.
+    DomainError(Payload),
+    /// "range error": a mutation overflowed.
+    RangeError(Payload),
+    /// "timed out": a "wait" operation gave up.
+    TimedOut(Payload),
+    /// "not supported": the request needs a feature the server lacks.
+    NotSupported(Payload),
+    /// "aborted": the transaction ran an "abort" operation.
+    Aborted(Payload),
+    /// "not owner": an "assert" on a lock the client does not hold.
+    NotOwner(Payload),
+    /// "unknown database": no database has the requested name.
+    UnknownDatabase(Payload),
+    /// "not leader": a clustered server that is not the leader refused
+    /// the request.
+    NotLeader(Payload),
+    /// Any other "error", such as "syntax error" or "unknown method".
+    Other { error: String, payload: Payload },
+    /// Reading from or writing to the connection failed.
+    Transport(Arc<std::io::Error>),
+    /// A message was not JSON, or not of the expected shape.
+    Json(Arc<serde_json::Error>),
+    /// A value does not fit the schema, such as an unknown column or a
+    /// mutation that does not apply to its column.
+    Schema(Arc<dyn std::error::Error + Send + Sync>),
+}
+
+impl Error {
+    /// Builds the error the "error" string names, with the given details.
+    pub fn new(error: impl Into<String>, details: Option<String>) -> Self {
+        Error::from_payload(
+            error,
+            Payload {
+                details,
+                extra: Map::new(),
+            },
+        )
+    }
+
+    pub fn from_payload(error: impl Into<String>, payload: Payload) -> Self {
+        let error = error.into();
+        match error.as_str() {
+            "referential integrity violation" => Error::ReferentialIntegrityViolation(payload),
+            "constraint violation" => Error::ConstraintViolation(payload),
+            "resources exhausted" => Error::ResourcesExhausted(payload),
+            "I/O error" => Error::IoError(payload),
+            "duplicate uuid-name" => Error::DuplicateUuidName(payload),
+            "domain error" => Error::DomainError(payload),
+            "range error" => Error::RangeError(payload),
+            "timed out" => Error::TimedOut(payload),
+            "not supported" => Error::NotSupported(payload),
+            "aborted" => Error::Aborted(payload),
+            "not owner" => Error::NotOwner(payload),
+            "unknown database" => Error::UnknownDatabase(payload),
+            "not leader" => Error::NotLeader(payload),
+            _ => Error::Other { error, payload },
+        }
+    }
+
+    pub fn schema(error: impl std::error::Error + Send + Sync + 'static) -> Self {
+        Error::Schema(Arc::new(error))
+    }
+
+    /// The "error" member on the wire. Transport, JSON and schema errors
+    /// never come from the server, and go out as "transport error",
+    /// "syntax error" and "schema error".
+    pub fn error(&self) -> &str {
+        match self {
+            Error::ReferentialIntegrityViolation(_) => "referential integrity violation",
+            Error::ConstraintViolation(_) => "constraint violation",
+            Error::ResourcesExhausted(_) => "resources exhausted",
+            Error::IoError(_) => "I/O error",
+            Error::DuplicateUuidName(_) => "duplicate uuid-name",
+            Error::DomainError(_) => "domain error",
+            Error::RangeError(_) => "range error",
+            Error::TimedOut(_) => "timed out",
+            Error::NotSupported(_) => "not supported",
+            Error::Aborted(_) => "aborted",
+            Error::NotOwner(_) => "not owner",
+            Error::UnknownDatabase(_) => "unknown database",
+            Error::NotLeader(_) => "not leader",
+            Error::Other { error, .. } => error,
+            Error::Transport(_) => "transport error",
+            Error::Json(_) => "syntax error",
+            Error::Schema(_) => "schema error",
+        }
+    }
+
+    /// What the peer sent along with the "error" string; `None` for errors
+    /// raised locally.
+    pub fn payload(&self) -> Option<&Payload> {
+        match self {
+            Error::ReferentialIntegrityViolation(payload)
+            | Error::ConstraintViolation(payload)
+            | Error::ResourcesExhausted(payload)
+            | Error::IoError(payload)
+            | Error::DuplicateUuidName(payload)
+            | Error::DomainError(payload)
+            | Error::RangeError(payload)
+            | Error::TimedOut(payload)
+            | Error::NotSupported(payload)
+            | Error::Aborted(payload)
+            | Error::NotOwner(payload)
+            | Error::UnknownDatabase(payload)
+            | Error::NotLeader(payload)
+            | Error::Other { payload, .. } => Some(payload),
+            Error::Transport(_) | Error::Json(_) | Error::Schema(_) => None,
+        }
+    }
+
+    pub fn details(&self) -> Option<&str> {
+        self.payload()?.details.as_deref()
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            // the cause is the source, not part of the message
+            Error::Transport(_) => f.write_str("transport error"),
+            Error::Json(_) => f.write_str("JSON error"),
+            Error::Schema(_) => f.write_str("schema error"),
+            _ => match self.details() {
+                Some(details) => write!(f, "{}: {}", self.error(), details),
+                None => f.write_str(self.error()),
+            },
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Transport(e) => Some(e.as_ref()),
+            Error::Json(e) => Some(e.as_ref()),
+            Error::Schema(e) => Some(e.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+// errors raised locally have no payload and compare by their cause
+impl PartialEq for Error {
+    fn eq(&self, other: &Self) -> bool {
+        self.error() == other.error()
+            && match (self.payload(), other.payload()) {
+                (Some(a), Some(b)) => a == b,
+                (None, None) => {
+                    self.source().map(ToString::to_string)
+                        == other.source().map(ToString::to_string)
+                }
+                _ => false,
+            }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Error::Transport(Arc::new(error))
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(error: serde_json::Error) -> Self {
+        Error::Json(Arc::new(error))
+    }
+}
+
+impl From<MutationError> for Error {
+    fn from(error: MutationError) -> Self {
+        Error::new(error.rfc_error(), Some(error.to_string()))
+    }
+}
+
+impl Serialize for Error {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("error", self.error())?;
+        match self.payload() {
+            Some(payload) => {
+                if let Some(details) = &payload.details {
+                    map.serialize_entry("details", details)?;
+                }
+                for (key, value) in &payload.extra {
+                    map.serialize_entry(key, value)?;
+                }
+            }
+            None => {
+                if let Some(source) = self.source() {
+                    map.serialize_entry("details", &source.to_string())?;
+                }
+            }
+        }
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Error {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let mut object = Map::<String, Value>::deserialize(deserializer)?;
+        let error = match object.remove("error") {
+            Some(Value::String(error)) => error,
+            _ => return Err(de::Error::custom("expected 'error' to be a string")),
+        };
+        let details = match object.remove("details") {
+            None | Some(Value::Null) => None,
+            Some(Value::String(details)) => Some(details),
+            // keep what does not fit instead of failing the whole response
+            Some(other) => Some(other.to_string()),
+        };
+        Ok(Error::from_payload(
+            error,
+            Payload {
+                details,
+                extra: object,
+            },
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_error_round_trip() {
+        let json = json!({
+            "error": "syntax error",
+            "details": "unknown column 'nmae'",
+            "syntax": "[\"nmae\",\"==\",\"sw0\"]"
+        });
+        let error: Error = serde_json::from_value(json.clone()).unwrap();
+        match &error {
+            Error::Other { error, payload } => {
+                assert_eq!(error, "syntax error");
+                assert_eq!(payload.extra["syntax"], "[\"nmae\",\"==\",\"sw0\"]");
+            }
+            other => panic!("Expected Error::Other, got {:?}", other),
+        }
+        assert_eq!(serde_json::to_value(&error).unwrap(), json);
+
+        let error: Error = serde_json::from_value(json!({"error": "not leader"})).unwrap();
+        assert_eq!(error, Error::NotLeader(Payload::default()));
+        assert_eq!(error.details(), None);
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            json!({"error": "not leader"})
+        );
+
+        assert!(serde_json::from_value::<Error>(json!({"details": "no error"})).is_err());
+    }
+
+    #[test]
+    fn test_rfc_errors() {
+        for error in [
+            "referential integrity violation",
+            "constraint violation",
+            "resources exhausted",
+            "I/O error",
+            "duplicate uuid-name",
+            "domain error",
+            "range error",
+            "timed out",
+            "not supported",
+            "aborted",
+            "not owner",
+            "unknown database",
+            "not leader",
+        ] {
+            let parsed = Error::new(error, None);
+            assert!(!matches!(parsed, Error::Other { .. }), "{}", error);
+            assert_eq!(parsed.error(), error);
+        }
+        assert_eq!(
+            Error::new(
+                "unknown database",
+                Some("no database named Foo".to_string())
+            )
+            .to_string(),
+            "unknown database: no database named Foo"
+        );
+        assert_eq!(
+            Error::from(MutationError::DivisionByZero),
+            Error::DomainError(Payload::with_details("division by zero"))
+        );
+    }
+
+    #[test]
+    fn test_source_chain() {
+        let error = Error::from(std::io::Error::new(
+            std::io::ErrorKind::ConnectionReset,
+            "reset by peer",
+        ));
+        assert_eq!(error.to_string(), "transport error");
+        let source = error.source().unwrap();
+        assert_eq!(source.to_string(), "reset by peer");
+        assert_eq!(
+            source.downcast_ref::<std::io::Error>().unwrap().kind(),
+            std::io::ErrorKind::ConnectionReset
+        );
+        assert_eq!(error.payload(), None);
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            json!({"error": "transport error", "details": "reset by peer"})
+        );
+
+        let json = Error::from(serde_json::from_str::<Value>("{").unwrap_err());
+        assert_eq!(json.to_string(), "JSON error");
+        assert!(json.source().unwrap().is::<serde_json::Error>());
+        assert_ne!(
+            json,
+            Error::from(serde_json::from_str::<Value>("[").unwrap_err())
+        );
+        assert!(Error::new("aborted", None).source().is_none());
+    }
 }
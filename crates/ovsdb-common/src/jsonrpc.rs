@@ -0,0 +1,215 @@
+use crate::error::Error;
+use serde::de::{self, Deserializer};
+use serde::ser::{SerializeStruct, Serializer};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+// JSON-RPC 1.0 messages
+// https://tools.ietf.org/html/rfc7047#section-4
+// OVSDB uses JSON-RPC 1.0 over a stream: messages are plain JSON objects
+// written back to back, with no delimiter or length prefix.
+
+/// A request that expects a response carrying the same `id`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Request {
+    pub method: String,
+    pub params: Value,
+    pub id: Value,
+}
+
+/// A response to a [`Request`]. Exactly one of `result` and `error` is meaningful.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Response {
+    pub result: Value,
+    pub error: Option<Error>,
+    pub id: Value,
+}
+
+/// A request with a null `id`, such as "update", "locked" or "stolen".
+/// The receiver never replies to a notification.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Notification {
+    pub method: String,
+    pub params: Value,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Message {
+    Request(Request),
+    Response(Response),
+    Notification(Notification),
+}
+
+impl Request {
+    pub fn new(method: impl Into<String>, params: Value, id: Value) -> Self {
+        Request {
+            method: method.into(),
+            params,
+            id,
+        }
+    }
+}
+
+impl Response {
+    pub fn ok(id: Value, result: Value) -> Self {
+        Response {
+            result,
+            error: None,
+            id,
+        }
+    }
+
+    pub fn err(id: Value, error: Error) -> Self {
+        Response {
+            result: Value::Null,
+            error: Some(error),
+            id,
+        }
+    }
+}
+
+impl Notification {
+    pub fn new(method: impl Into<String>, params: Value) -> Self {
+        Notification {
+            method: method.into(),
+            params,
+        }
+    }
+}
+
+impl From<Request> for Message {
+    fn from(request: Request) -> Self {
+        Message::Request(request)
+    }
+}
+
+impl From<Response> for Message {
+    fn from(response: Response) -> Self {
+        Message::Response(response)
+    }
+}
+
+impl From<Notification> for Message {
+    fn from(notification: Notification) -> Self {
+        Message::Notification(notification)
+    }
+}
+
+impl Serialize for Message {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("Message", 3)?;
+        match self {
+            Message::Request(request) => {
+                state.serialize_field("method", &request.method)?;
+                state.serialize_field("params", &request.params)?;
+                state.serialize_field("id", &request.id)?;
+            }
+            Message::Notification(notification) => {
+                state.serialize_field("method", &notification.method)?;
+                state.serialize_field("params", &notification.params)?;
+                state.serialize_field("id", &Value::Null)?;
+            }
+            Message::Response(response) => {
+                state.serialize_field("result", &response.result)?;
+                state.serialize_field("error", &response.error)?;
+                state.serialize_field("id", &response.id)?;
+            }
+        }
+        state.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Message {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let mut object = Map::<String, Value>::deserialize(deserializer)?;
+        let id = object.remove("id").unwrap_or(Value::Null);
+
+        // requests and notifications carry a "method", responses never do
+        if let Some(method) = object.remove("method") {
+            let method = match method {
+                Value::String(s) => s,
+                _ => return Err(de::Error::custom("expected 'method' to be a string")),
+            };
+            let params = object
+                .remove("params")
+                .unwrap_or_else(|| Value::Array(vec![]));
+            return Ok(if id.is_null() {
+                Message::Notification(Notification { method, params })
+            } else {
+                Message::Request(Request { method, params, id })
+            });
+        }
+
+        if !object.contains_key("result") && !object.contains_key("error") {
+            return Err(de::Error::custom(
+                "expected a request, notification or response",
+            ));
+        }
+        let result = object.remove("result").unwrap_or(Value::Null);
+        let error = match object.remove("error") {
+            None | Some(Value::Null) => None,
+            Some(error) => Some(serde_json::from_value(error).map_err(de::Error::custom)?),
+        };
+        Ok(Message::Response(Response { result, error, id }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::jsonrpc::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_request_round_trip() {
+        let json = json!({"method": "list_dbs", "params": [], "id": 1});
+        let message: Message = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(
+            message,
+            Message::Request(Request::new("list_dbs", json!([]), json!(1)))
+        );
+        assert_eq!(serde_json::to_value(&message).unwrap(), json);
+    }
+
+    #[test]
+    fn test_notification_has_null_id() {
+        let json = json!({"method": "update", "params": [null, {}], "id": null});
+        let message: Message = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(
+            message,
+            Message::Notification(Notification::new("update", json!([null, {}])))
+        );
+        assert_eq!(serde_json::to_value(&message).unwrap(), json);
+    }
+
+    #[test]
+    fn test_response_with_error() {
+        let json = json!({
+            "result": null,
+            "error": {"error": "unknown database", "details": "no database named Foo"},
+            "id": 7
+        });
+        let message: Message = serde_json::from_value(json.clone()).unwrap();
+        match &message {
+            Message::Response(response) => {
+                let error = response.error.as_ref().unwrap();
+                assert_eq!(error.error(), "unknown database");
+                assert_eq!(error.details(), Some("no database named Foo"));
+                assert_eq!(response.id, json!(7));
+            }
+            _ => panic!("Expected Message::Response"),
+        }
+        assert_eq!(serde_json::to_value(&message).unwrap(), json);
+    }
+
+    #[test]
+    fn test_response_without_result_or_error_is_rejected() {
+        let json = json!({"id": 1});
+        assert!(serde_json::from_value::<Message>(json).is_err());
+    }
+}
@@ -1,2 +1,7 @@
 pub mod common;
+pub mod condition;
+pub mod datum;
 pub mod error;
+pub mod jsonrpc;
+pub mod mutation;
+pub mod row;
@@ -0,0 +1,430 @@
+use crate::datum::{Atom, Datum};
+use serde::de::{self, Deserializer};
+use serde::{Deserialize, Serialize, Serializer};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::str::FromStr;
+
+// mutators
+// https://tools.ietf.org/html/rfc7047#section-5.1
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Mutator {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Insert,
+    Delete,
+}
+
+impl Mutator {
+    pub const ALL: [Mutator; 7] = [
+        Mutator::Add,
+        Mutator::Sub,
+        Mutator::Mul,
+        Mutator::Div,
+        Mutator::Rem,
+        Mutator::Insert,
+        Mutator::Delete,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Mutator::Add => "+=",
+            Mutator::Sub => "-=",
+            Mutator::Mul => "*=",
+            Mutator::Div => "/=",
+            Mutator::Rem => "%=",
+            Mutator::Insert => "insert",
+            Mutator::Delete => "delete",
+        }
+    }
+
+    /// `+=`, `-=`, `*=`, `/=` and `%=`, which apply to every element of an
+    /// integer or real scalar or set.
+    pub fn is_arithmetic(&self) -> bool {
+        !matches!(self, Mutator::Insert | Mutator::Delete)
+    }
+}
+
+impl fmt::Display for Mutator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Mutator {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Mutator::ALL
+            .into_iter()
+            .find(|mutator| mutator.as_str() == s)
+            .ok_or_else(|| format!("unknown mutator '{}'", s))
+    }
+}
+
+impl Serialize for Mutator {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Mutator {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+/// Why a mutation could not be applied to a value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MutationError {
+    /// The mutator does not apply to the value or the argument, such as
+    /// `+=` on a string. Checking the mutation against the column type
+    /// first rules this out.
+    TypeMismatch,
+    /// `/=` or `%=` by zero.
+    DivisionByZero,
+    /// The result of integer arithmetic does not fit in 64 bits, or that
+    /// of real arithmetic is not finite.
+    Overflow,
+    /// Arithmetic on a set turned two elements into the same value.
+    Duplicates,
+}
+
+impl MutationError {
+    /// The "error" member ovsdb-server replies with in this case.
+    pub fn rfc_error(&self) -> &'static str {
+        match self {
+            MutationError::TypeMismatch | MutationError::Duplicates => "constraint violation",
+            MutationError::DivisionByZero => "domain error",
+            MutationError::Overflow => "range error",
+        }
+    }
+}
+
+impl fmt::Display for MutationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            MutationError::TypeMismatch => "mutator does not apply to this type",
+            MutationError::DivisionByZero => "division by zero",
+            MutationError::Overflow => "result out of range",
+            MutationError::Duplicates => "result contains duplicate values",
+        })
+    }
+}
+
+impl std::error::Error for MutationError {}
+
+// ovsdb mutation
+// https://tools.ietf.org/html/rfc7047#section-5.1
+// A <mutation> is [<column>, <mutator>, <value>].
+//
+// Mutations are not checked against the column type here, as the schema
+// lives in ovsdb-schema; see `Table::check_mutation` there.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mutation {
+    pub column: String,
+    pub mutator: Mutator,
+    pub value: Datum,
+}
+
+impl Mutation {
+    pub fn new(column: impl Into<String>, mutator: Mutator, value: impl Into<Datum>) -> Self {
+        Mutation {
+            column: column.into(),
+            mutator,
+            value: value.into(),
+        }
+    }
+
+    pub fn add(column: impl Into<String>, value: impl Into<Datum>) -> Self {
+        Mutation::new(column, Mutator::Add, value)
+    }
+
+    pub fn sub(column: impl Into<String>, value: impl Into<Datum>) -> Self {
+        Mutation::new(column, Mutator::Sub, value)
+    }
+
+    pub fn mul(column: impl Into<String>, value: impl Into<Datum>) -> Self {
+        Mutation::new(column, Mutator::Mul, value)
+    }
+
+    pub fn div(column: impl Into<String>, value: impl Into<Datum>) -> Self {
+        Mutation::new(column, Mutator::Div, value)
+    }
+
+    pub fn rem(column: impl Into<String>, value: impl Into<Datum>) -> Self {
+        Mutation::new(column, Mutator::Rem, value)
+    }
+
+    pub fn insert(column: impl Into<String>, value: impl Into<Datum>) -> Self {
+        Mutation::new(column, Mutator::Insert, value)
+    }
+
+    pub fn delete(column: impl Into<String>, value: impl Into<Datum>) -> Self {
+        Mutation::new(column, Mutator::Delete, value)
+    }
+
+    /// The value of the column after the mutation, as ovsdb-server computes
+    /// it. The result may still break the constraints of the column type,
+    /// which are not known here.
+    ///
+    /// - arithmetic applies to every element, with integer division and
+    ///   remainder rounding toward zero
+    /// - `insert` adds elements, or map pairs whose key is not there yet
+    /// - `delete` removes elements, map pairs with the same key and value,
+    ///   or, given a set, map pairs with any of its keys
+    pub fn apply(&self, datum: &Datum) -> Result<Datum, MutationError> {
+        match self.mutator {
+            Mutator::Insert => Ok(insert(datum, &self.value)),
+            Mutator::Delete => Ok(delete(datum, &self.value)),
+            mutator => {
+                let argument = self.value.as_atom().ok_or(MutationError::TypeMismatch)?;
+                let elements = match datum {
+                    Datum::Map(_) => return Err(MutationError::TypeMismatch),
+                    Datum::Scalar(atom) => {
+                        return Ok(Datum::Scalar(arithmetic(mutator, atom, argument)?))
+                    }
+                    Datum::Set(set) => set,
+                };
+                let mutated = elements
+                    .iter()
+                    .map(|atom| arithmetic(mutator, atom, argument))
+                    .collect::<Result<BTreeSet<_>, _>>()?;
+                match mutated.len() == elements.len() {
+                    true => Ok(Datum::Set(mutated)),
+                    false => Err(MutationError::Duplicates),
+                }
+            }
+        }
+    }
+}
+
+fn arithmetic(mutator: Mutator, atom: &Atom, argument: &Atom) -> Result<Atom, MutationError> {
+    match (atom, argument) {
+        (Atom::Integer(a), Atom::Integer(b)) => {
+            if *b == 0 && matches!(mutator, Mutator::Div | Mutator::Rem) {
+                return Err(MutationError::DivisionByZero);
+            }
+            let result = match mutator {
+                Mutator::Add => a.checked_add(*b),
+                Mutator::Sub => a.checked_sub(*b),
+                Mutator::Mul => a.checked_mul(*b),
+                Mutator::Div => a.checked_div(*b),
+                Mutator::Rem => a.checked_rem(*b),
+                Mutator::Insert | Mutator::Delete => unreachable!("not arithmetic"),
+            };
+            result.map(Atom::Integer).ok_or(MutationError::Overflow)
+        }
+        // a real column takes integer arguments, as JSON cannot tell them apart
+        (Atom::Real(a), Atom::Real(_) | Atom::Integer(_)) => {
+            let b = argument.as_real().unwrap_or_default();
+            let result = match mutator {
+                Mutator::Add => a + b,
+                Mutator::Sub => a - b,
+                Mutator::Mul => a * b,
+                Mutator::Div if b == 0.0 => return Err(MutationError::DivisionByZero),
+                Mutator::Div => a / b,
+                _ => return Err(MutationError::TypeMismatch),
+            };
+            match result.is_finite() {
+                true => Ok(Atom::Real(result)),
+                false => Err(MutationError::Overflow),
+            }
+        }
+        _ => Err(MutationError::TypeMismatch),
+    }
+}
+
+// an empty set stands for an empty map as well
+fn as_map(datum: &Datum) -> Option<BTreeMap<Atom, Atom>> {
+    match datum {
+        Datum::Map(map) => Some(map.clone()),
+        Datum::Set(set) if set.is_empty() => Some(BTreeMap::new()),
+        _ => None,
+    }
+}
+
+fn insert(datum: &Datum, value: &Datum) -> Datum {
+    match (as_map(datum), value.as_map()) {
+        (Some(mut map), Some(pairs)) => {
+            for (key, value) in pairs {
+                map.entry(key.clone()).or_insert_with(|| value.clone());
+            }
+            Datum::Map(map)
+        }
+        _ if value.is_empty() => datum.clone(),
+        _ => Datum::Set(datum.keys().chain(value.keys()).cloned().collect()),
+    }
+}
+
+fn delete(datum: &Datum, value: &Datum) -> Datum {
+    match (datum.as_map(), value.as_map()) {
+        (Some(map), Some(pairs)) => Datum::Map(
+            map.iter()
+                .filter(|(key, value)| pairs.get(key) != Some(value))
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+        ),
+        (Some(map), None) => Datum::Map(
+            map.iter()
+                .filter(|(key, _)| !value.contains(key))
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+        ),
+        _ => Datum::Set(
+            datum
+                .keys()
+                .filter(|atom| !value.contains(atom))
+                .cloned()
+                .collect(),
+        ),
+    }
+}
+
+impl fmt::Display for Mutation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&serde_json::to_string(self).map_err(|_| fmt::Error)?)
+    }
+}
+
+impl Serialize for Mutation {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (&self.column, self.mutator, &self.value).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Mutation {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let (column, mutator, value) = <(String, Mutator, Datum)>::deserialize(deserializer)?;
+        Ok(Mutation {
+            column,
+            mutator,
+            value,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mutation::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_mutation_round_trip() {
+        let cases = vec![
+            (json!(["tag", "+=", 1]), Mutation::add("tag", 1)),
+            (json!(["n", "%=", 3]), Mutation::rem("n", 3)),
+            (
+                json!(["acls", "insert", ["set", [["named-uuid", "acl0"]]]]),
+                Mutation::insert("acls", Atom::NamedUuid("acl0".to_string())),
+            ),
+            (
+                json!(["external_ids", "delete", ["set", ["k0", "k1"]]]),
+                Mutation::delete("external_ids", Datum::set(["k0", "k1"])),
+            ),
+        ];
+        for (json, mutation) in cases {
+            let parsed: Mutation = serde_json::from_value(json.clone()).unwrap();
+            assert_eq!(parsed, mutation);
+            // one-element sets travel as the bare atom
+            let reparsed: Mutation =
+                serde_json::from_value(serde_json::to_value(&mutation).unwrap()).unwrap();
+            assert_eq!(reparsed, mutation);
+        }
+        for json in [json!(["n", "^=", 1]), json!(["n", "+="]), json!(true)] {
+            assert!(serde_json::from_value::<Mutation>(json).is_err());
+        }
+    }
+
+    #[test]
+    fn test_apply_arithmetic() {
+        assert_eq!(
+            Mutation::add("n", 2).apply(&Datum::from(5)),
+            Ok(Datum::from(7))
+        );
+        assert_eq!(
+            Mutation::div("n", 2).apply(&Datum::from(-7)),
+            Ok(Datum::from(-3))
+        );
+        assert_eq!(
+            Mutation::rem("n", 2).apply(&Datum::from(-7)),
+            Ok(Datum::from(-1))
+        );
+        assert_eq!(
+            Mutation::mul("n", 10).apply(&Datum::set([1, 2])),
+            Ok(Datum::set([10, 20]))
+        );
+        assert_eq!(
+            Mutation::div("n", 2).apply(&Datum::from(1.0)),
+            Ok(Datum::from(0.5))
+        );
+        assert_eq!(
+            Mutation::sub("n", 1).apply(&Datum::empty_set()),
+            Ok(Datum::empty_set())
+        );
+
+        assert_eq!(
+            Mutation::div("n", 0).apply(&Datum::from(1)),
+            Err(MutationError::DivisionByZero)
+        );
+        assert_eq!(
+            Mutation::add("n", 1).apply(&Datum::from(i64::MAX)),
+            Err(MutationError::Overflow)
+        );
+        assert_eq!(
+            Mutation::mul("n", 0).apply(&Datum::set([1, 2])),
+            Err(MutationError::Duplicates)
+        );
+        assert_eq!(
+            Mutation::rem("n", 2).apply(&Datum::from(1.5)),
+            Err(MutationError::TypeMismatch)
+        );
+        assert_eq!(
+            Mutation::add("n", 1).apply(&Datum::from("a")),
+            Err(MutationError::TypeMismatch)
+        );
+    }
+
+    #[test]
+    fn test_apply_insert_delete() {
+        let set = Datum::set(["a", "b"]);
+        assert_eq!(
+            Mutation::insert("s", Datum::set(["b", "c"])).apply(&set),
+            Ok(Datum::set(["a", "b", "c"]))
+        );
+        assert_eq!(
+            Mutation::delete("s", Datum::set(["b", "z"])).apply(&set),
+            Ok(Datum::from("a"))
+        );
+
+        let map = Datum::map([("k0", "v0"), ("k1", "v1")]);
+        // an existing key keeps its value
+        assert_eq!(
+            Mutation::insert("m", Datum::map([("k1", "new"), ("k2", "v2")])).apply(&map),
+            Ok(Datum::map([("k0", "v0"), ("k1", "v1"), ("k2", "v2")]))
+        );
+        assert_eq!(
+            Mutation::insert("m", Datum::map([("k0", "v0")])).apply(&Datum::empty_set()),
+            Ok(Datum::map([("k0", "v0")]))
+        );
+        // pairs only go with a matching value, keys regardless of it
+        assert_eq!(
+            Mutation::delete("m", Datum::map([("k0", "v0"), ("k1", "other")])).apply(&map),
+            Ok(Datum::map([("k1", "v1")]))
+        );
+        assert_eq!(
+            Mutation::delete("m", Datum::set(["k1"])).apply(&map),
+            Ok(Datum::map([("k0", "v0")]))
+        );
+    }
+}
@@ -0,0 +1,399 @@
+use crate::datum::{Atom, Datum, Uuid};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+
+/// A row as a map from column name to value.
+pub type Row = BTreeMap<String, Datum>;
+
+/// Conversion from an atom into a Rust value.
+pub trait FromAtom: Sized {
+    fn from_atom(atom: &Atom) -> Option<Self>;
+}
+
+/// Conversion from a Rust value into an atom.
+pub trait ToAtom {
+    fn to_atom(&self) -> Atom;
+}
+
+/// Conversion from a column value into a Rust value.
+///
+/// Scalars need exactly one element, `Option<T>` zero or one, and
+/// `BTreeSet<T>`/`BTreeMap<K, V>` take sets and maps of any size.
+pub trait FromDatum: Sized {
+    fn from_datum(datum: &Datum) -> Option<Self>;
+}
+
+/// Conversion from a Rust value into a column value.
+pub trait ToDatum {
+    fn to_datum(&self) -> Datum;
+}
+
+/// A Rust struct that mirrors the rows of one table.
+pub trait TableRow: Sized {
+    /// Name of the table in the schema.
+    const TABLE: &'static str;
+
+    fn uuid(&self) -> Uuid;
+
+    fn from_row(uuid: Uuid, row: &Row) -> Result<Self, ColumnError>;
+
+    fn to_row(&self) -> Row;
+}
+
+/// A column that is missing from a row or does not fit the Rust type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnError {
+    pub table: &'static str,
+    pub column: &'static str,
+}
+
+impl fmt::Display for ColumnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "column '{}' of table '{}' is missing or has the wrong type",
+            self.column, self.table
+        )
+    }
+}
+
+impl std::error::Error for ColumnError {}
+
+/// Reads `column` from `row` as a `T`.
+pub fn column<T: FromDatum>(
+    row: &Row,
+    table: &'static str,
+    column: &'static str,
+) -> Result<T, ColumnError> {
+    row.get(column)
+        .and_then(T::from_datum)
+        .ok_or(ColumnError { table, column })
+}
+
+/// Reads a scalar datum through `T`'s atom conversion.
+pub fn scalar_from_datum<T: FromAtom>(datum: &Datum) -> Option<T> {
+    datum.as_atom().and_then(T::from_atom)
+}
+
+/// A UUID that refers to a row of the table mirrored by `T`.
+pub struct Ref<T> {
+    uuid: Uuid,
+    table: PhantomData<fn() -> T>,
+}
+
+impl<T> Ref<T> {
+    pub fn new(uuid: Uuid) -> Self {
+        Ref {
+            uuid,
+            table: PhantomData,
+        }
+    }
+
+    pub fn uuid(&self) -> Uuid {
+        self.uuid
+    }
+}
+
+// implemented by hand so that T itself does not need to be Clone, Ord, ...
+impl<T> Clone for Ref<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Ref<T> {}
+
+impl<T> fmt::Debug for Ref<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Ref").field(&self.uuid).finish()
+    }
+}
+
+impl<T> PartialEq for Ref<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.uuid == other.uuid
+    }
+}
+
+impl<T> Eq for Ref<T> {}
+
+impl<T> PartialOrd for Ref<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for Ref<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.uuid.cmp(&other.uuid)
+    }
+}
+
+impl<T> Hash for Ref<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.uuid.hash(state)
+    }
+}
+
+impl<T> From<Uuid> for Ref<T> {
+    fn from(uuid: Uuid) -> Self {
+        Ref::new(uuid)
+    }
+}
+
+impl FromAtom for i64 {
+    fn from_atom(atom: &Atom) -> Option<Self> {
+        atom.as_integer()
+    }
+}
+
+impl FromAtom for f64 {
+    fn from_atom(atom: &Atom) -> Option<Self> {
+        atom.as_real()
+    }
+}
+
+impl FromAtom for bool {
+    fn from_atom(atom: &Atom) -> Option<Self> {
+        atom.as_bool()
+    }
+}
+
+impl FromAtom for String {
+    fn from_atom(atom: &Atom) -> Option<Self> {
+        atom.as_str().map(str::to_string)
+    }
+}
+
+impl FromAtom for Uuid {
+    fn from_atom(atom: &Atom) -> Option<Self> {
+        atom.as_uuid().copied()
+    }
+}
+
+impl<T> FromAtom for Ref<T> {
+    fn from_atom(atom: &Atom) -> Option<Self> {
+        atom.as_uuid().copied().map(Ref::new)
+    }
+}
+
+impl ToAtom for i64 {
+    fn to_atom(&self) -> Atom {
+        Atom::Integer(*self)
+    }
+}
+
+impl ToAtom for f64 {
+    fn to_atom(&self) -> Atom {
+        Atom::Real(*self)
+    }
+}
+
+impl ToAtom for bool {
+    fn to_atom(&self) -> Atom {
+        Atom::Boolean(*self)
+    }
+}
+
+impl ToAtom for String {
+    fn to_atom(&self) -> Atom {
+        Atom::String(self.clone())
+    }
+}
+
+impl ToAtom for Uuid {
+    fn to_atom(&self) -> Atom {
+        Atom::Uuid(*self)
+    }
+}
+
+impl<T> ToAtom for Ref<T> {
+    fn to_atom(&self) -> Atom {
+        Atom::Uuid(self.uuid)
+    }
+}
+
+macro_rules! scalar_datum {
+    ($($ty:ty),*) => {
+        $(
+            impl FromDatum for $ty {
+                fn from_datum(datum: &Datum) -> Option<Self> {
+                    scalar_from_datum(datum)
+                }
+            }
+
+            impl ToDatum for $ty {
+                fn to_datum(&self) -> Datum {
+                    Datum::Scalar(self.to_atom())
+                }
+            }
+        )*
+    };
+}
+
+scalar_datum!(i64, f64, bool, String, Uuid);
+
+impl<T> FromDatum for Ref<T> {
+    fn from_datum(datum: &Datum) -> Option<Self> {
+        scalar_from_datum(datum)
+    }
+}
+
+impl<T> ToDatum for Ref<T> {
+    fn to_datum(&self) -> Datum {
+        Datum::Scalar(self.to_atom())
+    }
+}
+
+impl<T: FromAtom> FromDatum for Option<T> {
+    fn from_datum(datum: &Datum) -> Option<Self> {
+        match datum {
+            Datum::Map(_) => None,
+            _ if datum.is_empty() => Some(None),
+            _ => scalar_from_datum(datum).map(Some),
+        }
+    }
+}
+
+impl<T: ToAtom> ToDatum for Option<T> {
+    fn to_datum(&self) -> Datum {
+        match self {
+            Some(value) => Datum::Scalar(value.to_atom()),
+            None => Datum::empty_set(),
+        }
+    }
+}
+
+impl<T: FromAtom + Ord> FromDatum for BTreeSet<T> {
+    fn from_datum(datum: &Datum) -> Option<Self> {
+        match datum {
+            Datum::Map(map) if !map.is_empty() => None,
+            _ => datum.keys().map(T::from_atom).collect(),
+        }
+    }
+}
+
+impl<T: ToAtom> ToDatum for BTreeSet<T> {
+    fn to_datum(&self) -> Datum {
+        Datum::Set(self.iter().map(ToAtom::to_atom).collect())
+    }
+}
+
+impl<T: FromAtom> FromDatum for Vec<T> {
+    fn from_datum(datum: &Datum) -> Option<Self> {
+        match datum {
+            Datum::Map(map) if !map.is_empty() => None,
+            _ => datum.keys().map(T::from_atom).collect(),
+        }
+    }
+}
+
+impl<T: ToAtom> ToDatum for Vec<T> {
+    fn to_datum(&self) -> Datum {
+        Datum::Set(self.iter().map(ToAtom::to_atom).collect())
+    }
+}
+
+impl<K: FromAtom + Ord, V: FromAtom> FromDatum for BTreeMap<K, V> {
+    fn from_datum(datum: &Datum) -> Option<Self> {
+        match datum {
+            Datum::Map(map) => map
+                .iter()
+                .map(|(k, v)| Some((K::from_atom(k)?, V::from_atom(v)?)))
+                .collect(),
+            // an empty set doubles as an empty map
+            _ if datum.is_empty() => Some(BTreeMap::new()),
+            _ => None,
+        }
+    }
+}
+
+impl<K: ToAtom, V: ToAtom> ToDatum for BTreeMap<K, V> {
+    fn to_datum(&self) -> Datum {
+        Datum::Map(
+            self.iter()
+                .map(|(k, v)| (k.to_atom(), v.to_atom()))
+                .collect(),
+        )
+    }
+}
+
+impl FromDatum for Datum {
+    fn from_datum(datum: &Datum) -> Option<Self> {
+        Some(datum.clone())
+    }
+}
+
+impl ToDatum for Datum {
+    fn to_datum(&self) -> Datum {
+        self.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::row::*;
+
+    struct Port;
+
+    #[test]
+    fn test_scalar() {
+        assert_eq!(i64::from_datum(&Datum::from(5)), Some(5));
+        assert_eq!(f64::from_datum(&Datum::from(5)), Some(5.0));
+        assert_eq!(String::from_datum(&Datum::from(5)), None);
+        assert_eq!(bool::from_datum(&Datum::empty_set()), None);
+        assert_eq!("sw0".to_string().to_datum(), Datum::from("sw0"));
+    }
+
+    #[test]
+    fn test_optional() {
+        assert_eq!(Option::<i64>::from_datum(&Datum::empty_set()), Some(None));
+        assert_eq!(Option::<i64>::from_datum(&Datum::from(1)), Some(Some(1)));
+        assert_eq!(Option::<i64>::from_datum(&Datum::set([1, 2])), None);
+        assert_eq!(None::<i64>.to_datum(), Datum::empty_set());
+    }
+
+    #[test]
+    fn test_set_and_map() {
+        let set = BTreeSet::<String>::from_datum(&Datum::set(["b", "a"])).unwrap();
+        assert_eq!(set.into_iter().collect::<Vec<_>>(), vec!["a", "b"]);
+
+        let map = BTreeMap::<String, i64>::from_datum(&Datum::map([("k", 1)])).unwrap();
+        assert_eq!(map.get("k"), Some(&1));
+        assert_eq!(map.to_datum(), Datum::map([("k", 1)]));
+        assert_eq!(
+            BTreeMap::<String, i64>::from_datum(&Datum::empty_set()),
+            Some(BTreeMap::new())
+        );
+        assert_eq!(BTreeSet::<i64>::from_datum(&Datum::map([(1, 2)])), None);
+    }
+
+    #[test]
+    fn test_ref() {
+        let uuid = Uuid::from_u128(1);
+        let port = Ref::<Port>::from_datum(&Datum::from(uuid)).unwrap();
+        assert_eq!(port.uuid(), uuid);
+        assert_eq!(port.to_datum(), Datum::from(uuid));
+        assert_eq!(Ref::<Port>::from_datum(&Datum::from("x")), None);
+    }
+
+    #[test]
+    fn test_column() {
+        let mut row = Row::new();
+        row.insert("name".to_string(), Datum::from("sw0"));
+        assert_eq!(
+            column::<String>(&row, "Logical_Switch", "name"),
+            Ok("sw0".to_string())
+        );
+        assert_eq!(
+            column::<i64>(&row, "Logical_Switch", "name"),
+            Err(ColumnError {
+                table: "Logical_Switch",
+                column: "name"
+            })
+        );
+    }
+}
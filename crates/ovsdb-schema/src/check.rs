@@ -0,0 +1,389 @@
+//! Semantic checks on a parsed schema, like `ovsdb-tool check-schema`.
+
+use crate::schema::{Column, Schema, Table};
+use crate::types::{BaseType, ColumnType, ConstrainedBaseType, MaxOrUnlimited, RefType};
+use std::collections::BTreeSet;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        })
+    }
+}
+
+/// One finding of [`Schema::validate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaDiagnostic {
+    pub severity: Severity,
+    /// Where in the schema JSON the problem is, e.g.
+    /// `$.tables.ACL.columns.label.type.key`.
+    pub path: String,
+    pub message: String,
+}
+
+impl SchemaDiagnostic {
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+impl fmt::Display for SchemaDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}: {}", self.severity, self.path, self.message)
+    }
+}
+
+/// Whether `name` is a valid identifier: `[A-Za-z_][A-Za-z0-9_]*`.
+fn is_id(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Whether `version` has the `<x>.<y>.<z>` form OVS requires.
+fn is_version(version: &str) -> bool {
+    let parts: Vec<&str> = version.split('.').collect();
+    parts.len() == 3
+        && parts.iter().all(|part| {
+            !part.is_empty()
+                && part.chars().all(|c| c.is_ascii_digit())
+                && (part.len() == 1 || !part.starts_with('0'))
+        })
+}
+
+#[derive(Default)]
+struct Diagnostics(Vec<SchemaDiagnostic>);
+
+impl Diagnostics {
+    fn error(&mut self, path: &str, message: String) {
+        self.push(Severity::Error, path, message);
+    }
+
+    fn warning(&mut self, path: &str, message: String) {
+        self.push(Severity::Warning, path, message);
+    }
+
+    fn push(&mut self, severity: Severity, path: &str, message: String) {
+        self.0.push(SchemaDiagnostic {
+            severity,
+            path: path.to_string(),
+            message,
+        });
+    }
+}
+
+impl Schema {
+    /// Checks everything that parsing alone does not: references, indexes,
+    /// constraint bounds, names and the version string.
+    pub fn validate(&self) -> Vec<SchemaDiagnostic> {
+        let mut diags = Diagnostics::default();
+        if !is_id(&self.name) {
+            diags.error(
+                "$.name",
+                format!("\"{}\" is not a valid identifier", self.name),
+            );
+        }
+        match &self.version {
+            Some(version) if !is_version(version) => diags.error(
+                "$.version",
+                format!("\"{}\" is not of the form <x>.<y>.<z>", version),
+            ),
+            Some(_) => {}
+            None => diags.warning("$", "schema has no version".to_string()),
+        }
+
+        let mut names: Vec<&String> = self.tables.keys().collect();
+        names.sort();
+        for name in names {
+            self.check_table(name, &self.tables[name], &mut diags);
+        }
+        diags.0
+    }
+
+    fn check_table(&self, name: &str, table: &Table, diags: &mut Diagnostics) {
+        let path = format!("$.tables.{}", name);
+        if !is_id(name) {
+            diags.error(&path, format!("\"{}\" is not a valid table name", name));
+        }
+        if table.max_rows == Some(0) {
+            diags.error(
+                &format!("{}.maxRows", path),
+                "maxRows must be at least 1".to_string(),
+            );
+        }
+        if !table.is_root() && !self.is_strongly_referenced(name) {
+            diags.warning(
+                &path,
+                format!(
+                    "non-root table {} has no strong references, so its rows are always \
+                     garbage collected",
+                    name
+                ),
+            );
+        }
+
+        let mut columns: Vec<&String> = table.columns.keys().collect();
+        columns.sort();
+        for column in columns {
+            self.check_column(
+                &format!("{}.columns.{}", path, column),
+                column,
+                &table.columns[column],
+                diags,
+            );
+        }
+
+        for (i, index) in table.indexes.iter().flatten().enumerate() {
+            let index_path = format!("{}.indexes[{}]", path, i);
+            if index.is_empty() {
+                diags.error(&index_path, "index has no columns".to_string());
+            }
+            let mut seen = BTreeSet::new();
+            for (j, column) in index.iter().enumerate() {
+                let column_path = format!("{}[{}]", index_path, j);
+                match table.columns.get(column) {
+                    None if column == "_uuid" || column == "_version" => {}
+                    None => diags.error(
+                        &column_path,
+                        format!("index names unknown column {}", column),
+                    ),
+                    Some(def) if def.ephemeral => diags.error(
+                        &column_path,
+                        format!("ephemeral column {} may not be indexed", column),
+                    ),
+                    Some(_) => {}
+                }
+                if !seen.insert(column) {
+                    diags.error(&column_path, format!("index repeats column {}", column));
+                }
+            }
+        }
+    }
+
+    fn check_column(&self, path: &str, name: &str, column: &Column, diags: &mut Diagnostics) {
+        if name == "_uuid" || name == "_version" {
+            diags.error(path, format!("{} is a reserved column name", name));
+        } else if name.starts_with('_') {
+            diags.error(
+                path,
+                "column names beginning with \"_\" are reserved".to_string(),
+            );
+        } else if !is_id(name) {
+            diags.error(path, format!("\"{}\" is not a valid column name", name));
+        }
+
+        let ColumnType::Complex(complex) = &column.type_ else {
+            return;
+        };
+        let type_path = format!("{}.type", path);
+        if complex.min != 0 && complex.min != 1 {
+            diags.error(
+                &format!("{}.min", type_path),
+                format!("min must be 0 or 1, not {}", complex.min),
+            );
+        }
+        if let MaxOrUnlimited::Max(max) = complex.max {
+            if max < 1 {
+                diags.error(
+                    &format!("{}.max", type_path),
+                    format!("max must be at least 1, not {}", max),
+                );
+            } else if complex.min > max {
+                diags.error(
+                    &type_path,
+                    format!("min {} is greater than max {}", complex.min, max),
+                );
+            }
+        }
+        self.check_base(&format!("{}.key", type_path), &complex.key, diags);
+        if let Some(value) = &complex.value {
+            self.check_base(&format!("{}.value", type_path), value, diags);
+        }
+    }
+
+    fn check_base(&self, path: &str, base: &BaseType, diags: &mut Diagnostics) {
+        let BaseType::Constrained(constrained) = base else {
+            return;
+        };
+        let inverted = match constrained {
+            ConstrainedBaseType::BaseTypeInt {
+                min_integer: Some(min),
+                max_integer: Some(max),
+                ..
+            } if max < min => Some(("Integer", min.to_string(), max.to_string())),
+            ConstrainedBaseType::BaseTypeReal {
+                min_real: Some(min),
+                max_real: Some(max),
+                ..
+            } if max < min => Some(("Real", min.to_string(), max.to_string())),
+            ConstrainedBaseType::BaseTypeString {
+                min_length: Some(min),
+                max_length: Some(max),
+                ..
+            } if max < min => Some(("Length", min.to_string(), max.to_string())),
+            _ => None,
+        };
+        if let Some((what, min, max)) = inverted {
+            diags.error(
+                path,
+                format!("max{} {} is less than min{} {}", what, max, what, min),
+            );
+        }
+
+        match constrained {
+            ConstrainedBaseType::BaseTypeString {
+                min_length: Some(min),
+                ..
+            } if *min < 0 => diags.error(path, "minLength may not be negative".to_string()),
+            ConstrainedBaseType::BaseTypeUUID { ref_table, .. }
+                if !self.tables.contains_key(ref_table) =>
+            {
+                diags.error(
+                    &format!("{}.refTable", path),
+                    format!("refTable names unknown table {}", ref_table),
+                )
+            }
+            _ => {}
+        }
+    }
+
+    fn is_strongly_referenced(&self, table: &str) -> bool {
+        let strong_ref = |base: &BaseType| {
+            matches!(
+                base,
+                BaseType::Constrained(ConstrainedBaseType::BaseTypeUUID {
+                    ref_table,
+                    ref_type: RefType::Strong,
+                }) if ref_table == table
+            )
+        };
+        self.tables
+            .values()
+            .flat_map(|t| t.columns.values())
+            .any(|column| match &column.type_ {
+                ColumnType::Atomic(_) => false,
+                ColumnType::Complex(complex) => {
+                    strong_ref(&complex.key) || complex.value.as_ref().is_some_and(strong_ref)
+                }
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::check::*;
+    use serde_json::json;
+
+    const OVN_NB: &str = include_str!("../../../apps/cli/tests/ovn-nb.ovsschema");
+
+    fn errors(schema: serde_json::Value) -> Vec<(String, String)> {
+        let schema: Schema = serde_json::from_value(schema).unwrap();
+        schema
+            .validate()
+            .into_iter()
+            .filter(SchemaDiagnostic::is_error)
+            .map(|d| (d.path, d.message))
+            .collect()
+    }
+
+    #[test]
+    fn test_ovn_nb_is_clean() {
+        let schema: Schema = serde_json::from_str(OVN_NB).unwrap();
+        assert_eq!(schema.validate(), vec![]);
+    }
+
+    #[test]
+    fn test_references_and_indexes() {
+        let found = errors(json!({
+            "name": "Test",
+            "version": "1.0.0",
+            "tables": {
+                "A": {
+                    "columns": {
+                        "b": {"type": {"key": {"type": "uuid", "refTable": "B"}}},
+                        "tmp": {"type": "string", "ephemeral": true}
+                    },
+                    "indexes": [["name"], ["tmp"]]
+                }
+            }
+        }));
+        assert_eq!(
+            found,
+            vec![
+                (
+                    "$.tables.A.columns.b.type.key.refTable".to_string(),
+                    "refTable names unknown table B".to_string()
+                ),
+                (
+                    "$.tables.A.indexes[0][0]".to_string(),
+                    "index names unknown column name".to_string()
+                ),
+                (
+                    "$.tables.A.indexes[1][0]".to_string(),
+                    "ephemeral column tmp may not be indexed".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bounds_and_names() {
+        let found = errors(json!({
+            "name": "Test",
+            "version": "1.0",
+            "tables": {
+                "A": {
+                    "columns": {
+                        "_uuid": {"type": "uuid"},
+                        "n": {"type": {"key": {"type": "integer",
+                                               "minInteger": 5, "maxInteger": 1}}},
+                        "s": {"type": {"key": "string", "min": 1, "max": 0}}
+                    }
+                }
+            }
+        }));
+        let paths: Vec<&str> = found.iter().map(|(path, _)| path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "$.version",
+                "$.tables.A.columns._uuid",
+                "$.tables.A.columns.n.type.key",
+                "$.tables.A.columns.s.type.max",
+            ]
+        );
+        assert_eq!(found[2].1, "maxInteger 1 is less than minInteger 5");
+    }
+
+    #[test]
+    fn test_warnings() {
+        let schema: Schema = serde_json::from_value(json!({
+            "name": "Test",
+            "tables": {"A": {"columns": {}, "isRoot": false},
+                       "B": {"columns": {}, "isRoot": true}}
+        }))
+        .unwrap();
+        let diags = schema.validate();
+        assert!(diags.iter().all(|d| d.severity == Severity::Warning));
+        assert_eq!(diags.len(), 2);
+    }
+
+    #[test]
+    fn test_version() {
+        assert!(is_version("5.33.1"));
+        assert!(!is_version("5.33"));
+        assert!(!is_version("5.033.1"));
+        assert!(!is_version("v5.33.1"));
+    }
+}
@@ -0,0 +1,148 @@
+//! The schema `cksum` field, computed the way the OVS build does it:
+//! `sed '/"cksum": *"[^"]*"/d' schema | cksum`.
+
+use crate::schema::Schema;
+use std::fmt;
+
+const POLY: u32 = 0x04c1_1db7;
+
+/// The checksum of the POSIX `cksum` utility.
+fn posix_cksum(data: &[u8]) -> u32 {
+    fn update(mut crc: u32, byte: u8) -> u32 {
+        crc ^= (byte as u32) << 24;
+        for _ in 0..8 {
+            crc = if crc & 0x8000_0000 != 0 {
+                (crc << 1) ^ POLY
+            } else {
+                crc << 1
+            };
+        }
+        crc
+    }
+
+    let mut crc = data.iter().fold(0, |crc, &byte| update(crc, byte));
+    let mut len = data.len();
+    while len > 0 {
+        crc = update(crc, len as u8);
+        len >>= 8;
+    }
+    !crc
+}
+
+/// Byte range of the quoted value in a `"cksum": "..."` line.
+fn cksum_value(line: &str) -> Option<(usize, usize)> {
+    const KEY: &str = "\"cksum\":";
+    let mut offset = 0;
+    while let Some(found) = line[offset..].find(KEY) {
+        let start = offset + found + KEY.len();
+        let rest = &line[start..];
+        let trimmed = rest.trim_start_matches(' ');
+        let open = start + rest.len() - trimmed.len();
+        if let Some(value) = trimmed.strip_prefix('"') {
+            if let Some(close) = value.find('"') {
+                return Some((open, open + close + 2));
+            }
+        }
+        offset = start;
+    }
+    None
+}
+
+/// Computes the checksum of schema text as `"<crc> <length>"`, leaving out
+/// any line that holds the `cksum` field itself.
+pub fn compute(text: &str) -> String {
+    let data: String = text
+        .split_inclusive('\n')
+        .filter(|line| cksum_value(line).is_none())
+        .collect();
+    format!("{} {}", posix_cksum(data.as_bytes()), data.len())
+}
+
+/// Rewrites the `cksum` field of schema text to match its contents, or
+/// returns `None` if the text has no `cksum` line.
+pub fn update(text: &str) -> Option<String> {
+    let cksum = compute(text);
+    let mut lines: Vec<String> = text.split_inclusive('\n').map(str::to_string).collect();
+    let line = lines.iter_mut().find(|line| cksum_value(line).is_some())?;
+    let (start, end) = cksum_value(line)?;
+    line.replace_range(start..end, &format!("\"{}\"", cksum));
+    Some(lines.concat())
+}
+
+/// A schema whose `cksum` field does not match its contents.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CksumMismatch {
+    pub stored: String,
+    pub computed: String,
+}
+
+impl fmt::Display for CksumMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "schema cksum is \"{}\" but its contents give \"{}\"",
+            self.stored, self.computed
+        )
+    }
+}
+
+impl std::error::Error for CksumMismatch {}
+
+impl Schema {
+    /// Computes the checksum of `text`, the file this schema was read from.
+    pub fn compute_cksum(text: &str) -> String {
+        compute(text)
+    }
+
+    /// Checks the stored `cksum` against `text`, the file this schema was
+    /// read from. A schema without a `cksum` field always passes.
+    pub fn verify_cksum(&self, text: &str) -> Result<(), CksumMismatch> {
+        let Some(stored) = &self.cksum else {
+            return Ok(());
+        };
+        let computed = compute(text);
+        match *stored == computed {
+            true => Ok(()),
+            false => Err(CksumMismatch {
+                stored: stored.clone(),
+                computed,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cksum::*;
+
+    const OVN_NB: &str = include_str!("../../../apps/cli/tests/ovn-nb.ovsschema");
+
+    #[test]
+    fn test_posix_cksum() {
+        // printf '' | cksum; printf 'abc' | cksum
+        assert_eq!(posix_cksum(b""), 4294967295);
+        assert_eq!(posix_cksum(b"abc"), 1219131554);
+    }
+
+    #[test]
+    fn test_ovn_nb() {
+        assert_eq!(Schema::compute_cksum(OVN_NB), "1931852754 30731");
+        let schema: Schema = serde_json::from_str(OVN_NB).unwrap();
+        assert_eq!(schema.verify_cksum(OVN_NB), Ok(()));
+    }
+
+    #[test]
+    fn test_mismatch_and_update() {
+        let edited = OVN_NB.replace("\"maxRows\": 1", "\"maxRows\": 2");
+        let schema: Schema = serde_json::from_str(&edited).unwrap();
+        let err = schema.verify_cksum(&edited).unwrap_err();
+        assert_eq!(err.stored, "1931852754 30731");
+        assert_eq!(err.computed, compute(&edited));
+
+        let updated = update(&edited).unwrap();
+        let schema: Schema = serde_json::from_str(&updated).unwrap();
+        assert_eq!(schema.cksum, Some(compute(&edited)));
+        assert_eq!(schema.verify_cksum(&updated), Ok(()));
+        assert_eq!(update("{\"name\": \"x\"}"), None);
+    }
+}
@@ -0,0 +1,473 @@
+use crate::schema::{Schema, Table};
+use crate::types::{BaseType, ColumnType, ConstrainedBaseType};
+use ovsdb_common::common::AtomicType;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+const COMMON: &str = "::ovsdb_common";
+
+// https://doc.rust-lang.org/reference/keywords.html
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn",
+    "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in", "let",
+    "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return",
+    "static", "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use",
+    "virtual", "where", "while", "yield",
+];
+// keywords that cannot be raw identifiers
+const RESERVED: &[&str] = &["crate", "self", "Self", "super"];
+
+/// Converts a schema name such as `Logical_Switch_Port` or `ACL` to an upper
+/// camel case Rust type name (`LogicalSwitchPort`, `Acl`).
+pub fn type_name(name: &str) -> String {
+    let mut out = String::new();
+    for part in name.split(|c: char| !c.is_ascii_alphanumeric()) {
+        let mut chars = part.chars();
+        let Some(first) = chars.next() else { continue };
+        out.push(first.to_ascii_uppercase());
+        // an all-caps word like "ACL" or "DHCP" becomes "Acl", "Dhcp"
+        if part.chars().all(|c| !c.is_ascii_lowercase()) {
+            out.extend(chars.map(|c| c.to_ascii_lowercase()));
+        } else {
+            out.extend(chars);
+        }
+    }
+    match out.chars().next() {
+        Some(c) if c.is_ascii_alphabetic() => out,
+        _ => format!("V{}", out),
+    }
+}
+
+/// Converts a column name to a Rust field name, escaping keywords.
+pub fn field_name(column: &str) -> String {
+    if KEYWORDS.contains(&column) {
+        format!("r#{}", column)
+    } else if RESERVED.contains(&column) {
+        format!("{}_", column)
+    } else {
+        column.to_string()
+    }
+}
+
+fn string_enum(base: &BaseType) -> Option<Vec<String>> {
+    match base {
+        BaseType::Constrained(ConstrainedBaseType::BaseTypeString {
+            enum_: Some(values),
+            ..
+        }) => Some(values.iter().cloned().collect()),
+        _ => None,
+    }
+}
+
+fn ref_table(base: &BaseType) -> Option<&str> {
+    match base {
+        BaseType::Constrained(ConstrainedBaseType::BaseTypeUUID { ref_table, .. }) => {
+            Some(ref_table)
+        }
+        _ => None,
+    }
+}
+
+/// A string enum found in the schema, emitted as a Rust enum.
+struct EnumDef {
+    name: String,
+    table: String,
+    column: String,
+    values: Vec<String>,
+}
+
+/// Rust names for the tables and enums of one schema.
+struct Names {
+    structs: BTreeMap<String, String>,
+    enums: Vec<EnumDef>,
+}
+
+impl Names {
+    fn new(schema: &Schema) -> Self {
+        let structs = schema
+            .tables
+            .keys()
+            .map(|table| (table.clone(), type_name(table)))
+            .collect();
+        Names {
+            structs,
+            enums: Vec::new(),
+        }
+    }
+
+    fn enum_name(&self, table: &str, column: &str, suffix: &str) -> String {
+        let name = format!("{}{}{}", self.structs[table], type_name(column), suffix);
+        let taken = |name: &String| {
+            self.structs.values().any(|s| s == name) || self.enums.iter().any(|e| &e.name == name)
+        };
+        let mut candidate = name.clone();
+        let mut i = 1;
+        while taken(&candidate) {
+            candidate = match i {
+                1 => format!("{}Enum", name),
+                _ => format!("{}Enum{}", name, i),
+            };
+            i += 1;
+        }
+        candidate
+    }
+
+    fn atom_type(&mut self, table: &str, column: &str, base: &BaseType, suffix: &str) -> String {
+        if let Some(values) = string_enum(base) {
+            let name = self.enum_name(table, column, suffix);
+            self.enums.push(EnumDef {
+                name: name.clone(),
+                table: table.to_string(),
+                column: column.to_string(),
+                values,
+            });
+            return name;
+        }
+        match base.atomic_type() {
+            AtomicType::Integer => "i64".to_string(),
+            AtomicType::Real => "f64".to_string(),
+            AtomicType::Boolean => "bool".to_string(),
+            AtomicType::String => "String".to_string(),
+            AtomicType::Uuid => match ref_table(base).and_then(|t| self.structs.get(t)) {
+                Some(target) => format!("{}::row::Ref<{}>", COMMON, target),
+                None => format!("{}::datum::Uuid", COMMON),
+            },
+        }
+    }
+
+    /// The Rust type of a column, registering any enums it needs.
+    fn column_type(&mut self, table: &str, column: &str, ty: &ColumnType) -> String {
+        let complex = match ty {
+            ColumnType::Atomic(atomic) => {
+                return self.atom_type(table, column, &BaseType::Atomic(*atomic), "")
+            }
+            ColumnType::Complex(complex) => complex,
+        };
+        let key_is_real = ty.key() == AtomicType::Real;
+        if let Some(value) = &complex.value {
+            // f64 is not Ord and cannot key a BTreeMap
+            if key_is_real {
+                return format!("{}::datum::Datum", COMMON);
+            }
+            let key = self.atom_type(table, column, &complex.key, "");
+            let value = self.atom_type(table, column, value, "Value");
+            return format!("::std::collections::BTreeMap<{}, {}>", key, value);
+        }
+        let key = self.atom_type(table, column, &complex.key, "");
+        if ty.is_scalar() {
+            key
+        } else if ty.is_optional() {
+            format!("::std::option::Option<{}>", key)
+        } else if key_is_real {
+            format!("::std::vec::Vec<{}>", key)
+        } else {
+            format!("::std::collections::BTreeSet<{}>", key)
+        }
+    }
+}
+
+fn variant_names(values: &[String]) -> Vec<String> {
+    let mut seen = BTreeSet::new();
+    values
+        .iter()
+        .map(|value| {
+            let base = match type_name(value) {
+                name if name == "V" => "Empty".to_string(),
+                name => name,
+            };
+            let mut name = base.clone();
+            let mut i = 2;
+            while !seen.insert(name.clone()) {
+                name = format!("{}{}", base, i);
+                i += 1;
+            }
+            name
+        })
+        .collect()
+}
+
+fn write_enum(out: &mut String, def: &EnumDef) {
+    let variants = variant_names(&def.values);
+    let name = &def.name;
+    let _ = writeln!(
+        out,
+        "/// Values of the \"{}\" column of the \"{}\" table.",
+        def.column, def.table
+    );
+    let _ = writeln!(
+        out,
+        "#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]"
+    );
+    let _ = writeln!(out, "pub enum {} {{", name);
+    for variant in &variants {
+        let _ = writeln!(out, "    {},", variant);
+    }
+    let _ = writeln!(out, "}}\n");
+
+    let _ = writeln!(out, "impl {} {{", name);
+    let _ = writeln!(out, "    pub fn as_str(&self) -> &'static str {{");
+    let _ = writeln!(out, "        match self {{");
+    for (variant, value) in variants.iter().zip(&def.values) {
+        let _ = writeln!(out, "            {}::{} => {:?},", name, variant, value);
+    }
+    let _ = writeln!(out, "        }}\n    }}\n}}\n");
+
+    let _ = writeln!(out, "impl {}::row::FromAtom for {} {{", COMMON, name);
+    let _ = writeln!(
+        out,
+        "    fn from_atom(atom: &{}::datum::Atom) -> ::std::option::Option<Self> {{",
+        COMMON
+    );
+    let _ = writeln!(out, "        match atom.as_str()? {{");
+    for (variant, value) in variants.iter().zip(&def.values) {
+        let _ = writeln!(
+            out,
+            "            {:?} => Some({}::{}),",
+            value, name, variant
+        );
+    }
+    let _ = writeln!(out, "            _ => None,\n        }}\n    }}\n}}\n");
+
+    let _ = writeln!(out, "impl {}::row::ToAtom for {} {{", COMMON, name);
+    let _ = writeln!(out, "    fn to_atom(&self) -> {}::datum::Atom {{", COMMON);
+    let _ = writeln!(
+        out,
+        "        {}::datum::Atom::String(self.as_str().to_string())\n    }}\n}}\n",
+        COMMON
+    );
+
+    let _ = writeln!(out, "impl {}::row::FromDatum for {} {{", COMMON, name);
+    let _ = writeln!(
+        out,
+        "    fn from_datum(datum: &{}::datum::Datum) -> ::std::option::Option<Self> {{",
+        COMMON
+    );
+    let _ = writeln!(
+        out,
+        "        {}::row::scalar_from_datum(datum)\n    }}\n}}\n",
+        COMMON
+    );
+
+    let _ = writeln!(out, "impl {}::row::ToDatum for {} {{", COMMON, name);
+    let _ = writeln!(out, "    fn to_datum(&self) -> {}::datum::Datum {{", COMMON);
+    let _ = writeln!(
+        out,
+        "        {}::datum::Datum::Scalar({}::row::ToAtom::to_atom(self))\n    }}\n}}\n",
+        COMMON, COMMON
+    );
+}
+
+fn write_struct(
+    out: &mut String,
+    name: &str,
+    table_name: &str,
+    table: &Table,
+    fields: &[(String, String)],
+) {
+    let _ = writeln!(out, "/// A row of the \"{}\" table.", table_name);
+    if !table.is_root() {
+        let _ = writeln!(
+            out,
+            "///\n/// Not a root table: rows without strong references are garbage collected."
+        );
+    }
+    let _ = writeln!(out, "#[derive(Debug, Clone, PartialEq)]");
+    let _ = writeln!(out, "pub struct {} {{", name);
+    let _ = writeln!(out, "    pub _uuid: {}::datum::Uuid,", COMMON);
+    for (column, ty) in fields {
+        let _ = writeln!(out, "    pub {}: {},", field_name(column), ty);
+    }
+    let _ = writeln!(out, "}}\n");
+
+    let row = if fields.is_empty() { "_row" } else { "row" };
+    let _ = writeln!(out, "impl {}::row::TableRow for {} {{", COMMON, name);
+    let _ = writeln!(out, "    const TABLE: &'static str = {:?};\n", table_name);
+    let _ = writeln!(out, "    fn uuid(&self) -> {}::datum::Uuid {{", COMMON);
+    let _ = writeln!(out, "        self._uuid\n    }}\n");
+    let _ = writeln!(out, "    fn from_row(");
+    let _ = writeln!(out, "        uuid: {}::datum::Uuid,", COMMON);
+    let _ = writeln!(out, "        {}: &{}::row::Row,", row, COMMON);
+    let _ = writeln!(
+        out,
+        "    ) -> ::std::result::Result<Self, {}::row::ColumnError> {{",
+        COMMON
+    );
+    let _ = writeln!(out, "        Ok({} {{", name);
+    let _ = writeln!(out, "            _uuid: uuid,");
+    for (column, _) in fields {
+        let _ = writeln!(
+            out,
+            "            {}: {}::row::column(row, Self::TABLE, {:?})?,",
+            field_name(column),
+            COMMON,
+            column
+        );
+    }
+    let _ = writeln!(out, "        }})\n    }}\n");
+    let _ = writeln!(out, "    fn to_row(&self) -> {}::row::Row {{", COMMON);
+    if fields.is_empty() {
+        let _ = writeln!(out, "        {}::row::Row::new()", COMMON);
+    } else {
+        let _ = writeln!(out, "        let mut row = {}::row::Row::new();", COMMON);
+        for (column, _) in fields {
+            let _ = writeln!(
+                out,
+                "        row.insert({:?}.to_string(), {}::row::ToDatum::to_datum(&self.{}));",
+                column,
+                COMMON,
+                field_name(column)
+            );
+        }
+        let _ = writeln!(out, "        row");
+    }
+    let _ = writeln!(out, "    }}\n}}\n");
+}
+
+/// Generates Rust source for a schema: one struct per table implementing
+/// `ovsdb_common::row::TableRow`, and one enum per string column with an
+/// `enum` constraint.
+///
+/// Column types map as follows: scalars to the plain Rust type, `min` 0 /
+/// `max` 1 to `Option<T>`, other sets to `BTreeSet<T>` and maps to
+/// `BTreeMap<K, V>`. UUIDs with a `refTable` become `Ref<Table>`.
+///
+/// The output only uses absolute paths, so it can be written to a file or
+/// expanded in place by a macro.
+pub fn generate(schema: &Schema) -> String {
+    let mut names = Names::new(schema);
+    let mut tables: Vec<(&String, &Table)> = schema.iter_tables().collect();
+    tables.sort_by_key(|(name, _)| name.as_str());
+
+    let mut structs = Vec::new();
+    for (table_name, table) in tables {
+        let mut columns: Vec<_> = table.iter_columns().collect();
+        columns.sort_by_key(|(name, _)| name.as_str());
+        let fields: Vec<(String, String)> = columns
+            .into_iter()
+            .map(|(column, def)| {
+                (
+                    column.clone(),
+                    names.column_type(table_name, column, &def.type_),
+                )
+            })
+            .collect();
+        structs.push((names.structs[table_name].clone(), table_name, table, fields));
+    }
+
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "// Generated by ovsdb-rs from the {} schema{}. Do not edit.\n",
+        schema.name,
+        schema
+            .version
+            .as_ref()
+            .map(|v| format!(", version {}", v))
+            .unwrap_or_default()
+    );
+    let _ = writeln!(out, "pub const SCHEMA_NAME: &str = {:?};", schema.name);
+    if let Some(version) = &schema.version {
+        let _ = writeln!(out, "pub const SCHEMA_VERSION: &str = {:?};", version);
+    }
+    out.push('\n');
+    for def in &names.enums {
+        write_enum(&mut out, def);
+    }
+    for (name, table_name, table, fields) in &structs {
+        write_struct(&mut out, name, table_name, table, fields);
+    }
+    // one trailing newline
+    while out.ends_with("\n\n") {
+        out.pop();
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::codegen::*;
+    use serde_json::json;
+
+    fn schema(json: serde_json::Value) -> Schema {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn test_type_name() {
+        assert_eq!(type_name("Logical_Switch_Port"), "LogicalSwitchPort");
+        assert_eq!(type_name("ACL"), "Acl");
+        assert_eq!(type_name("DHCP_Options"), "DhcpOptions");
+        assert_eq!(type_name("QoS"), "QoS");
+        assert_eq!(type_name("allow-related"), "AllowRelated");
+        assert_eq!(type_name("802.1q"), "V8021q");
+    }
+
+    #[test]
+    fn test_field_name() {
+        assert_eq!(field_name("match"), "r#match");
+        assert_eq!(field_name("self"), "self_");
+        assert_eq!(field_name("external_ids"), "external_ids");
+    }
+
+    #[test]
+    fn test_column_types() {
+        let schema = schema(json!({
+            "name": "Test",
+            "tables": {
+                "Port": {"columns": {}},
+                "Switch": {
+                    "columns": {
+                        "name": {"type": "string"},
+                        "tag": {"type": {"key": "integer", "min": 0, "max": 1}},
+                        "ports": {"type": {"key": {"type": "uuid", "refTable": "Port"},
+                                           "min": 0, "max": "unlimited"}},
+                        "options": {"type": {"key": "string", "value": "string",
+                                             "min": 0, "max": "unlimited"}},
+                        "weights": {"type": {"key": "real", "min": 0, "max": 4}},
+                        "mode": {"type": {"key": {"type": "string",
+                                                  "enum": ["set", ["fast", "slow"]]}}}
+                    }
+                }
+            }
+        }));
+        let mut names = Names::new(&schema);
+        let table = schema.get_table("Switch").unwrap();
+        let ty = |names: &mut Names, column: &str| {
+            names.column_type("Switch", column, &table.columns[column].type_)
+        };
+        assert_eq!(ty(&mut names, "name"), "String");
+        assert_eq!(ty(&mut names, "tag"), "::std::option::Option<i64>");
+        assert_eq!(
+            ty(&mut names, "ports"),
+            "::std::collections::BTreeSet<::ovsdb_common::row::Ref<Port>>"
+        );
+        assert_eq!(
+            ty(&mut names, "options"),
+            "::std::collections::BTreeMap<String, String>"
+        );
+        assert_eq!(ty(&mut names, "weights"), "::std::vec::Vec<f64>");
+        assert_eq!(ty(&mut names, "mode"), "SwitchMode");
+        assert_eq!(names.enums[0].values, vec!["fast", "slow"]);
+    }
+
+    #[test]
+    fn test_generate_is_deterministic() {
+        let schema = schema(json!({
+            "name": "Test",
+            "version": "1.0.0",
+            "tables": {
+                "B": {"columns": {"y": {"type": "integer"}, "x": {"type": "boolean"}}},
+                "A": {"columns": {"type": {"type": {"key": {"type": "string",
+                                                             "enum": ["set", ["a-b", ""]]}}}}}
+            }
+        }));
+        let code = generate(&schema);
+        assert_eq!(code, generate(&schema));
+        assert!(code.contains("pub const SCHEMA_VERSION: &str = \"1.0.0\";"));
+        assert!(code.find("pub struct A {").unwrap() < code.find("pub struct B {").unwrap());
+        assert!(code.find("pub x: bool").unwrap() < code.find("pub y: i64").unwrap());
+        assert!(code.contains("pub r#type: AType,"));
+        assert!(code.contains("AType::AB => \"a-b\","));
+        assert!(code.contains("AType::Empty => \"\","));
+    }
+}
@@ -0,0 +1,271 @@
+//! Type checks of conditions, which live in ovsdb-common and so cannot see
+//! the schema themselves.
+
+use crate::schema::Table;
+use crate::types::{ColumnComplexType, ColumnType, MaxOrUnlimited};
+use crate::validate::Violation;
+use ovsdb_common::common::AtomicType;
+use ovsdb_common::condition::{Condition, Function};
+use ovsdb_common::datum::Datum;
+use std::fmt;
+
+/// Why a condition cannot be used on a table.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConditionError {
+    UnknownColumn(String),
+    /// The function is not defined for the type of the column, such as `<`
+    /// on a string or `includes` on a scalar.
+    NotApplicable {
+        column: String,
+        function: Function,
+    },
+    /// The value does not fit the type of the column.
+    InvalidValue {
+        column: String,
+        violations: Vec<Violation>,
+    },
+}
+
+impl fmt::Display for ConditionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConditionError::UnknownColumn(column) => write!(f, "unknown column '{}'", column),
+            ConditionError::NotApplicable { column, function } => write!(
+                f,
+                "'{}' cannot be applied to column '{}' of this type",
+                function, column
+            ),
+            ConditionError::InvalidValue { column, violations } => {
+                write!(f, "invalid value for column '{}': ", column)?;
+                for (i, violation) in violations.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}", violation)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConditionError {}
+
+impl From<ConditionError> for ovsdb_common::error::Error {
+    fn from(error: ConditionError) -> Self {
+        ovsdb_common::error::Error::schema(error)
+    }
+}
+
+impl ColumnType {
+    /// Whether `function` is defined for the type: inequalities need a
+    /// single or optional integer or real, `includes` and `excludes` a set
+    /// or map, and `==` and `!=` apply to anything.
+    ///
+    /// This is stricter than RFC 7047 and ovsdb-server, which also take
+    /// `includes` and `excludes` on a scalar, as a set of one.
+    pub fn allows_function(&self, function: Function) -> bool {
+        if function.is_inequality() {
+            return (self.is_scalar() || self.is_optional())
+                && matches!(self.key(), AtomicType::Integer | AtomicType::Real);
+        }
+        if function.is_set_function() {
+            return !self.is_scalar();
+        }
+        true
+    }
+
+    /// The type the value of a condition with `function` must have: this
+    /// type, except that `includes` accepts as few elements as it likes,
+    /// `excludes` any number of them, and an inequality exactly one.
+    pub fn condition_type(&self, function: Function) -> ColumnType {
+        let ColumnType::Complex(complex) = self else {
+            return self.clone();
+        };
+        let (min, max) = match function {
+            Function::Includes => (0, complex.max),
+            Function::Excludes => (0, MaxOrUnlimited::Unlimited),
+            function if function.is_inequality() => (1, MaxOrUnlimited::Max(1)),
+            _ => return self.clone(),
+        };
+        ColumnType::Complex(ColumnComplexType {
+            min,
+            max,
+            ..complex.clone()
+        })
+    }
+}
+
+impl Table {
+    /// The type of `column`, including the implicit `_uuid` and `_version`.
+    pub fn column_type(&self, column: &str) -> Option<ColumnType> {
+        match column {
+            "_uuid" | "_version" => Some(ColumnType::Atomic(AtomicType::Uuid)),
+            _ => self.columns.get(column).map(|column| column.type_.clone()),
+        }
+    }
+
+    /// Checks that the condition names a column of the table, that its
+    /// function applies to the column's type, and that its value fits that
+    /// type. The constants `true` and `false` always pass.
+    pub fn check_condition(&self, condition: &Condition) -> Result<(), ConditionError> {
+        self.check(condition, true)
+    }
+
+    /// Checks the condition as RFC 7047 and ovsdb-server do, which is what
+    /// a server must accept: like [`Table::check_condition`], except that
+    /// `includes` and `excludes` apply to scalars too.
+    pub fn check_server_condition(&self, condition: &Condition) -> Result<(), ConditionError> {
+        self.check(condition, false)
+    }
+
+    fn check(&self, condition: &Condition, strict: bool) -> Result<(), ConditionError> {
+        let (column, function, value) = match condition {
+            Condition::Constant(_) => return Ok(()),
+            Condition::Clause {
+                column,
+                function,
+                value,
+            } => (column, *function, value),
+        };
+        let type_ = self
+            .column_type(column)
+            .ok_or_else(|| ConditionError::UnknownColumn(column.clone()))?;
+        let allowed = match strict {
+            true => type_.allows_function(function),
+            false => function.is_set_function() || type_.allows_function(function),
+        };
+        if !allowed {
+            return Err(ConditionError::NotApplicable {
+                column: column.clone(),
+                function,
+            });
+        }
+        type_
+            .condition_type(function)
+            .validate(value)
+            .map_err(|violations| ConditionError::InvalidValue {
+                column: column.clone(),
+                violations,
+            })
+    }
+
+    /// Builds a condition, checked as by [`Table::check_condition`].
+    pub fn condition(
+        &self,
+        column: &str,
+        function: Function,
+        value: impl Into<Datum>,
+    ) -> Result<Condition, ConditionError> {
+        let condition = Condition::new(column, function, value);
+        self.check_condition(&condition)?;
+        Ok(condition)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::condition::*;
+    use serde_json::json;
+
+    fn table() -> Table {
+        serde_json::from_value(json!({
+            "columns": {
+                "name": {"type": "string"},
+                "tag": {"type": {"key": {"type": "integer", "minInteger": 0, "maxInteger": 4095},
+                                 "min": 0, "max": 1}},
+                "priority": {"type": {"key": {"type": "integer", "minInteger": 0, "maxInteger": 32767}}},
+                "ports": {"type": {"key": "string", "min": 1, "max": "unlimited"}},
+                "acls": {"type": {"key": "string", "min": 0, "max": 2}},
+                "external_ids": {"type": {"key": "string", "value": "string",
+                                          "min": 0, "max": "unlimited"}}
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_check_condition() {
+        let table = table();
+        for condition in [
+            Condition::eq("name", "sw0"),
+            Condition::lt("priority", 100),
+            Condition::ge("priority", 1),
+            // optional, which ovsdb-server takes too
+            Condition::gt("tag", 1),
+            Condition::eq("tag", Datum::empty_set()),
+            Condition::includes("external_ids", Datum::map([("k", "v")])),
+            // the column's min of 1 does not apply
+            Condition::excludes("ports", Datum::empty_set()),
+            // nor does the max of 2
+            Condition::excludes("acls", Datum::set(["a", "b", "c"])),
+            Condition::ne("_uuid", Datum::from(ovsdb_common::datum::Uuid::nil())),
+            Condition::FALSE,
+        ] {
+            assert_eq!(table.check_condition(&condition), Ok(()), "{}", condition);
+        }
+
+        assert_eq!(
+            table.check_condition(&Condition::lt("name", "m")),
+            Err(ConditionError::NotApplicable {
+                column: "name".to_string(),
+                function: Function::Lt,
+            })
+        );
+        assert!(matches!(
+            table.check_condition(&Condition::gt("tag", Datum::empty_set())),
+            Err(ConditionError::InvalidValue { .. })
+        ));
+        assert_eq!(
+            table.check_condition(&Condition::includes("name", "sw0")),
+            Err(ConditionError::NotApplicable {
+                column: "name".to_string(),
+                function: Function::Includes,
+            })
+        );
+        assert_eq!(
+            table.check_condition(&Condition::eq("nmae", "sw0")),
+            Err(ConditionError::UnknownColumn("nmae".to_string()))
+        );
+        assert!(matches!(
+            table.check_condition(&Condition::eq("priority", 40000)),
+            Err(ConditionError::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            table.check_condition(&Condition::includes("acls", Datum::set(["a", "b", "c"]))),
+            Err(ConditionError::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            table.condition("ports", Function::Includes, Datum::map([("k", "v")])),
+            Err(ConditionError::InvalidValue { .. })
+        ));
+        assert_eq!(
+            table.condition("name", Function::Eq, "sw0"),
+            Ok(Condition::eq("name", "sw0"))
+        );
+    }
+
+    #[test]
+    fn test_check_server_condition() {
+        let table = table();
+        for condition in [
+            Condition::includes("name", "sw0"),
+            Condition::excludes("priority", 1),
+            Condition::includes("tag", Datum::empty_set()),
+            Condition::gt("tag", 1),
+        ] {
+            assert_eq!(
+                table.check_server_condition(&condition),
+                Ok(()),
+                "{}",
+                condition
+            );
+        }
+        assert!(table
+            .check_server_condition(&Condition::lt("name", "m"))
+            .is_err());
+        assert!(table
+            .check_server_condition(&Condition::includes("priority", 40000))
+            .is_err());
+    }
+}
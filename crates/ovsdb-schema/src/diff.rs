@@ -0,0 +1,657 @@
+//! Differences between two versions of a schema, classified by whether
+//! `ovsdb-server` can convert a live database across them without losing
+//! data or failing on rows that no longer fit.
+
+use crate::schema::{Column, Schema, Table};
+use crate::types::{BaseType, ColumnType, ConstrainedBaseType, MaxOrUnlimited, RefType};
+use ovsdb_common::common::{AtomicType, Set};
+use serde::Serialize;
+use std::borrow::Cow;
+use std::collections::BTreeSet;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Compatibility {
+    /// Every database valid under the old schema converts, and clients of
+    /// the old schema keep working.
+    Compatible,
+    /// Conversion can drop data or fail, or old clients can break.
+    Breaking,
+}
+
+impl fmt::Display for Compatibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Compatibility::Compatible => "compatible",
+            Compatibility::Breaking => "breaking",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "change", rename_all = "kebab-case")]
+pub enum ChangeKind {
+    TableAdded {
+        table: String,
+    },
+    TableRemoved {
+        table: String,
+    },
+    ColumnAdded {
+        table: String,
+        column: String,
+    },
+    ColumnRemoved {
+        table: String,
+        column: String,
+    },
+    /// The atomic type, refTable or map-ness of a column changed.
+    TypeChanged {
+        table: String,
+        column: String,
+        old: String,
+        new: String,
+    },
+    /// Fewer values are allowed than before.
+    ConstraintNarrowed {
+        table: String,
+        column: String,
+        constraint: String,
+        old: String,
+        new: String,
+    },
+    /// More values are allowed than before.
+    ConstraintWidened {
+        table: String,
+        column: String,
+        constraint: String,
+        old: String,
+        new: String,
+    },
+    IndexAdded {
+        table: String,
+        columns: Vec<String>,
+    },
+    IndexRemoved {
+        table: String,
+        columns: Vec<String>,
+    },
+    IsRootChanged {
+        table: String,
+        old: bool,
+        new: bool,
+    },
+    MaxRowsChanged {
+        table: String,
+        old: Option<u64>,
+        new: Option<u64>,
+    },
+}
+
+impl ChangeKind {
+    fn compatibility(&self) -> Compatibility {
+        let breaking = match self {
+            ChangeKind::TableAdded { .. }
+            | ChangeKind::ColumnAdded { .. }
+            | ChangeKind::ConstraintWidened { .. }
+            | ChangeKind::IndexRemoved { .. } => false,
+            ChangeKind::TableRemoved { .. }
+            | ChangeKind::ColumnRemoved { .. }
+            | ChangeKind::TypeChanged { .. }
+            | ChangeKind::ConstraintNarrowed { .. }
+            | ChangeKind::IndexAdded { .. } => true,
+            // rows that are no longer roots get garbage collected
+            ChangeKind::IsRootChanged { new, .. } => !new,
+            ChangeKind::MaxRowsChanged { old, new, .. } => match (old, new) {
+                (_, None) => false,
+                (None, Some(_)) => true,
+                (Some(old), Some(new)) => new < old,
+            },
+        };
+        match breaking {
+            true => Compatibility::Breaking,
+            false => Compatibility::Compatible,
+        }
+    }
+}
+
+impl fmt::Display for ChangeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn opt(value: &Option<u64>) -> String {
+            value.map_or("none".to_string(), |v| v.to_string())
+        }
+
+        match self {
+            ChangeKind::TableAdded { table } => write!(f, "table {} added", table),
+            ChangeKind::TableRemoved { table } => write!(f, "table {} removed", table),
+            ChangeKind::ColumnAdded { table, column } => {
+                write!(f, "column {}.{} added", table, column)
+            }
+            ChangeKind::ColumnRemoved { table, column } => {
+                write!(f, "column {}.{} removed", table, column)
+            }
+            ChangeKind::TypeChanged {
+                table,
+                column,
+                old,
+                new,
+            } => write!(
+                f,
+                "column {}.{} changed type from {} to {}",
+                table, column, old, new
+            ),
+            ChangeKind::ConstraintNarrowed {
+                table,
+                column,
+                constraint,
+                old,
+                new,
+            } => write!(
+                f,
+                "column {}.{} narrowed {} from {} to {}",
+                table, column, constraint, old, new
+            ),
+            ChangeKind::ConstraintWidened {
+                table,
+                column,
+                constraint,
+                old,
+                new,
+            } => write!(
+                f,
+                "column {}.{} widened {} from {} to {}",
+                table, column, constraint, old, new
+            ),
+            ChangeKind::IndexAdded { table, columns } => {
+                write!(f, "table {} gained index {:?}", table, columns)
+            }
+            ChangeKind::IndexRemoved { table, columns } => {
+                write!(f, "table {} lost index {:?}", table, columns)
+            }
+            ChangeKind::IsRootChanged { table, old, new } => {
+                write!(f, "table {} changed isRoot from {} to {}", table, old, new)
+            }
+            ChangeKind::MaxRowsChanged { table, old, new } => write!(
+                f,
+                "table {} changed maxRows from {} to {}",
+                table,
+                opt(old),
+                opt(new)
+            ),
+        }
+    }
+}
+
+/// One difference between two schemas.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Change {
+    #[serde(flatten)]
+    pub kind: ChangeKind,
+    pub compatibility: Compatibility,
+}
+
+impl Change {
+    fn new(kind: ChangeKind) -> Self {
+        Change {
+            compatibility: kind.compatibility(),
+            kind,
+        }
+    }
+
+    pub fn is_breaking(&self) -> bool {
+        self.compatibility == Compatibility::Breaking
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}", self.compatibility, self.kind)
+    }
+}
+
+/// The result of [`Schema::diff`], ordered by table and column name.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SchemaDiff {
+    pub changes: Vec<Change>,
+}
+
+impl SchemaDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Whether a database can be converted without any breaking change.
+    pub fn is_compatible(&self) -> bool {
+        !self.changes.iter().any(Change::is_breaking)
+    }
+
+    pub fn breaking(&self) -> impl Iterator<Item = &Change> {
+        self.changes.iter().filter(|change| change.is_breaking())
+    }
+}
+
+/// Collects the changes of one column.
+struct ColumnDiff<'a> {
+    table: &'a str,
+    column: &'a str,
+    changes: &'a mut Vec<Change>,
+}
+
+impl ColumnDiff<'_> {
+    fn constraint(&mut self, narrowed: bool, constraint: &str, old: String, new: String) {
+        let (table, column, constraint) = (
+            self.table.to_string(),
+            self.column.to_string(),
+            constraint.to_string(),
+        );
+        self.changes.push(Change::new(match narrowed {
+            true => ChangeKind::ConstraintNarrowed {
+                table,
+                column,
+                constraint,
+                old,
+                new,
+            },
+            false => ChangeKind::ConstraintWidened {
+                table,
+                column,
+                constraint,
+                old,
+                new,
+            },
+        }));
+    }
+
+    /// Compares a lower (`is_min`) or upper bound, where `None` is no bound
+    /// and is shown as `none`.
+    fn bound<T>(
+        &mut self,
+        constraint: &str,
+        is_min: bool,
+        old: Option<T>,
+        new: Option<T>,
+        none: &str,
+    ) where
+        T: PartialOrd + fmt::Display,
+    {
+        let narrowed = match (&old, &new) {
+            (None, None) => return,
+            (Some(old), Some(new)) if old == new => return,
+            (None, Some(_)) => true,
+            (Some(_), None) => false,
+            (Some(old), Some(new)) => (new > old) == is_min,
+        };
+        let show = |value: Option<T>| value.map_or(none.to_string(), |v| v.to_string());
+        self.constraint(narrowed, constraint, show(old), show(new));
+    }
+
+    fn enumeration<T>(&mut self, constraint: &str, old: &Option<Set<T>>, new: &Option<Set<T>>)
+    where
+        T: PartialEq + fmt::Display,
+    {
+        let show = |set: &Option<Set<T>>| match set {
+            None => "none".to_string(),
+            Some(set) => {
+                let values: Vec<String> = set.iter().map(|v| v.to_string()).collect();
+                format!("[{}]", values.join(", "))
+            }
+        };
+        let subset = |a: &Set<T>, b: &Set<T>| a.iter().all(|v| b.contains(v));
+        let narrowed = match (old, new) {
+            (None, None) => return,
+            (None, Some(_)) => true,
+            (Some(_), None) => false,
+            (Some(old), Some(new)) => match (subset(old, new), subset(new, old)) {
+                (true, true) => return,
+                (true, false) => false,
+                _ => true,
+            },
+        };
+        self.constraint(narrowed, constraint, show(old), show(new));
+    }
+
+    fn base(&mut self, which: &str, old: &BaseType, new: &BaseType) {
+        use ConstrainedBaseType::*;
+
+        let (Some(old), Some(new)) = (constraints(old), constraints(new)) else {
+            return;
+        };
+        let name = |constraint: &str| format!("{} {}", which, constraint);
+        match (&*old, &*new) {
+            (
+                BaseTypeInt {
+                    min_integer: old_min,
+                    max_integer: old_max,
+                    enum_: old_enum,
+                },
+                BaseTypeInt {
+                    min_integer: new_min,
+                    max_integer: new_max,
+                    enum_: new_enum,
+                },
+            ) => {
+                self.bound(&name("minInteger"), true, *old_min, *new_min, "none");
+                self.bound(&name("maxInteger"), false, *old_max, *new_max, "none");
+                self.enumeration(&name("enum"), old_enum, new_enum);
+            }
+            (
+                BaseTypeReal {
+                    min_real: old_min,
+                    max_real: old_max,
+                    enum_: old_enum,
+                },
+                BaseTypeReal {
+                    min_real: new_min,
+                    max_real: new_max,
+                    enum_: new_enum,
+                },
+            ) => {
+                self.bound(&name("minReal"), true, *old_min, *new_min, "none");
+                self.bound(&name("maxReal"), false, *old_max, *new_max, "none");
+                self.enumeration(&name("enum"), old_enum, new_enum);
+            }
+            (
+                BaseTypeString {
+                    min_length: old_min,
+                    max_length: old_max,
+                    enum_: old_enum,
+                },
+                BaseTypeString {
+                    min_length: new_min,
+                    max_length: new_max,
+                    enum_: new_enum,
+                },
+            ) => {
+                self.bound(&name("minLength"), true, *old_min, *new_min, "none");
+                self.bound(&name("maxLength"), false, *old_max, *new_max, "none");
+                self.enumeration(&name("enum"), old_enum, new_enum);
+            }
+            (
+                BaseTypeUUID {
+                    ref_type: old_ref, ..
+                },
+                BaseTypeUUID {
+                    ref_type: new_ref, ..
+                },
+            ) if old_ref != new_ref => {
+                // a weak reference to a deleted row just goes away, a strong
+                // one makes the conversion fail
+                let show = |ref_type: &RefType| format!("{:?}", ref_type).to_lowercase();
+                self.constraint(
+                    *new_ref == RefType::Strong,
+                    &name("refType"),
+                    show(old_ref),
+                    show(new_ref),
+                );
+            }
+            _ => {}
+        }
+    }
+
+    fn column(&mut self, old: &ColumnType, new: &ColumnType) {
+        if signature(old) != signature(new) {
+            let show = |ty: &ColumnType| serde_json::to_string(ty).unwrap_or_default();
+            self.changes.push(Change::new(ChangeKind::TypeChanged {
+                table: self.table.to_string(),
+                column: self.column.to_string(),
+                old: show(old),
+                new: show(new),
+            }));
+            return;
+        }
+
+        let max = |ty: &ColumnType| match ty.max() {
+            MaxOrUnlimited::Max(max) => Some(max),
+            MaxOrUnlimited::Unlimited => None,
+        };
+        self.bound("min", true, Some(old.min()), Some(new.min()), "none");
+        self.bound("max", false, max(old), max(new), "unlimited");
+        if let (ColumnType::Complex(old), ColumnType::Complex(new)) = (old, new) {
+            self.base("key", &old.key, &new.key);
+            if let (Some(old), Some(new)) = (&old.value, &new.value) {
+                self.base("value", old, new);
+            }
+        } else {
+            let (old_key, new_key) = (base_of(old), base_of(new));
+            self.base("key", &old_key, &new_key);
+        }
+    }
+}
+
+/// The base type of a column's keys.
+fn base_of(ty: &ColumnType) -> Cow<'_, BaseType> {
+    match ty {
+        ColumnType::Atomic(atomic) => Cow::Owned(BaseType::Atomic(*atomic)),
+        ColumnType::Complex(complex) => Cow::Borrowed(&complex.key),
+    }
+}
+
+/// The constraints of a base type, or `None` for types that cannot have any.
+fn constraints(base: &BaseType) -> Option<Cow<'_, ConstrainedBaseType>> {
+    match base {
+        BaseType::Constrained(constrained) => Some(Cow::Borrowed(constrained)),
+        BaseType::Atomic(AtomicType::Integer) => {
+            Some(Cow::Owned(ConstrainedBaseType::BaseTypeInt {
+                min_integer: None,
+                max_integer: None,
+                enum_: None,
+            }))
+        }
+        BaseType::Atomic(AtomicType::Real) => Some(Cow::Owned(ConstrainedBaseType::BaseTypeReal {
+            min_real: None,
+            max_real: None,
+            enum_: None,
+        })),
+        BaseType::Atomic(AtomicType::String) => {
+            Some(Cow::Owned(ConstrainedBaseType::BaseTypeString {
+                min_length: None,
+                max_length: None,
+                enum_: None,
+            }))
+        }
+        BaseType::Atomic(_) => None,
+    }
+}
+
+/// What a column holds, apart from constraints: key and value types with
+/// their referenced tables. Columns whose signature changes cannot be
+/// converted value by value.
+fn signature(ty: &ColumnType) -> (Signature<'_>, Option<Signature<'_>>) {
+    fn base(base: &BaseType) -> Signature<'_> {
+        match base {
+            BaseType::Constrained(ConstrainedBaseType::BaseTypeUUID { ref_table, .. }) => {
+                (AtomicType::Uuid, Some(ref_table.as_str()))
+            }
+            _ => (base.atomic_type(), None),
+        }
+    }
+
+    match ty {
+        ColumnType::Atomic(atomic) => ((*atomic, None), None),
+        ColumnType::Complex(complex) => (base(&complex.key), complex.value.as_ref().map(base)),
+    }
+}
+
+type Signature<'a> = (AtomicType, Option<&'a str>);
+
+fn diff_table(name: &str, old: &Table, new: &Table, changes: &mut Vec<Change>) {
+    let table = name.to_string();
+    if old.is_root() != new.is_root() {
+        changes.push(Change::new(ChangeKind::IsRootChanged {
+            table: table.clone(),
+            old: old.is_root(),
+            new: new.is_root(),
+        }));
+    }
+    if old.max_rows != new.max_rows {
+        changes.push(Change::new(ChangeKind::MaxRowsChanged {
+            table: table.clone(),
+            old: old.max_rows,
+            new: new.max_rows,
+        }));
+    }
+
+    let old_indexes: BTreeSet<&Vec<String>> = old.indexes.iter().flatten().collect();
+    let new_indexes: BTreeSet<&Vec<String>> = new.indexes.iter().flatten().collect();
+    for columns in old_indexes.difference(&new_indexes) {
+        changes.push(Change::new(ChangeKind::IndexRemoved {
+            table: table.clone(),
+            columns: columns.to_vec(),
+        }));
+    }
+    for columns in new_indexes.difference(&old_indexes) {
+        changes.push(Change::new(ChangeKind::IndexAdded {
+            table: table.clone(),
+            columns: columns.to_vec(),
+        }));
+    }
+
+    let columns: BTreeSet<&String> = old.columns.keys().chain(new.columns.keys()).collect();
+    for column in columns {
+        let kind = match (old.columns.get(column), new.columns.get(column)) {
+            (Some(_), None) => ChangeKind::ColumnRemoved {
+                table: table.clone(),
+                column: column.clone(),
+            },
+            (None, Some(_)) => ChangeKind::ColumnAdded {
+                table: table.clone(),
+                column: column.clone(),
+            },
+            (Some(Column { type_: old, .. }), Some(Column { type_: new, .. })) => {
+                ColumnDiff {
+                    table: name,
+                    column,
+                    changes: &mut *changes,
+                }
+                .column(old, new);
+                continue;
+            }
+            (None, None) => unreachable!(),
+        };
+        changes.push(Change::new(kind));
+    }
+}
+
+impl Schema {
+    /// Lists what changed from `self` to `other`, the newer schema.
+    pub fn diff(&self, other: &Schema) -> SchemaDiff {
+        let mut changes = Vec::new();
+        let tables: BTreeSet<&String> = self.tables.keys().chain(other.tables.keys()).collect();
+        for table in tables {
+            match (self.tables.get(table), other.tables.get(table)) {
+                (Some(_), None) => changes.push(Change::new(ChangeKind::TableRemoved {
+                    table: table.clone(),
+                })),
+                (None, Some(_)) => changes.push(Change::new(ChangeKind::TableAdded {
+                    table: table.clone(),
+                })),
+                (Some(old), Some(new)) => diff_table(table, old, new, &mut changes),
+                (None, None) => unreachable!(),
+            }
+        }
+        SchemaDiff { changes }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::diff::*;
+    use serde_json::json;
+
+    fn schema(tables: serde_json::Value) -> Schema {
+        serde_json::from_value(json!({"name": "Test", "version": "1.0.0", "tables": tables}))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_no_changes() {
+        let text = include_str!("../../../apps/cli/tests/ovn-nb.ovsschema");
+        let nb: Schema = serde_json::from_str(text).unwrap();
+        assert!(nb.diff(&serde_json::from_str(text).unwrap()).is_empty());
+    }
+
+    #[test]
+    fn test_tables_and_columns() {
+        let old = schema(json!({
+            "A": {"columns": {"x": {"type": "integer"}, "y": {"type": "string"}}},
+            "B": {"columns": {}}
+        }));
+        let new = schema(json!({
+            "A": {"columns": {"x": {"type": "real"}, "z": {"type": "string"}},
+                  "indexes": [["z"]], "isRoot": false, "maxRows": 10},
+            "C": {"columns": {}, "isRoot": true}
+        }));
+        let diff = old.diff(&new);
+        let found: Vec<String> = diff.changes.iter().map(|c| c.to_string()).collect();
+        assert_eq!(
+            found,
+            vec![
+                "[breaking] table A changed isRoot from true to false",
+                "[breaking] table A changed maxRows from none to 10",
+                "[breaking] table A gained index [\"z\"]",
+                "[breaking] column A.x changed type from \"integer\" to \"real\"",
+                "[breaking] column A.y removed",
+                "[compatible] column A.z added",
+                "[breaking] table B removed",
+                "[compatible] table C added",
+            ]
+        );
+        assert!(!diff.is_compatible());
+    }
+
+    #[test]
+    fn test_constraints() {
+        let old = schema(json!({
+            "A": {"columns": {
+                "n": {"type": {"key": {"type": "integer", "minInteger": 0, "maxInteger": 10},
+                               "min": 0, "max": 4}},
+                "s": {"type": {"key": {"type": "string", "enum": ["set", ["a", "b"]]}}},
+                "r": {"type": {"key": {"type": "uuid", "refTable": "A", "refType": "weak"},
+                               "min": 0, "max": "unlimited"}}
+            }}
+        }));
+        let new = schema(json!({
+            "A": {"columns": {
+                "n": {"type": {"key": {"type": "integer", "maxInteger": 5},
+                               "min": 0, "max": "unlimited"}},
+                "s": {"type": {"key": {"type": "string", "enum": ["set", ["a", "b", "c"]]}}},
+                "r": {"type": {"key": {"type": "uuid", "refTable": "A"},
+                               "min": 1, "max": "unlimited"}}
+            }}
+        }));
+        let found: Vec<String> = old
+            .diff(&new)
+            .changes
+            .iter()
+            .map(|c| c.to_string())
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                "[compatible] column A.n widened max from 4 to unlimited",
+                "[compatible] column A.n widened key minInteger from 0 to none",
+                "[breaking] column A.n narrowed key maxInteger from 10 to 5",
+                "[breaking] column A.r narrowed min from 0 to 1",
+                "[breaking] column A.r narrowed key refType from weak to strong",
+                "[compatible] column A.s widened key enum from [a, b] to [a, b, c]",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_json() {
+        let old = schema(json!({"A": {"columns": {}, "maxRows": 5}}));
+        let new = schema(json!({"A": {"columns": {}, "maxRows": 7}}));
+        assert_eq!(
+            serde_json::to_value(old.diff(&new)).unwrap(),
+            json!({"changes": [{
+                "change": "max-rows-changed",
+                "table": "A",
+                "old": 5,
+                "new": 7,
+                "compatibility": "compatible"
+            }]})
+        );
+    }
+}
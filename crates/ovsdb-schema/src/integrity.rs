@@ -0,0 +1,773 @@
+//! Referential integrity and garbage collection over a set of rows, as
+//! `ovsdb-server` enforces them when a transaction commits.
+// https://tools.ietf.org/html/rfc7047#section-3.2
+//
+// Strong references must point to rows that exist. Rows of non-root tables
+// that no strong reference points to are deleted. Weak references to rows
+// that do not exist are removed, which fails if that leaves a column with
+// fewer than its `min` elements.
+
+use crate::schema::{Schema, Table};
+use crate::types::{BaseType, ColumnType, ConstrainedBaseType, RefType};
+use ovsdb_common::datum::{Atom, Datum, Uuid};
+use ovsdb_common::row::Row;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+/// Rows by table name and UUID. Tables missing from the map, and columns
+/// missing from a row, are unknown rather than empty, so that a partial
+/// replica such as a client cache can be checked too: references to rows of
+/// a missing table are taken to be valid, and rows that a missing table or
+/// column may refer to are not collected.
+pub type Tables = BTreeMap<String, BTreeMap<Uuid, Row>>;
+
+/// Why a set of rows breaks the references of its schema.
+#[derive(Debug, Clone, PartialEq)]
+pub enum IntegrityError {
+    /// A strong reference to a row that does not exist.
+    DanglingReference {
+        table: String,
+        column: String,
+        row: Uuid,
+        ref_table: String,
+        target: Uuid,
+    },
+    /// Removing weak references to rows that do not exist would leave the
+    /// column with fewer than `min` elements.
+    TooFewElements {
+        table: String,
+        column: String,
+        row: Uuid,
+        count: usize,
+        min: i64,
+    },
+}
+
+impl fmt::Display for IntegrityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IntegrityError::DanglingReference {
+                table,
+                column,
+                row,
+                ref_table,
+                target,
+            } => write!(
+                f,
+                "Table {} column {} row {} references nonexistent row {} in table {}",
+                table, column, row, target, ref_table
+            ),
+            IntegrityError::TooFewElements {
+                table,
+                column,
+                row,
+                count,
+                min,
+            } => write!(
+                f,
+                "Table {} column {} row {} would have {} elements, fewer than the minimum \
+                 of {}, once weak references to deleted rows are removed",
+                table, column, row, count, min
+            ),
+        }
+    }
+}
+
+impl std::error::Error for IntegrityError {}
+
+// the errors ovsdb-server replies with at commit
+impl From<IntegrityError> for ovsdb_common::error::Error {
+    fn from(error: IntegrityError) -> Self {
+        let rfc_error = match error {
+            IntegrityError::DanglingReference { .. } => "referential integrity violation",
+            IntegrityError::TooFewElements { .. } => "constraint violation",
+        };
+        ovsdb_common::error::Error::new(rfc_error, Some(error.to_string()))
+    }
+}
+
+/// What [`Integrity::enforce`] changed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Cleanup {
+    /// The rows garbage collected, as `(table, uuid)`.
+    pub collected: Vec<(String, Uuid)>,
+    /// The rows that lost weak references to rows that do not exist.
+    pub weakened: Vec<(String, Uuid)>,
+}
+
+/// A column whose keys or values refer to rows.
+#[derive(Debug)]
+struct RefColumn<'a> {
+    name: &'a str,
+    key: Option<(&'a str, RefType)>,
+    value: Option<(&'a str, RefType)>,
+    min: i64,
+}
+
+impl<'a> RefColumn<'a> {
+    /// The rows `datum` refers to by references of `ref_type`.
+    fn targets(&self, datum: &Datum, ref_type: RefType) -> Vec<(&'a str, Uuid)> {
+        let mut targets = Vec::new();
+        for (reference, atoms) in [(self.key, datum.keys()), (self.value, datum.values())] {
+            if let Some((table, type_)) = reference {
+                if type_ == ref_type {
+                    targets
+                        .extend(atoms.filter_map(|atom| atom.as_uuid().map(|uuid| (table, *uuid))));
+                }
+            }
+        }
+        targets
+    }
+
+    /// Whether the column may hold references of `ref_type` to `table`.
+    fn refers_to(&self, table: &str, ref_type: RefType) -> bool {
+        [self.key, self.value].contains(&Some((table, ref_type)))
+    }
+}
+
+fn reference(base: &BaseType) -> Option<(&str, RefType)> {
+    match base {
+        BaseType::Constrained(ConstrainedBaseType::BaseTypeUUID {
+            ref_table,
+            ref_type,
+        }) => Some((ref_table, *ref_type)),
+        _ => None,
+    }
+}
+
+fn ref_columns(table: &Table) -> Vec<RefColumn<'_>> {
+    table
+        .columns
+        .iter()
+        .filter_map(|(name, column)| {
+            let ColumnType::Complex(complex) = &column.type_ else {
+                return None;
+            };
+            let key = reference(&complex.key);
+            let value = complex.value.as_ref().and_then(reference);
+            (key.is_some() || value.is_some()).then_some(RefColumn {
+                name,
+                key,
+                value,
+                min: complex.min,
+            })
+        })
+        .collect()
+}
+
+/// The reference rules of a schema, applied to sets of rows. A server runs
+/// [`Integrity::enforce`] on the rows a transaction leaves behind; a client
+/// can run it on a copy of its replica with its changes applied, to learn
+/// ahead of committing whether the server would refuse them and which rows
+/// it would collect.
+///
+/// ```
+/// use ovsdb_schema::integrity::{Integrity, Tables};
+/// use ovsdb_schema::Schema;
+///
+/// let schema: Schema = r#"{"name": "db", "tables": {
+///     "Parent": {"columns": {"children": {"type": {
+///         "key": {"type": "uuid", "refTable": "Child"}, "min": 0, "max": "unlimited"}}},
+///         "isRoot": true},
+///     "Child": {"columns": {}, "isRoot": false}}}"#
+///     .parse()
+///     .unwrap();
+/// let mut tables = Tables::new();
+/// tables.entry("Parent".to_string()).or_default();
+/// tables.entry("Child".to_string()).or_default().insert(Default::default(), Default::default());
+///
+/// let cleanup = Integrity::new(&schema).enforce(&mut tables).unwrap();
+/// assert_eq!(cleanup.collected.len(), 1);
+/// assert!(tables["Child"].is_empty());
+/// ```
+#[derive(Debug)]
+pub struct Integrity<'a> {
+    schema: &'a Schema,
+    /// The columns with references, by table.
+    columns: BTreeMap<&'a str, Vec<RefColumn<'a>>>,
+    /// What changed, with [`Integrity::for_changes`]; `None` to check all.
+    scope: Option<Scope<'a>>,
+}
+
+/// The tables that changed, and the non-root tables whose rows may have
+/// lost their last strong reference as a result.
+#[derive(Debug)]
+struct Scope<'a> {
+    changed: BTreeSet<&'a str>,
+    collectable: BTreeSet<&'a str>,
+}
+
+impl<'a> Integrity<'a> {
+    pub fn new(schema: &'a Schema) -> Self {
+        let columns = schema
+            .tables
+            .iter()
+            .map(|(name, table)| (name.as_str(), ref_columns(table)))
+            .filter(|(_, columns)| !columns.is_empty())
+            .collect();
+        Integrity {
+            schema,
+            columns,
+            scope: None,
+        }
+    }
+
+    /// The rules for rows that kept to them until the rows of the `changed`
+    /// tables changed. Only what those changes may have broken is checked:
+    /// the work is in proportion to the changed tables and the tables that
+    /// refer to them or that they refer to, rather than to all of them.
+    pub fn for_changes(schema: &'a Schema, changed: &[&str]) -> Self {
+        let mut integrity = Integrity::new(schema);
+        let changed: BTreeSet<&'a str> = schema
+            .tables
+            .keys()
+            .map(String::as_str)
+            .filter(|table| changed.contains(table))
+            .collect();
+        let collectable = integrity.collectable_after(&changed);
+        integrity.scope = Some(Scope {
+            changed,
+            collectable,
+        });
+        integrity
+    }
+
+    /// Whether rows of `table` may be gone.
+    fn may_lose_rows(&self, table: &str) -> bool {
+        self.scope
+            .as_ref()
+            .is_none_or(|scope| scope.changed.contains(table) || scope.collectable.contains(table))
+    }
+
+    /// The non-root tables whose rows may lose their last strong reference
+    /// once rows of the `changed` tables have changed.
+    fn collectable_after(&self, changed: &BTreeSet<&str>) -> BTreeSet<&'a str> {
+        let mut collectable = BTreeSet::new();
+        loop {
+            let losing = |table: &str| changed.contains(table) || collectable.contains(table);
+            let more: Vec<&'a str> = self
+                .schema
+                .tables
+                .iter()
+                .map(|(name, table)| (name.as_str(), table))
+                .filter(|(name, table)| !table.is_root() && !collectable.contains(name))
+                .filter(|(name, _)| {
+                    losing(name)
+                        || self.columns.iter().any(|(referrer, columns)| {
+                            losing(referrer)
+                                && columns.iter().any(|c| c.refers_to(name, RefType::Strong))
+                        })
+                })
+                .map(|(name, _)| name)
+                .collect();
+            if more.is_empty() {
+                return collectable;
+            }
+            collectable.extend(more);
+        }
+    }
+
+    /// Every `(table, uuid)` of the `targets` tables that the rows refer to
+    /// by strong references.
+    fn referenced(&self, tables: &Tables, targets: &[&String]) -> BTreeSet<(&'a str, Uuid)> {
+        let mut referenced = BTreeSet::new();
+        for (table, columns) in &self.columns {
+            let columns: Vec<&RefColumn> = columns
+                .iter()
+                .filter(|column| targets.iter().any(|t| column.refers_to(t, RefType::Strong)))
+                .collect();
+            if columns.is_empty() {
+                continue;
+            }
+            for row in tables
+                .get(*table)
+                .into_iter()
+                .flat_map(|rows| rows.values())
+            {
+                for column in &columns {
+                    if let Some(datum) = row.get(column.name) {
+                        referenced.extend(column.targets(datum, RefType::Strong));
+                    }
+                }
+            }
+        }
+        referenced
+    }
+
+    /// Whether every strong reference to rows of `table` can be seen: the
+    /// tables and columns that may hold one are all in `tables`.
+    fn sees_references_to(&self, tables: &Tables, table: &str) -> bool {
+        self.columns.iter().all(|(referrer, columns)| {
+            columns
+                .iter()
+                .filter(|column| column.refers_to(table, RefType::Strong))
+                .all(|column| {
+                    tables
+                        .get(*referrer)
+                        .is_some_and(|rows| rows.values().all(|row| row.contains_key(column.name)))
+                })
+        })
+    }
+
+    /// Checks that every strong reference points to a row that exists.
+    pub fn check_references(&self, tables: &Tables) -> Result<(), IntegrityError> {
+        // new references are in changed rows, and references to deleted
+        // rows point into changed tables
+        let may_dangle = |table: &str, columns: &[RefColumn]| {
+            self.scope.as_ref().is_none_or(|scope| {
+                scope.changed.contains(table)
+                    || columns.iter().any(|column| {
+                        scope
+                            .changed
+                            .iter()
+                            .any(|t| column.refers_to(t, RefType::Strong))
+                    })
+            })
+        };
+        for (table, columns) in &self.columns {
+            if !may_dangle(table, columns) {
+                continue;
+            }
+            for (uuid, row) in tables.get(*table).into_iter().flatten() {
+                for column in columns {
+                    let Some(datum) = row.get(column.name) else {
+                        continue;
+                    };
+                    for (ref_table, target) in column.targets(datum, RefType::Strong) {
+                        if !exists(tables, ref_table, &target) {
+                            return Err(IntegrityError::DanglingReference {
+                                table: table.to_string(),
+                                column: column.name.to_string(),
+                                row: *uuid,
+                                ref_table: ref_table.to_string(),
+                                target,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Deletes the rows of non-root tables that no strong reference points
+    /// to, and returns them. As deleting a row drops its own references,
+    /// this goes on until every row left is referenced. Tables that a table
+    /// or column missing from `tables` may refer to are left alone.
+    pub fn collect_garbage(&self, tables: &mut Tables) -> Vec<(String, Uuid)> {
+        let collectable: Vec<&String> = self
+            .schema
+            .tables
+            .iter()
+            .filter(|(name, table)| {
+                !table.is_root()
+                    && self
+                        .scope
+                        .as_ref()
+                        .is_none_or(|scope| scope.collectable.contains(name.as_str()))
+                    && self.sees_references_to(tables, name)
+            })
+            .map(|(name, _)| name)
+            .collect();
+        let mut collected = Vec::new();
+        loop {
+            let referenced = self.referenced(tables, &collectable);
+            let garbage: Vec<(String, Uuid)> = collectable
+                .iter()
+                .flat_map(|&name| {
+                    tables
+                        .get(name)
+                        .into_iter()
+                        .flat_map(|rows| rows.keys())
+                        .filter(|uuid| !referenced.contains(&(name.as_str(), **uuid)))
+                        .map(|uuid| (name.clone(), *uuid))
+                        .collect::<Vec<_>>()
+                })
+                .collect();
+            if garbage.is_empty() {
+                return collected;
+            }
+            for (table, uuid) in &garbage {
+                if let Some(rows) = tables.get_mut(table) {
+                    rows.remove(uuid);
+                }
+            }
+            collected.extend(garbage);
+        }
+    }
+
+    /// Removes weak references to rows that do not exist, and returns the
+    /// rows that lost some. Fails, leaving the rows as they were, if that
+    /// would leave a column with fewer than its `min` elements.
+    pub fn remove_weak_references(
+        &self,
+        tables: &mut Tables,
+    ) -> Result<Vec<(String, Uuid)>, IntegrityError> {
+        // the new values, all worked out before any is stored
+        let mut changes = Vec::new();
+        let may_dangle = |columns: &[RefColumn]| {
+            columns.iter().any(|column| {
+                [column.key, column.value]
+                    .into_iter()
+                    .flatten()
+                    .any(|(table, ref_type)| ref_type == RefType::Weak && self.may_lose_rows(table))
+            })
+        };
+        for (table, columns) in &self.columns {
+            if !may_dangle(columns) {
+                continue;
+            }
+            for (uuid, row) in tables.get(*table).into_iter().flatten() {
+                for column in columns {
+                    let Some(datum) = row.get(column.name) else {
+                        continue;
+                    };
+                    let dangling = |reference: Option<(&str, RefType)>, atom: &Atom| {
+                        matches!(reference, Some((ref_table, RefType::Weak))
+                            if atom.as_uuid().is_some_and(|target| !exists(tables, ref_table, target)))
+                    };
+                    let kept = match datum {
+                        Datum::Map(map) => Datum::Map(
+                            map.iter()
+                                .filter(|(k, v)| {
+                                    !dangling(column.key, k) && !dangling(column.value, v)
+                                })
+                                .map(|(k, v)| (k.clone(), v.clone()))
+                                .collect(),
+                        ),
+                        datum => Datum::Set(
+                            datum
+                                .keys()
+                                .filter(|k| !dangling(column.key, k))
+                                .cloned()
+                                .collect(),
+                        ),
+                    };
+                    if kept.len() == datum.len() {
+                        continue;
+                    }
+                    if (kept.len() as i64) < column.min {
+                        return Err(IntegrityError::TooFewElements {
+                            table: table.to_string(),
+                            column: column.name.to_string(),
+                            row: *uuid,
+                            count: kept.len(),
+                            min: column.min,
+                        });
+                    }
+                    changes.push((*table, *uuid, column.name, kept));
+                }
+            }
+        }
+
+        let mut weakened: Vec<(String, Uuid)> = Vec::new();
+        for (table, uuid, column, datum) in changes {
+            if let Some(row) = tables.get_mut(table).and_then(|rows| rows.get_mut(&uuid)) {
+                row.insert(column.to_string(), datum);
+            }
+            if weakened.last() != Some(&(table.to_string(), uuid)) {
+                weakened.push((table.to_string(), uuid));
+            }
+        }
+        Ok(weakened)
+    }
+
+    /// The tables whose rows [`Integrity::enforce`] may change once rows of
+    /// the `changed` tables have: the non-root tables that may lose rows to
+    /// garbage collection, and the tables with weak references to tables
+    /// that may lose rows.
+    pub fn affected_tables(&self, changed: &[&str]) -> BTreeSet<&'a str> {
+        let changed: BTreeSet<&str> = changed.iter().copied().collect();
+        let mut collectable = self.collectable_after(&changed);
+        let weakened: Vec<&'a str> = self
+            .columns
+            .iter()
+            .filter(|(_, columns)| {
+                columns.iter().any(|column| {
+                    self.schema.tables.keys().any(|table| {
+                        (changed.contains(&table.as_str()) || collectable.contains(table.as_str()))
+                            && column.refers_to(table, RefType::Weak)
+                    })
+                })
+            })
+            .map(|(table, _)| *table)
+            .collect();
+        collectable.extend(weakened);
+        collectable
+    }
+
+    /// Applies all of the rules in the order `ovsdb-server` does: checks
+    /// the strong references, collects garbage, then removes the weak
+    /// references left dangling.
+    pub fn enforce(&self, tables: &mut Tables) -> Result<Cleanup, IntegrityError> {
+        self.check_references(tables)?;
+        let collected = self.collect_garbage(tables);
+        let weakened = self.remove_weak_references(tables)?;
+        Ok(Cleanup {
+            collected,
+            weakened,
+        })
+    }
+}
+
+/// Whether the row may exist: rows of tables missing from `tables` are
+/// unknown, and so taken to.
+fn exists(tables: &Tables, table: &str, uuid: &Uuid) -> bool {
+    tables.get(table).is_none_or(|rows| rows.contains_key(uuid))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::integrity::*;
+    use serde_json::json;
+
+    fn schema() -> Schema {
+        serde_json::from_value(json!({
+            "name": "Test",
+            "tables": {
+                "Switch": {
+                    "columns": {
+                        "ports": {"type": {"key": {"type": "uuid", "refTable": "Port"},
+                                           "min": 0, "max": "unlimited"}},
+                        "acls": {"type": {"key": {"type": "uuid", "refTable": "ACL",
+                                                  "refType": "weak"},
+                                          "min": 0, "max": "unlimited"}}
+                    },
+                    "isRoot": true
+                },
+                "Port": {
+                    "columns": {
+                        "peer": {"type": {"key": {"type": "uuid", "refTable": "Port"},
+                                          "min": 0, "max": 1}},
+                        "acl": {"type": {"key": {"type": "uuid", "refTable": "ACL",
+                                                 "refType": "weak"}}}
+                    },
+                    "isRoot": false
+                },
+                "ACL": {"columns": {}, "isRoot": true}
+            }
+        }))
+        .unwrap()
+    }
+
+    fn uuid(n: u128) -> Uuid {
+        Uuid::from_u128(n)
+    }
+
+    fn row(columns: &[(&str, Datum)]) -> Row {
+        columns
+            .iter()
+            .map(|(column, datum)| (column.to_string(), datum.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_check_references() {
+        let schema = schema();
+        let integrity = Integrity::new(&schema);
+        let mut tables = Tables::new();
+        tables
+            .entry("Switch".to_string())
+            .or_default()
+            .insert(uuid(1), row(&[("ports", Datum::set([uuid(2)]))]));
+        // without the Port table, the port may well exist
+        assert_eq!(integrity.check_references(&tables), Ok(()));
+
+        tables.entry("Port".to_string()).or_default();
+        assert_eq!(
+            integrity.check_references(&tables),
+            Err(IntegrityError::DanglingReference {
+                table: "Switch".to_string(),
+                column: "ports".to_string(),
+                row: uuid(1),
+                ref_table: "Port".to_string(),
+                target: uuid(2),
+            })
+        );
+        let error =
+            ovsdb_common::error::Error::from(integrity.check_references(&tables).unwrap_err());
+        assert_eq!(error.error(), "referential integrity violation");
+
+        // weak references may dangle, until they are removed
+        tables
+            .entry("Port".to_string())
+            .or_default()
+            .insert(uuid(2), row(&[("acl", Datum::from(uuid(9)))]));
+        assert_eq!(integrity.check_references(&tables), Ok(()));
+    }
+
+    #[test]
+    fn test_collect_garbage() {
+        let schema = schema();
+        let integrity = Integrity::new(&schema);
+        let mut tables = Tables::new();
+        tables
+            .entry("Switch".to_string())
+            .or_default()
+            .insert(uuid(1), row(&[("ports", Datum::set([uuid(2)]))]));
+        let ports = tables.entry("Port".to_string()).or_default();
+        ports.insert(uuid(2), row(&[("peer", Datum::set([uuid(3)]))]));
+        ports.insert(uuid(3), row(&[("peer", Datum::empty_set())]));
+        // only referenced by another orphan
+        ports.insert(uuid(4), row(&[("peer", Datum::set([uuid(5)]))]));
+        ports.insert(uuid(5), row(&[("peer", Datum::empty_set())]));
+
+        // a port without its "peer" column may refer to any other port
+        let mut partial = tables.clone();
+        partial.get_mut("Port").unwrap().insert(uuid(5), row(&[]));
+        assert!(integrity.collect_garbage(&mut partial).is_empty());
+
+        let collected = integrity.collect_garbage(&mut tables);
+        assert_eq!(
+            collected,
+            vec![("Port".to_string(), uuid(4)), ("Port".to_string(), uuid(5))]
+        );
+        assert_eq!(
+            tables["Port"].keys().copied().collect::<Vec<_>>(),
+            vec![uuid(2), uuid(3)]
+        );
+
+        // root tables are never collected
+        tables
+            .entry("ACL".to_string())
+            .or_default()
+            .insert(uuid(6), row(&[]));
+        assert!(integrity.collect_garbage(&mut tables).is_empty());
+    }
+
+    #[test]
+    fn test_remove_weak_references() {
+        let schema = schema();
+        let integrity = Integrity::new(&schema);
+        let mut tables = Tables::new();
+        tables
+            .entry("ACL".to_string())
+            .or_default()
+            .insert(uuid(7), row(&[]));
+        tables.entry("Switch".to_string()).or_default().insert(
+            uuid(1),
+            row(&[
+                ("ports", Datum::set([uuid(2)])),
+                ("acls", Datum::set([uuid(7), uuid(8)])),
+            ]),
+        );
+        tables.entry("Port".to_string()).or_default().insert(
+            uuid(2),
+            row(&[("acl", Datum::from(uuid(7))), ("peer", Datum::empty_set())]),
+        );
+
+        let cleanup = integrity.enforce(&mut tables).unwrap();
+        assert_eq!(
+            cleanup,
+            Cleanup {
+                collected: vec![],
+                weakened: vec![("Switch".to_string(), uuid(1))],
+            }
+        );
+        assert_eq!(tables["Switch"][&uuid(1)]["acls"], Datum::set([uuid(7)]));
+
+        // the port's "acl" must keep its one element
+        tables.get_mut("ACL").unwrap().remove(&uuid(7));
+        let before = tables.clone();
+        assert_eq!(
+            integrity.remove_weak_references(&mut tables),
+            Err(IntegrityError::TooFewElements {
+                table: "Port".to_string(),
+                column: "acl".to_string(),
+                row: uuid(2),
+                count: 0,
+                min: 1,
+            })
+        );
+        assert_eq!(tables, before);
+
+        // unless the port goes too
+        tables
+            .get_mut("Switch")
+            .unwrap()
+            .get_mut(&uuid(1))
+            .unwrap()
+            .insert("ports".to_string(), Datum::empty_set());
+        let cleanup = integrity.enforce(&mut tables).unwrap();
+        assert_eq!(cleanup.collected, vec![("Port".to_string(), uuid(2))]);
+        assert_eq!(tables["Switch"][&uuid(1)]["acls"], Datum::empty_set());
+    }
+
+    #[test]
+    fn test_affected_tables() {
+        let schema = schema();
+        let integrity = Integrity::new(&schema);
+        // ports a switch lets go of are collected
+        assert_eq!(
+            integrity.affected_tables(&["Switch"]),
+            BTreeSet::from(["Port"])
+        );
+        // deleted ACLs are removed from the switches and ports
+        assert_eq!(
+            integrity.affected_tables(&["ACL"]),
+            BTreeSet::from(["Port", "Switch"])
+        );
+        assert_eq!(integrity.affected_tables(&[]), BTreeSet::new());
+    }
+
+    #[test]
+    fn test_for_changes() {
+        let schema = schema();
+        let mut tables = Tables::new();
+        tables
+            .entry("Switch".to_string())
+            .or_default()
+            .insert(uuid(1), row(&[("ports", Datum::set([uuid(2)]))]));
+        tables.entry("Port".to_string()).or_default().insert(
+            uuid(3),
+            row(&[("acl", Datum::from(uuid(9))), ("peer", Datum::empty_set())]),
+        );
+        tables.entry("ACL".to_string()).or_default();
+
+        // only the ACLs changed: the ports may hold weak references to them
+        let integrity = Integrity::for_changes(&schema, &["ACL"]);
+        assert_eq!(integrity.check_references(&tables), Ok(()));
+        assert!(integrity.collect_garbage(&mut tables.clone()).is_empty());
+        assert!(integrity.remove_weak_references(&mut tables).is_err());
+
+        // the switch or its ports changed: the switch's references are checked
+        for changed in ["Switch", "Port"] {
+            let integrity = Integrity::for_changes(&schema, &[changed]);
+            assert!(integrity.check_references(&tables).is_err());
+        }
+        tables.get_mut("Switch").unwrap().clear();
+        let integrity = Integrity::for_changes(&schema, &["Switch"]);
+        assert_eq!(
+            integrity.collect_garbage(&mut tables),
+            vec![("Port".to_string(), uuid(3))]
+        );
+    }
+
+    #[test]
+    fn test_collect_ovn_nb_connections() {
+        // Connection leaves out "isRoot", and other tables set it
+        let schema: Schema =
+            serde_json::from_str(include_str!("../../../apps/cli/tests/ovn-nb.ovsschema")).unwrap();
+        let integrity = Integrity::new(&schema);
+        let mut tables = Tables::new();
+        tables
+            .entry("NB_Global".to_string())
+            .or_default()
+            .insert(uuid(1), row(&[("connections", Datum::set([uuid(2)]))]));
+        let connections = tables.entry("Connection".to_string()).or_default();
+        connections.insert(uuid(2), row(&[]));
+        connections.insert(uuid(3), row(&[]));
+
+        // only NB_Global refers to connections, so without it none can go
+        let mut partial = tables.clone();
+        partial.remove("NB_Global");
+        assert!(integrity.collect_garbage(&mut partial).is_empty());
+
+        let cleanup = integrity.enforce(&mut tables).unwrap();
+        assert_eq!(cleanup.collected, vec![("Connection".to_string(), uuid(3))]);
+        assert_eq!(
+            tables["Connection"].keys().copied().collect::<Vec<_>>(),
+            vec![uuid(2)]
+        );
+    }
+}
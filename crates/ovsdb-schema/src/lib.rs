@@ -1,5 +1,22 @@
+pub mod check;
+pub mod cksum;
+pub mod codegen;
+pub mod condition;
+pub mod diff;
+pub mod integrity;
+pub mod load;
+pub mod mutation;
 pub mod schema;
 pub mod types;
+pub mod validate;
 
+pub use check::{SchemaDiagnostic, Severity};
+pub use cksum::CksumMismatch;
+pub use condition::ConditionError;
+pub use diff::{Change, ChangeKind, Compatibility, SchemaDiff};
+pub use integrity::{Cleanup, Integrity, IntegrityError};
+pub use load::SchemaError;
+pub use mutation::InvalidMutation;
 pub use schema::Schema;
 pub use types::*;
+pub use validate::Violation;
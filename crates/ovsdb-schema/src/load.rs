@@ -0,0 +1,245 @@
+//! Reading schemas from files, readers and strings, with errors that point
+//! at the failing element.
+
+use crate::schema::Schema;
+use serde_path_to_error::{Path as JsonPath, Segment};
+use std::fmt;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Why a schema could not be loaded.
+#[derive(Debug)]
+pub enum SchemaError {
+    /// The file or reader could not be read.
+    Io {
+        file: Option<PathBuf>,
+        source: io::Error,
+    },
+    /// The text is not valid JSON.
+    Syntax {
+        file: Option<PathBuf>,
+        line: usize,
+        column: usize,
+        message: String,
+        source: serde_json::Error,
+    },
+    /// The JSON is not a schema, such as a missing member or a column type
+    /// that does not parse. `json_path` names the failing element, e.g.
+    /// `$.tables.ACL.columns.label.type`.
+    Invalid {
+        file: Option<PathBuf>,
+        line: usize,
+        column: usize,
+        json_path: String,
+        message: String,
+        source: serde_json::Error,
+    },
+}
+
+impl SchemaError {
+    /// The file the schema came from; `None` for strings and readers.
+    pub fn file(&self) -> Option<&Path> {
+        match self {
+            SchemaError::Io { file, .. }
+            | SchemaError::Syntax { file, .. }
+            | SchemaError::Invalid { file, .. } => file.as_deref(),
+        }
+    }
+
+    /// The 1-based line and column of the failing element, if it got that
+    /// far.
+    pub fn position(&self) -> Option<(usize, usize)> {
+        match self {
+            SchemaError::Io { .. } => None,
+            SchemaError::Syntax { line, column, .. }
+            | SchemaError::Invalid { line, column, .. } => Some((*line, *column)),
+        }
+    }
+
+    pub fn json_path(&self) -> Option<&str> {
+        match self {
+            SchemaError::Invalid { json_path, .. } => Some(json_path),
+            _ => None,
+        }
+    }
+
+    /// Records the file the text was read from.
+    pub fn with_file(mut self, path: impl Into<PathBuf>) -> Self {
+        match &mut self {
+            SchemaError::Io { file, .. }
+            | SchemaError::Syntax { file, .. }
+            | SchemaError::Invalid { file, .. } => *file = Some(path.into()),
+        }
+        self
+    }
+
+    fn from_json(error: serde_path_to_error::Error<serde_json::Error>) -> Self {
+        let json_path = format_path(error.path());
+        let source = error.into_inner();
+        let (line, column) = (source.line(), source.column());
+        // serde_json appends the position, which is reported separately
+        let message = source.to_string();
+        let message = message
+            .strip_suffix(&format!(" at line {} column {}", line, column))
+            .unwrap_or(&message)
+            .to_string();
+        match source.classify() {
+            serde_json::error::Category::Data => SchemaError::Invalid {
+                file: None,
+                line,
+                column,
+                json_path,
+                message,
+                source,
+            },
+            _ => SchemaError::Syntax {
+                file: None,
+                line,
+                column,
+                message,
+                source,
+            },
+        }
+    }
+}
+
+// same form as the paths of `SchemaDiagnostic`
+fn format_path(path: &JsonPath) -> String {
+    let mut formatted = "$".to_string();
+    for segment in path.iter() {
+        match segment {
+            Segment::Seq { index } => formatted.push_str(&format!("[{}]", index)),
+            Segment::Map { key } => formatted.push_str(&format!(".{}", key)),
+            Segment::Enum { variant } => formatted.push_str(&format!(".{}", variant)),
+            Segment::Unknown => formatted.push_str(".?"),
+        }
+    }
+    formatted
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let file = self
+            .file()
+            .map_or("<schema>".to_string(), |file| file.display().to_string());
+        match self {
+            SchemaError::Io { source, .. } => write!(f, "{}: {}", file, source),
+            SchemaError::Syntax {
+                line,
+                column,
+                message,
+                ..
+            } => write!(f, "{}:{}:{}: {}", file, line, column, message),
+            SchemaError::Invalid {
+                line,
+                column,
+                json_path,
+                message,
+                ..
+            } => write!(
+                f,
+                "{}:{}:{}: {}: {}",
+                file, line, column, json_path, message
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SchemaError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SchemaError::Io { source, .. } => Some(source),
+            SchemaError::Syntax { source, .. } | SchemaError::Invalid { source, .. } => {
+                Some(source)
+            }
+        }
+    }
+}
+
+impl FromStr for Schema {
+    type Err = SchemaError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut deserializer = serde_json::Deserializer::from_str(text);
+        let schema: Schema =
+            serde_path_to_error::deserialize(&mut deserializer).map_err(SchemaError::from_json)?;
+        // trailing characters after the schema
+        deserializer.end().map_err(|source| SchemaError::Syntax {
+            file: None,
+            line: source.line(),
+            column: source.column(),
+            message: "trailing characters".to_string(),
+            source,
+        })?;
+        Ok(schema)
+    }
+}
+
+impl Schema {
+    /// Reads a schema file, such as `ovn-nb.ovsschema`.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Schema, SchemaError> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path).map_err(|source| SchemaError::Io {
+            file: Some(path.to_path_buf()),
+            source,
+        })?;
+        text.parse().map_err(|e: SchemaError| e.with_file(path))
+    }
+
+    pub fn from_reader(mut reader: impl Read) -> Result<Schema, SchemaError> {
+        let mut text = String::new();
+        reader
+            .read_to_string(&mut text)
+            .map_err(|source| SchemaError::Io { file: None, source })?;
+        text.parse()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::load::*;
+
+    #[test]
+    fn test_schema_errors() {
+        let schema: Schema = r#"{"name": "db", "tables": {"T": {"columns": {}}}}"#
+            .parse()
+            .unwrap();
+        assert!(schema.has_table("T"));
+
+        let error = "{\n  \"name\": \"db\",\n  \"tables\": {\n"
+            .parse::<Schema>()
+            .unwrap_err();
+        assert!(matches!(error, SchemaError::Syntax { .. }), "{:?}", error);
+        assert_eq!(error.json_path(), None);
+
+        let text = r#"{
+  "name": "db",
+  "tables": {
+    "T": {
+      "columns": {
+        "c": {"type": {"key": "strnig"}}
+      }
+    }
+  }
+}"#;
+        let error = Schema::from_reader(text.as_bytes()).unwrap_err();
+        assert_eq!(error.json_path(), Some("$.tables.T.columns.c.type"));
+        assert_eq!(error.position().map(|(line, _)| line), Some(6));
+        assert_eq!(
+            error.to_string(),
+            "<schema>:6:40: $.tables.T.columns.c.type: \
+             unknown atomic type \"strnig\", expected one of integer, real, boolean, string or uuid"
+        );
+
+        let error = r#"{"name": "db"}"#.parse::<Schema>().unwrap_err();
+        assert_eq!(error.json_path(), Some("$"));
+        assert!(error.to_string().contains("missing field `tables`"));
+
+        let error = Schema::from_path("no/such/file.ovsschema").unwrap_err();
+        assert!(matches!(error, SchemaError::Io { .. }));
+        assert_eq!(error.file(), Some(Path::new("no/such/file.ovsschema")));
+        assert_eq!(error.position(), None);
+    }
+}
@@ -0,0 +1,356 @@
+//! Type checks of mutations, which live in ovsdb-common and so cannot see
+//! the schema themselves.
+
+use crate::schema::Table;
+use crate::types::{ColumnComplexType, ColumnType, MaxOrUnlimited};
+use crate::validate::Violation;
+use ovsdb_common::common::AtomicType;
+use ovsdb_common::datum::Datum;
+use ovsdb_common::mutation::{Mutation, MutationError, Mutator};
+use std::fmt;
+
+/// Why a mutation cannot be applied to a column.
+#[derive(Debug, Clone, PartialEq)]
+pub enum InvalidMutation {
+    UnknownColumn(String),
+    /// The column is declared `"mutable": false`, or is `_uuid` or
+    /// `_version`.
+    Immutable(String),
+    /// The mutator is not defined for the type of the column, such as `+=`
+    /// on a string or `insert` on a scalar.
+    NotApplicable {
+        column: String,
+        mutator: Mutator,
+    },
+    /// The value does not fit the type the mutator expects.
+    InvalidValue {
+        column: String,
+        violations: Vec<Violation>,
+    },
+    /// Applying the mutation failed, such as by dividing by zero.
+    Failed {
+        column: String,
+        error: MutationError,
+    },
+    /// The result would break the constraints of the column type, such as
+    /// `maxInteger` or the `max` number of elements.
+    ConstraintViolation {
+        column: String,
+        violations: Vec<Violation>,
+    },
+}
+
+fn write_violations(f: &mut fmt::Formatter<'_>, violations: &[Violation]) -> fmt::Result {
+    for (i, violation) in violations.iter().enumerate() {
+        if i > 0 {
+            f.write_str(", ")?;
+        }
+        write!(f, "{}", violation)?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for InvalidMutation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InvalidMutation::UnknownColumn(column) => write!(f, "unknown column '{}'", column),
+            InvalidMutation::Immutable(column) => {
+                write!(f, "cannot mutate immutable column '{}'", column)
+            }
+            InvalidMutation::NotApplicable { column, mutator } => write!(
+                f,
+                "'{}' cannot be applied to column '{}' of this type",
+                mutator, column
+            ),
+            InvalidMutation::InvalidValue { column, violations } => {
+                write!(f, "invalid value for column '{}': ", column)?;
+                write_violations(f, violations)
+            }
+            InvalidMutation::Failed { column, error } => {
+                write!(f, "mutation of column '{}' failed: {}", column, error)
+            }
+            InvalidMutation::ConstraintViolation { column, violations } => {
+                write!(f, "mutation would leave column '{}' invalid: ", column)?;
+                write_violations(f, violations)
+            }
+        }
+    }
+}
+
+impl std::error::Error for InvalidMutation {}
+
+// what ovsdb-server replies for a mutation it cannot carry out; the rest
+// is caught before the transaction is sent
+impl From<InvalidMutation> for ovsdb_common::error::Error {
+    fn from(error: InvalidMutation) -> Self {
+        let rfc_error = match &error {
+            InvalidMutation::Failed { error, .. } => error.rfc_error(),
+            InvalidMutation::ConstraintViolation { .. } => "constraint violation",
+            _ => return ovsdb_common::error::Error::schema(error),
+        };
+        ovsdb_common::error::Error::new(rfc_error, Some(error.to_string()))
+    }
+}
+
+impl ColumnType {
+    /// Whether `mutator` is defined for the type, as in ovsdb-server:
+    /// arithmetic needs integer or real scalars or sets, `%=` integers only,
+    /// and `insert` and `delete` a set or map.
+    pub fn allows_mutator(&self, mutator: Mutator) -> bool {
+        match mutator {
+            Mutator::Insert | Mutator::Delete => !self.is_scalar(),
+            Mutator::Rem => !self.is_map() && self.key() == AtomicType::Integer,
+            _ => !self.is_map() && matches!(self.key(), AtomicType::Integer | AtomicType::Real),
+        }
+    }
+
+    /// The type the value of a mutation with `mutator` must have. For
+    /// arithmetic, it is a single atom of the key type, free of its
+    /// constraints, which only apply to the result. `insert` takes as few
+    /// elements as it likes, and `delete` any number of them.
+    ///
+    /// `delete` on a map also accepts a set of keys, see
+    /// [`ColumnType::delete_keys_type`].
+    pub fn mutation_type(&self, mutator: Mutator) -> ColumnType {
+        if mutator.is_arithmetic() {
+            return ColumnType::Atomic(self.key());
+        }
+        let ColumnType::Complex(complex) = self else {
+            return self.clone();
+        };
+        let max = match mutator {
+            Mutator::Delete => MaxOrUnlimited::Unlimited,
+            _ => complex.max,
+        };
+        ColumnType::Complex(ColumnComplexType {
+            min: 0,
+            max,
+            ..complex.clone()
+        })
+    }
+
+    /// The type of a set of keys to `delete` from a map of this type.
+    pub fn delete_keys_type(&self) -> Option<ColumnType> {
+        let ColumnType::Complex(complex) = self else {
+            return None;
+        };
+        complex.value.as_ref()?;
+        Some(ColumnType::Complex(ColumnComplexType {
+            key: complex.key.clone(),
+            value: None,
+            min: 0,
+            max: MaxOrUnlimited::Unlimited,
+        }))
+    }
+}
+
+impl Table {
+    /// Checks that the mutation names a mutable column of the table, that
+    /// its mutator applies to the column's type, and that its value fits
+    /// the type the mutator expects.
+    pub fn check_mutation(&self, mutation: &Mutation) -> Result<(), InvalidMutation> {
+        let column = &mutation.column;
+        let type_ = match self.columns.get(column) {
+            Some(schema) if !schema.mutable => {
+                return Err(InvalidMutation::Immutable(column.clone()))
+            }
+            Some(schema) => &schema.type_,
+            None if matches!(column.as_str(), "_uuid" | "_version") => {
+                return Err(InvalidMutation::Immutable(column.clone()))
+            }
+            None => return Err(InvalidMutation::UnknownColumn(column.clone())),
+        };
+        if !type_.allows_mutator(mutation.mutator) {
+            return Err(InvalidMutation::NotApplicable {
+                column: column.clone(),
+                mutator: mutation.mutator,
+            });
+        }
+        let value_type = match (mutation.mutator, type_.delete_keys_type()) {
+            (Mutator::Delete, Some(keys)) if !mutation.value.is_map() => keys,
+            _ => type_.mutation_type(mutation.mutator),
+        };
+        value_type
+            .validate(&mutation.value)
+            .map_err(|violations| InvalidMutation::InvalidValue {
+                column: column.clone(),
+                violations,
+            })
+    }
+
+    /// Builds a mutation, checked as by [`Table::check_mutation`].
+    pub fn mutation(
+        &self,
+        column: &str,
+        mutator: Mutator,
+        value: impl Into<Datum>,
+    ) -> Result<Mutation, InvalidMutation> {
+        let mutation = Mutation::new(column, mutator, value);
+        self.check_mutation(&mutation)?;
+        Ok(mutation)
+    }
+
+    /// Predicts the value of the column after applying the mutation to
+    /// `current`, the value it has now, and checks the result against the
+    /// column type. This tells ahead of a transaction whether the server
+    /// would reject it for going past `maxInteger` or the `max` number of
+    /// elements, among others.
+    pub fn apply_mutation(
+        &self,
+        mutation: &Mutation,
+        current: &Datum,
+    ) -> Result<Datum, InvalidMutation> {
+        self.check_mutation(mutation)?;
+        let column = &mutation.column;
+        let result = mutation
+            .apply(current)
+            .map_err(|error| InvalidMutation::Failed {
+                column: column.clone(),
+                error,
+            })?;
+        let type_ = &self.columns[column].type_;
+        type_
+            .validate(&result)
+            .map_err(|violations| InvalidMutation::ConstraintViolation {
+                column: column.clone(),
+                violations,
+            })?;
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mutation::*;
+    use serde_json::json;
+
+    fn table() -> Table {
+        serde_json::from_value(json!({
+            "columns": {
+                "name": {"type": "string", "mutable": false},
+                "priority": {"type": {"key": {"type": "integer", "minInteger": 0, "maxInteger": 32767}}},
+                "rate": {"type": "real"},
+                "tags": {"type": {"key": "integer", "min": 0, "max": "unlimited"}},
+                "acls": {"type": {"key": "string", "min": 0, "max": 2}},
+                "external_ids": {"type": {"key": "string", "value": "string",
+                                          "min": 0, "max": "unlimited"}}
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_check_mutation() {
+        let table = table();
+        for mutation in [
+            Mutation::add("priority", 1),
+            // the argument is not bound by maxInteger, only the result is
+            Mutation::sub("priority", 40000),
+            Mutation::rem("tags", 2),
+            Mutation::div("rate", 2.5),
+            Mutation::insert("acls", Datum::set(["a", "b"])),
+            Mutation::delete("acls", Datum::set(["a", "b", "c"])),
+            Mutation::insert("external_ids", Datum::map([("k", "v")])),
+            Mutation::delete("external_ids", Datum::map([("k", "v")])),
+            Mutation::delete("external_ids", Datum::set(["k0", "k1"])),
+        ] {
+            assert_eq!(table.check_mutation(&mutation), Ok(()), "{}", mutation);
+        }
+
+        assert_eq!(
+            table.check_mutation(&Mutation::rem("rate", 2)),
+            Err(InvalidMutation::NotApplicable {
+                column: "rate".to_string(),
+                mutator: Mutator::Rem,
+            })
+        );
+        assert!(table
+            .check_mutation(&Mutation::add("external_ids", 1))
+            .is_err());
+        assert_eq!(
+            table.check_mutation(&Mutation::insert("priority", 1)),
+            Err(InvalidMutation::NotApplicable {
+                column: "priority".to_string(),
+                mutator: Mutator::Insert,
+            })
+        );
+        assert_eq!(
+            table.check_mutation(&Mutation::insert("name", "sw0")),
+            Err(InvalidMutation::Immutable("name".to_string()))
+        );
+        assert_eq!(
+            table.check_mutation(&Mutation::insert("_uuid", "sw0")),
+            Err(InvalidMutation::Immutable("_uuid".to_string()))
+        );
+        assert_eq!(
+            table.check_mutation(&Mutation::add("prio", 1)),
+            Err(InvalidMutation::UnknownColumn("prio".to_string()))
+        );
+        assert!(matches!(
+            table.check_mutation(&Mutation::add("tags", Datum::set([1, 2]))),
+            Err(InvalidMutation::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            table.mutation("acls", Mutator::Insert, Datum::set(["a", "b", "c"])),
+            Err(InvalidMutation::InvalidValue { .. })
+        ));
+        assert_eq!(
+            table.mutation("priority", Mutator::Mul, 2),
+            Ok(Mutation::mul("priority", 2))
+        );
+    }
+
+    #[test]
+    fn test_apply_mutation() {
+        let table = table();
+        assert_eq!(
+            table.apply_mutation(&Mutation::add("priority", 1), &Datum::from(100)),
+            Ok(Datum::from(101))
+        );
+        assert!(matches!(
+            table.apply_mutation(&Mutation::add("priority", 1), &Datum::from(32767)),
+            Err(InvalidMutation::ConstraintViolation { violations, .. })
+                if matches!(violations[..], [Violation::OutOfRange { .. }])
+        ));
+        assert_eq!(
+            table.apply_mutation(
+                &Mutation::insert("acls", Datum::set(["b", "c"])),
+                &Datum::set(["a"])
+            ),
+            Err(InvalidMutation::ConstraintViolation {
+                column: "acls".to_string(),
+                violations: vec![Violation::TooManyElements { count: 3, max: 2 }],
+            })
+        );
+        // inserting what is already there does not count twice
+        assert_eq!(
+            table.apply_mutation(
+                &Mutation::insert("acls", Datum::set(["a", "b"])),
+                &Datum::set(["a"])
+            ),
+            Ok(Datum::set(["a", "b"]))
+        );
+        assert_eq!(
+            table.apply_mutation(&Mutation::div("tags", 0), &Datum::set([1])),
+            Err(InvalidMutation::Failed {
+                column: "tags".to_string(),
+                error: MutationError::DivisionByZero,
+            })
+        );
+
+        let error = table
+            .apply_mutation(&Mutation::div("tags", 0), &Datum::set([1]))
+            .unwrap_err();
+        assert!(matches!(
+            ovsdb_common::error::Error::from(error),
+            ovsdb_common::error::Error::DomainError(_)
+        ));
+        let error = table
+            .apply_mutation(&Mutation::insert("priority", 1), &Datum::from(1))
+            .unwrap_err();
+        assert!(matches!(
+            ovsdb_common::error::Error::from(error),
+            ovsdb_common::error::Error::Schema(_)
+        ));
+    }
+}
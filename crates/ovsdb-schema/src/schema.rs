@@ -1,42 +1,91 @@
 use crate::types::ColumnType;
-use serde::Deserialize;
-use std::collections::HashMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::{BTreeMap, HashMap};
 
-#[derive(Debug, Deserialize)]
+/// A database schema. Serializes to the canonical compact form, with tables
+/// and columns sorted by name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Schema {
     pub name: String,
-    pub cksum: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cksum: Option<String>,
+    #[serde(serialize_with = "serialize_sorted")]
+    #[serde(deserialize_with = "deserialize_tables")]
     pub tables: HashMap<String, Table>,
 }
 
-#[derive(Debug, Deserialize)]
-#[serde(rename_all(deserialize = "camelCase"))]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct Table {
+    #[serde(serialize_with = "serialize_sorted")]
     pub columns: HashMap<String, Column>,
-    max_rows: Option<u64>,
-    #[serde(default = "default_true")]
-    is_root: bool,
-    indexes: Option<Vec<Vec<String>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) max_rows: Option<u64>,
+    // as written in the schema, so that it serializes back unchanged; what
+    // it means depends on the other tables, see `deserialize_tables`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) is_root: Option<bool>,
+    #[serde(skip)]
+    root: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) indexes: Option<Vec<Vec<String>>>,
 }
 
 fn default_true() -> bool {
     true
 }
 
-#[derive(Debug, Deserialize)]
+// https://tools.ietf.org/html/rfc7047#section-3.2
+// A table is a root if it says "isRoot": true. For compatibility with
+// schemas that predate "isRoot", when no table says so every table is a root.
+fn deserialize_tables<'de, D>(deserializer: D) -> Result<HashMap<String, Table>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let mut tables = HashMap::<String, Table>::deserialize(deserializer)?;
+    let any_root = tables.values().any(|table| table.is_root == Some(true));
+    for table in tables.values_mut() {
+        table.root = !any_root || table.is_root == Some(true);
+    }
+    Ok(tables)
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+fn is_true(value: &bool) -> bool {
+    *value
+}
+
+fn serialize_sorted<V, S>(map: &HashMap<String, V>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    V: Serialize,
+    S: Serializer,
+{
+    map.iter().collect::<BTreeMap<_, _>>().serialize(serializer)
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Column {
     #[serde(rename = "type")]
     pub type_: ColumnType,
     #[serde(default)]
+    #[serde(skip_serializing_if = "is_false")]
     pub ephemeral: bool,
-    #[serde(default)]
+    // columns are mutable unless the schema says otherwise
+    #[serde(default = "default_true")]
+    #[serde(skip_serializing_if = "is_true")]
     pub mutable: bool,
 }
 
 impl Table {
+    /// Whether the table is part of the root set, so that its rows are
+    /// kept without strong references to them.
     pub fn is_root(&self) -> bool {
-        self.is_root
+        self.root
     }
 
     pub fn iter_columns(&self) -> impl Iterator<Item = (&String, &Column)> {
@@ -65,7 +114,6 @@ impl Schema {
         self.tables.get(name)
     }
 
-
     pub fn table_names(&self) -> Vec<String> {
         self.tables.keys().cloned().collect()
     }
@@ -78,3 +126,89 @@ impl Schema {
         self.tables.contains_key(name)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::schema::*;
+    use serde_json::json;
+
+    const OVN_NB: &str = include_str!("../../../apps/cli/tests/ovn-nb.ovsschema");
+
+    #[test]
+    fn test_round_trip() {
+        let schema: Schema = serde_json::from_str(OVN_NB).unwrap();
+        let text = serde_json::to_string_pretty(&schema).unwrap();
+        let reparsed: Schema = serde_json::from_str(&text).unwrap();
+        assert_eq!(reparsed, schema);
+        assert_eq!(serde_json::to_string_pretty(&reparsed).unwrap(), text);
+    }
+
+    #[test]
+    fn test_canonical_form() {
+        let schema: Schema = serde_json::from_value(json!({
+            "name": "Test",
+            "tables": {
+                "T": {
+                    "columns": {
+                        "a": {"type": {"key": {"type": "string"}, "min": 1, "max": 1}},
+                        "b": {"type": {"key": "integer", "min": 0, "max": "unlimited"},
+                              "mutable": true},
+                        "c": {"type": {"key": {"type": "uuid", "refTable": "T",
+                                               "refType": "strong"},
+                                       "value": {"type": "integer", "minInteger": 0}},
+                              "ephemeral": true, "mutable": false}
+                    },
+                    "maxRows": 2,
+                    "isRoot": false
+                }
+            }
+        }))
+        .unwrap();
+        assert_eq!(
+            serde_json::to_value(&schema).unwrap(),
+            json!({
+                "name": "Test",
+                "tables": {
+                    "T": {
+                        "columns": {
+                            "a": {"type": "string"},
+                            "b": {"type": {"key": "integer", "min": 0, "max": "unlimited"}},
+                            "c": {"type": {"key": {"type": "uuid", "refTable": "T"},
+                                           "value": {"type": "integer", "minInteger": 0}},
+                                  "ephemeral": true, "mutable": false}
+                        },
+                        "maxRows": 2,
+                        "isRoot": false
+                    }
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_is_root() {
+        let schema: Schema = serde_json::from_value(json!({
+            "name": "Test",
+            "tables": {"A": {"columns": {}}, "B": {"columns": {}, "isRoot": false}}
+        }))
+        .unwrap();
+        assert!(schema.tables["A"].is_root());
+        assert!(schema.tables["B"].is_root());
+
+        let schema: Schema = serde_json::from_value(json!({
+            "name": "Test",
+            "tables": {"A": {"columns": {}}, "B": {"columns": {}, "isRoot": true}}
+        }))
+        .unwrap();
+        assert!(!schema.tables["A"].is_root());
+        assert!(schema.tables["B"].is_root());
+        assert_eq!(
+            serde_json::to_value(&schema).unwrap()["tables"],
+            json!({"A": {"columns": {}}, "B": {"columns": {}, "isRoot": true}})
+        );
+
+        let nb: Schema = serde_json::from_str(OVN_NB).unwrap();
+        assert!(!nb.tables["Connection"].is_root());
+        assert!(nb.tables["NB_Global"].is_root());
+    }
+}
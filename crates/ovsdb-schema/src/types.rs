@@ -1,40 +1,52 @@
 use ovsdb_common::common::{deserialize_set, serialize_set, AtomicType, Set};
+use ovsdb_common::datum::{Atom, Datum};
 use serde::de::{self, Deserializer};
+use serde::ser::{SerializeMap, Serializer};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
 use std::default::Default;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "type")]
 #[serde(rename_all_fields(deserialize = "camelCase", serialize = "camelCase"))]
 pub enum ConstrainedBaseType {
     #[serde(rename = "integer")]
     BaseTypeInt {
+        #[serde(skip_serializing_if = "Option::is_none")]
         min_integer: Option<i64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
         max_integer: Option<i64>,
         #[serde(rename = "enum")]
         #[serde(default)]
         #[serde(deserialize_with = "deserialize_set")]
         #[serde(serialize_with = "serialize_set")]
+        #[serde(skip_serializing_if = "Option::is_none")]
         enum_: Option<Set<i64>>,
     },
     #[serde(rename = "real")]
     BaseTypeReal {
+        #[serde(skip_serializing_if = "Option::is_none")]
         min_real: Option<f64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
         max_real: Option<f64>,
         #[serde(rename = "enum")]
         #[serde(default)]
         #[serde(deserialize_with = "deserialize_set")]
         #[serde(serialize_with = "serialize_set")]
+        #[serde(skip_serializing_if = "Option::is_none")]
         enum_: Option<Set<f64>>,
     },
     #[serde(rename = "string")]
     BaseTypeString {
+        #[serde(skip_serializing_if = "Option::is_none")]
         min_length: Option<i64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
         max_length: Option<i64>,
         #[serde(rename = "enum")]
         #[serde(default)]
         #[serde(deserialize_with = "deserialize_set")]
         #[serde(serialize_with = "serialize_set")]
+        #[serde(skip_serializing_if = "Option::is_none")]
         enum_: Option<Set<String>>,
     },
     #[serde(rename = "uuid")]
@@ -42,28 +54,126 @@ pub enum ConstrainedBaseType {
         ref_table: String,
         #[serde(deserialize_with = "deserialize_ref_type")]
         #[serde(default = "ref_type_strong")]
+        #[serde(skip_serializing_if = "RefType::is_strong")]
         ref_type: RefType,
     },
 }
 
-#[derive(Debug, Deserialize)]
-#[serde(untagged)]
+/// A base type; one without any constraints is always `Atomic`, whichever
+/// form it was written in.
+#[derive(Debug, Clone, PartialEq)]
 pub enum BaseType {
     Atomic(AtomicType),
     Constrained(ConstrainedBaseType),
 }
 
+impl<'de> Deserialize<'de> for BaseType {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        // dispatched by hand rather than with an untagged enum, whose error
+        // would not say what is wrong
+        let value = serde_json::Value::deserialize(deserializer)?;
+        let object = match &value {
+            serde_json::Value::String(_) => return atomic_type(&value).map(BaseType::Atomic),
+            serde_json::Value::Object(object) => object,
+            _ => return Err(de::Error::custom("expected an atomic type or an object")),
+        };
+        // {"type": "uuid"}, which has no refTable to be constrained by
+        if let (1, Some(type_)) = (object.len(), object.get("type")) {
+            return atomic_type(type_).map(BaseType::Atomic);
+        }
+        if let Some(type_) = object.get("type") {
+            atomic_type::<D::Error>(type_)?;
+        }
+        let constrained = ConstrainedBaseType::deserialize(value).map_err(de::Error::custom)?;
+        Ok(match constrained.is_unconstrained() {
+            true => BaseType::Atomic(constrained.atomic_type()),
+            false => BaseType::Constrained(constrained),
+        })
+    }
+}
+
+impl Serialize for BaseType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            BaseType::Constrained(constrained) if !constrained.is_unconstrained() => {
+                constrained.serialize(serializer)
+            }
+            _ => self.atomic_type().serialize(serializer),
+        }
+    }
+}
+
+impl ConstrainedBaseType {
+    pub fn atomic_type(&self) -> AtomicType {
+        match self {
+            ConstrainedBaseType::BaseTypeInt { .. } => AtomicType::Integer,
+            ConstrainedBaseType::BaseTypeReal { .. } => AtomicType::Real,
+            ConstrainedBaseType::BaseTypeString { .. } => AtomicType::String,
+            ConstrainedBaseType::BaseTypeUUID { .. } => AtomicType::Uuid,
+        }
+    }
+
+    /// Whether this is just the atomic type, spelled as an object.
+    pub fn is_unconstrained(&self) -> bool {
+        match self {
+            ConstrainedBaseType::BaseTypeInt {
+                min_integer,
+                max_integer,
+                enum_,
+            } => min_integer.is_none() && max_integer.is_none() && enum_.is_none(),
+            ConstrainedBaseType::BaseTypeReal {
+                min_real,
+                max_real,
+                enum_,
+            } => min_real.is_none() && max_real.is_none() && enum_.is_none(),
+            ConstrainedBaseType::BaseTypeString {
+                min_length,
+                max_length,
+                enum_,
+            } => min_length.is_none() && max_length.is_none() && enum_.is_none(),
+            ConstrainedBaseType::BaseTypeUUID { .. } => false,
+        }
+    }
+}
+
+impl BaseType {
+    pub fn atomic_type(&self) -> AtomicType {
+        match self {
+            BaseType::Atomic(atomic) => *atomic,
+            BaseType::Constrained(constrained) => constrained.atomic_type(),
+        }
+    }
+}
+
+fn atomic_type<E: de::Error>(value: &serde_json::Value) -> Result<AtomicType, E> {
+    AtomicType::deserialize(value).map_err(|_| {
+        E::custom(format!(
+            "unknown atomic type {}, expected one of integer, real, boolean, string or uuid",
+            value
+        ))
+    })
+}
+
 fn ref_type_strong() -> RefType {
     RefType::Strong
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum RefType {
     Strong,
     Weak,
 }
 
+impl RefType {
+    fn is_strong(&self) -> bool {
+        *self == RefType::Strong
+    }
+}
+
 fn deserialize_ref_type<'de, D>(deserializer: D) -> Result<RefType, D::Error>
 where
     D: Deserializer<'de>,
@@ -78,7 +188,7 @@ where
         _ => Err(de::Error::custom("expected string")),
     }
 }
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MaxOrUnlimited {
     Max(i64),
     Unlimited,
@@ -90,6 +200,24 @@ impl Default for MaxOrUnlimited {
     }
 }
 
+impl Serialize for MaxOrUnlimited {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            MaxOrUnlimited::Max(max) => serializer.serialize_i64(*max),
+            MaxOrUnlimited::Unlimited => serializer.serialize_str("unlimited"),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for MaxOrUnlimited {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_max_or_unlimited(deserializer)
+    }
+}
+
 fn deserialize_max_or_unlimited<'de, D>(deserializer: D) -> Result<MaxOrUnlimited, D::Error>
 where
     D: Deserializer<'de>,
@@ -115,14 +243,59 @@ where
     }
 }
 
-#[derive(Debug, Deserialize)]
-#[serde(untagged)]
+/// A column type; an unconstrained scalar is always `Atomic`, whichever form
+/// it was written in.
+#[derive(Debug, Clone, PartialEq)]
 pub enum ColumnType {
     Atomic(AtomicType),
     Complex(ColumnComplexType),
 }
 
-#[derive(Debug, Deserialize)]
+impl<'de> Deserialize<'de> for ColumnType {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        if value.is_string() {
+            return atomic_type(&value).map(ColumnType::Atomic);
+        }
+        if !value.is_object() {
+            return Err(de::Error::custom("expected an atomic type or an object"));
+        }
+        let complex = ColumnComplexType::deserialize(value).map_err(de::Error::custom)?;
+        Ok(match complex.as_atomic() {
+            Some(atomic) => ColumnType::Atomic(atomic),
+            None => ColumnType::Complex(complex),
+        })
+    }
+}
+
+impl Serialize for ColumnType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let complex = match self {
+            ColumnType::Atomic(atomic) => return atomic.serialize(serializer),
+            ColumnType::Complex(complex) => complex,
+        };
+        if let Some(atomic) = complex.as_atomic() {
+            return atomic.serialize(serializer);
+        }
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("key", &complex.key)?;
+        if let Some(value) = &complex.value {
+            map.serialize_entry("value", value)?;
+        }
+        if complex.min != 1 {
+            map.serialize_entry("min", &complex.min)?;
+        }
+        if complex.max != MaxOrUnlimited::Max(1) {
+            map.serialize_entry("max", &complex.max)?;
+        }
+        map.end()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct ColumnComplexType {
     pub key: BaseType,
     pub value: Option<BaseType>,
@@ -146,6 +319,139 @@ fn default_to_one() -> i64 {
     1
 }
 
+impl ColumnComplexType {
+    /// The atomic type, if this is an unconstrained scalar.
+    fn as_atomic(&self) -> Option<AtomicType> {
+        match (&self.key, &self.value, self.min, self.max) {
+            (BaseType::Atomic(atomic), None, 1, MaxOrUnlimited::Max(1)) => Some(*atomic),
+            _ => None,
+        }
+    }
+}
+
+impl MaxOrUnlimited {
+    /// Whether `n` elements are allowed by this maximum.
+    pub fn allows(&self, n: usize) -> bool {
+        match self {
+            MaxOrUnlimited::Max(max) => (n as i64) <= *max,
+            MaxOrUnlimited::Unlimited => true,
+        }
+    }
+}
+
+impl ColumnType {
+    pub fn key(&self) -> AtomicType {
+        match self {
+            ColumnType::Atomic(atomic) => *atomic,
+            ColumnType::Complex(complex) => complex.key.atomic_type(),
+        }
+    }
+
+    pub fn value(&self) -> Option<AtomicType> {
+        match self {
+            ColumnType::Atomic(_) => None,
+            ColumnType::Complex(complex) => complex.value.as_ref().map(BaseType::atomic_type),
+        }
+    }
+
+    pub fn min(&self) -> i64 {
+        match self {
+            ColumnType::Atomic(_) => 1,
+            ColumnType::Complex(complex) => complex.min,
+        }
+    }
+
+    pub fn max(&self) -> MaxOrUnlimited {
+        match self {
+            ColumnType::Atomic(_) => MaxOrUnlimited::Max(1),
+            ColumnType::Complex(complex) => complex.max,
+        }
+    }
+
+    pub fn is_map(&self) -> bool {
+        self.value().is_some()
+    }
+
+    /// A single value that must always be present.
+    pub fn is_scalar(&self) -> bool {
+        !self.is_map() && self.min() == 1 && self.max() == MaxOrUnlimited::Max(1)
+    }
+
+    /// A single value that may be absent, i.e. `min` 0 and `max` 1.
+    pub fn is_optional(&self) -> bool {
+        !self.is_map() && self.min() == 0 && self.max() == MaxOrUnlimited::Max(1)
+    }
+
+    /// A set that can hold more than one value.
+    pub fn is_set(&self) -> bool {
+        !self.is_map() && self.max() != MaxOrUnlimited::Max(1)
+    }
+
+    /// Converts integers to reals where the key or value type is real, as
+    /// JSON does not tell `1` from `1.0`. Other atoms are left as they are,
+    /// for [`ColumnType::validate`] to judge.
+    pub fn coerce(&self, datum: Datum) -> Datum {
+        fn coerce(atom: Atom, ty: Option<AtomicType>) -> Atom {
+            match (atom, ty) {
+                (Atom::Integer(i), Some(AtomicType::Real)) => Atom::Real(i as f64),
+                (atom, _) => atom,
+            }
+        }
+        let (key, value) = (Some(self.key()), self.value());
+        match datum {
+            Datum::Scalar(atom) => Datum::Scalar(coerce(atom, key)),
+            Datum::Set(set) => Datum::Set(set.into_iter().map(|atom| coerce(atom, key)).collect()),
+            Datum::Map(map) => Datum::Map(
+                map.into_iter()
+                    .map(|(k, v)| (coerce(k, key), coerce(v, value)))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// The value of the column in a new row: `min` default atoms (or pairs).
+    pub fn default_datum(&self) -> Datum {
+        let key = Atom::default_for(self.key());
+        match (self.value(), self.min()) {
+            (Some(_), min) if min < 1 => Datum::empty_map(),
+            (Some(value), _) => Datum::map([(key, Atom::default_for(value))]),
+            (None, min) if min < 1 => Datum::empty_set(),
+            (None, _) => Datum::Scalar(key),
+        }
+    }
+
+    /// Applies a diff in the `update2` "modify" format: a scalar is
+    /// replaced, set elements in `diff` are added if missing and removed if
+    /// present, and map pairs in `diff` are added if the key is missing,
+    /// removed if present with the same value, and replaced otherwise.
+    pub fn apply_diff(&self, old: &Datum, diff: &Datum) -> Datum {
+        if self.is_scalar() {
+            return diff.clone();
+        }
+        if self.is_map() {
+            let mut map = old.as_map().cloned().unwrap_or_default();
+            for (key, value) in diff.as_map().into_iter().flatten() {
+                match map.get(key) {
+                    Some(old) if old == value => {
+                        map.remove(key);
+                    }
+                    _ => {
+                        map.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+            return Datum::Map(map);
+        }
+        let mut set: BTreeSet<Atom> = old.keys().cloned().collect();
+        for atom in diff.keys() {
+            if !set.remove(atom) {
+                set.insert(atom.clone());
+            }
+        }
+        Datum::Set(set)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::types::*;
@@ -372,6 +678,15 @@ mod tests {
             _ => panic!("Expected BaseTypeUUID"),
         }
     }
+
+    #[test]
+    fn test_base_type_uuid_without_ref_table() {
+        let base_type: BaseType = serde_json::from_value(json!({"type": "uuid"})).unwrap();
+        assert_eq!(base_type, BaseType::Atomic(AtomicType::Uuid));
+        let bad = serde_json::from_value::<BaseType>(json!({"type": "uuid", "refType": "weak"}));
+        assert!(bad.is_err());
+    }
+
     #[test]
     fn test_column_type_int() {
         let json = json!({
@@ -432,4 +747,86 @@ mod tests {
             _ => panic!("Expected ColumnComplexType"),
         }
     }
+
+    #[test]
+    fn test_default_datum() {
+        let ty = |json| serde_json::from_value::<ColumnType>(json).unwrap();
+        assert_eq!(ty(json!("integer")).default_datum(), Datum::from(0));
+        assert_eq!(
+            ty(json!({"key": "string", "min": 0})).default_datum(),
+            Datum::empty_set()
+        );
+        assert_eq!(
+            ty(json!({"key": "string", "value": "string", "min": 0, "max": "unlimited"}))
+                .default_datum(),
+            Datum::empty_map()
+        );
+    }
+
+    #[test]
+    fn test_coerce() {
+        let ty = |json| serde_json::from_value::<ColumnType>(json).unwrap();
+        let real = ty(json!("real"));
+        assert!(matches!(
+            real.coerce(Datum::from(1)),
+            Datum::Scalar(Atom::Real(r)) if r == 1.0
+        ));
+        let map = ty(json!({"key": "integer", "value": "real", "min": 0, "max": "unlimited"}));
+        assert!(matches!(
+            map.coerce(Datum::map([(1, 2)])).as_map().unwrap().get(&Atom::Integer(1)),
+            Some(Atom::Real(r)) if *r == 2.0
+        ));
+        // left for validation to reject
+        assert_eq!(real.coerce(Datum::from("a")), Datum::from("a"));
+    }
+
+    #[test]
+    fn test_apply_diff() {
+        let ty = |json| serde_json::from_value::<ColumnType>(json).unwrap();
+        let scalar = ty(json!("string"));
+        assert_eq!(
+            scalar.apply_diff(&Datum::from("a"), &Datum::from("b")),
+            Datum::from("b")
+        );
+
+        let set = ty(json!({"key": "integer", "min": 0, "max": "unlimited"}));
+        assert_eq!(
+            set.apply_diff(&Datum::set([1, 2]), &Datum::set([2, 3])),
+            Datum::set([1, 3])
+        );
+        let optional = ty(json!({"key": "integer", "min": 0, "max": 1}));
+        assert_eq!(
+            optional.apply_diff(&Datum::from(1), &Datum::set([1, 2])),
+            Datum::from(2)
+        );
+
+        let map = ty(json!({"key": "string", "value": "string", "min": 0, "max": "unlimited"}));
+        assert_eq!(
+            map.apply_diff(
+                &Datum::map([("a", "1"), ("b", "2"), ("c", "3")]),
+                &Datum::map([("a", "1"), ("b", "9"), ("d", "4")])
+            ),
+            Datum::map([("b", "9"), ("c", "3"), ("d", "4")])
+        );
+    }
+
+    #[test]
+    fn test_type_errors() {
+        let error = |json| {
+            serde_json::from_value::<ColumnType>(json)
+                .unwrap_err()
+                .to_string()
+        };
+        let unknown = "unknown atomic type \"strnig\", expected one of integer, real, boolean, \
+                       string or uuid";
+        assert_eq!(error(json!("strnig")), unknown);
+        assert_eq!(error(json!({"key": "strnig", "min": 0})), unknown);
+        assert_eq!(
+            error(json!({"key": {"type": "strnig", "minLength": 1}})),
+            unknown
+        );
+        assert!(error(json!({"key": {"type": "uuid", "refType": "weak"}}))
+            .contains("missing field `refTable`"));
+        assert_eq!(error(json!(1)), "expected an atomic type or an object");
+    }
 }
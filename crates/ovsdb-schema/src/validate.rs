@@ -0,0 +1,395 @@
+use crate::types::{BaseType, ColumnType, ConstrainedBaseType, MaxOrUnlimited};
+use ovsdb_common::common::AtomicType;
+use ovsdb_common::datum::{Atom, Datum};
+use std::fmt;
+
+/// A way in which a value breaks the constraints of its column type.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Violation {
+    /// An atom is not of the declared atomic type.
+    WrongType {
+        expected: AtomicType,
+        found: AtomicType,
+    },
+    /// A set or scalar was given for a map column.
+    ExpectedMap,
+    /// A non-empty map was given for a set or scalar column.
+    UnexpectedMap,
+    /// An integer or real outside of `minInteger`/`maxInteger` or `minReal`/`maxReal`.
+    OutOfRange {
+        value: Atom,
+        min: Option<Atom>,
+        max: Option<Atom>,
+    },
+    /// An atom that is not one of the `enum` values.
+    NotInEnum { value: Atom },
+    /// A string shorter than `minLength`, counted in UTF-8 characters.
+    StringTooShort { length: usize, min_length: i64 },
+    /// A string longer than `maxLength`, counted in UTF-8 characters.
+    StringTooLong { length: usize, max_length: i64 },
+    /// Fewer elements than the column's `min`.
+    TooFewElements { count: usize, min: i64 },
+    /// More elements than the column's `max`.
+    TooManyElements { count: usize, max: i64 },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::WrongType { expected, found } => {
+                write!(f, "expected {} but found {}", expected, found)
+            }
+            Violation::ExpectedMap => write!(f, "expected a map"),
+            Violation::UnexpectedMap => write!(f, "expected a set or scalar but found a map"),
+            Violation::OutOfRange { value, min, max } => {
+                let bound = |atom: &Option<Atom>| match atom {
+                    Some(atom) => serde_json::to_string(atom).unwrap_or_default(),
+                    None => "-".to_string(),
+                };
+                write!(
+                    f,
+                    "{} is outside of the range [{}, {}]",
+                    serde_json::to_string(value).unwrap_or_default(),
+                    bound(min),
+                    bound(max)
+                )
+            }
+            Violation::NotInEnum { value } => write!(
+                f,
+                "{} is not one of the allowed values",
+                serde_json::to_string(value).unwrap_or_default()
+            ),
+            Violation::StringTooShort { length, min_length } => write!(
+                f,
+                "string is {} characters long, shorter than the minimum of {}",
+                length, min_length
+            ),
+            Violation::StringTooLong { length, max_length } => write!(
+                f,
+                "string is {} characters long, longer than the maximum of {}",
+                length, max_length
+            ),
+            Violation::TooFewElements { count, min } => {
+                write!(f, "{} elements, fewer than the minimum of {}", count, min)
+            }
+            Violation::TooManyElements { count, max } => {
+                write!(f, "{} elements, more than the maximum of {}", count, max)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Violation {}
+
+fn check_type(expected: AtomicType, atom: &Atom) -> Result<Atom, Violation> {
+    atom.clone()
+        .coerce(expected)
+        .ok_or_else(|| Violation::WrongType {
+            expected,
+            found: atom.atomic_type(),
+        })
+}
+
+fn check_range<T>(value: T, min: Option<T>, max: Option<T>, atom: Atom) -> Result<(), Violation>
+where
+    T: PartialOrd + Copy + Into<Atom>,
+{
+    if min.is_some_and(|min| value < min) || max.is_some_and(|max| value > max) {
+        return Err(Violation::OutOfRange {
+            value: atom,
+            min: min.map(Into::into),
+            max: max.map(Into::into),
+        });
+    }
+    Ok(())
+}
+
+impl ConstrainedBaseType {
+    /// Checks one atom against the type and its constraints.
+    ///
+    /// As in ovsdb-server, an `enum` replaces the range and length
+    /// constraints rather than adding to them.
+    pub fn validate_atom(&self, atom: &Atom) -> Result<(), Violation> {
+        let atom = check_type(self.atomic_type(), atom)?;
+        let not_in_enum = |atom: &Atom| Violation::NotInEnum {
+            value: atom.clone(),
+        };
+        match (self, &atom) {
+            (
+                ConstrainedBaseType::BaseTypeInt {
+                    min_integer,
+                    max_integer,
+                    enum_,
+                },
+                Atom::Integer(i),
+            ) => match enum_ {
+                Some(values) if !values.contains(i) => Err(not_in_enum(&atom)),
+                Some(_) => Ok(()),
+                None => check_range(*i, *min_integer, *max_integer, atom.clone()),
+            },
+            (
+                ConstrainedBaseType::BaseTypeReal {
+                    min_real,
+                    max_real,
+                    enum_,
+                },
+                Atom::Real(r),
+            ) => match enum_ {
+                Some(values) if !values.contains(r) => Err(not_in_enum(&atom)),
+                Some(_) => Ok(()),
+                None => check_range(*r, *min_real, *max_real, atom.clone()),
+            },
+            (
+                ConstrainedBaseType::BaseTypeString {
+                    min_length,
+                    max_length,
+                    enum_,
+                },
+                Atom::String(s),
+            ) => {
+                if let Some(values) = enum_ {
+                    return match values.contains(s) {
+                        true => Ok(()),
+                        false => Err(not_in_enum(&atom)),
+                    };
+                }
+                let length = s.chars().count();
+                if let Some(min_length) = *min_length {
+                    if (length as i64) < min_length {
+                        return Err(Violation::StringTooShort { length, min_length });
+                    }
+                }
+                if let Some(max_length) = *max_length {
+                    if (length as i64) > max_length {
+                        return Err(Violation::StringTooLong { length, max_length });
+                    }
+                }
+                Ok(())
+            }
+            // whether the row exists is up to the server, only the type is checked
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks every element of a scalar or set against the type.
+    pub fn validate(&self, datum: &Datum) -> Result<(), Vec<Violation>> {
+        let violations: Vec<Violation> = datum
+            .keys()
+            .filter_map(|atom| self.validate_atom(atom).err())
+            .collect();
+        match violations.is_empty() {
+            true => Ok(()),
+            false => Err(violations),
+        }
+    }
+}
+
+impl BaseType {
+    pub fn validate_atom(&self, atom: &Atom) -> Result<(), Violation> {
+        match self {
+            BaseType::Atomic(atomic) => check_type(*atomic, atom).map(drop),
+            BaseType::Constrained(constrained) => constrained.validate_atom(atom),
+        }
+    }
+}
+
+impl ColumnType {
+    fn validate_key(&self, atom: &Atom) -> Result<(), Violation> {
+        match self {
+            ColumnType::Atomic(atomic) => check_type(*atomic, atom).map(drop),
+            ColumnType::Complex(complex) => complex.key.validate_atom(atom),
+        }
+    }
+
+    fn validate_value(&self, atom: &Atom) -> Result<(), Violation> {
+        match self {
+            ColumnType::Complex(complex) => match &complex.value {
+                Some(value) => value.validate_atom(atom),
+                None => Err(Violation::UnexpectedMap),
+            },
+            ColumnType::Atomic(_) => Err(Violation::UnexpectedMap),
+        }
+    }
+
+    /// Checks a value against the column type: set or map shape, the `min`
+    /// and `max` number of elements, and the constraints of every key and value.
+    pub fn validate(&self, datum: &Datum) -> Result<(), Vec<Violation>> {
+        match datum {
+            // an empty set doubles as an empty map
+            Datum::Set(set) if set.is_empty() => {}
+            Datum::Map(_) if !self.is_map() => return Err(vec![Violation::UnexpectedMap]),
+            Datum::Scalar(_) | Datum::Set(_) if self.is_map() => {
+                return Err(vec![Violation::ExpectedMap])
+            }
+            _ => {}
+        }
+
+        let mut violations = Vec::new();
+        let count = datum.len();
+        if (count as i64) < self.min() {
+            violations.push(Violation::TooFewElements {
+                count,
+                min: self.min(),
+            });
+        }
+        if let MaxOrUnlimited::Max(max) = self.max() {
+            if count as i64 > max {
+                violations.push(Violation::TooManyElements { count, max });
+            }
+        }
+        violations.extend(
+            datum
+                .keys()
+                .filter_map(|atom| self.validate_key(atom).err()),
+        );
+        violations.extend(
+            datum
+                .values()
+                .filter_map(|atom| self.validate_value(atom).err()),
+        );
+
+        match violations.is_empty() {
+            true => Ok(()),
+            false => Err(violations),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::*;
+    use crate::validate::*;
+    use serde_json::json;
+
+    fn column_type(json: serde_json::Value) -> ColumnType {
+        serde_json::from_value(json).unwrap()
+    }
+
+    fn datum(json: serde_json::Value) -> Datum {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn test_integer_range() {
+        let ty = column_type(json!({
+            "key": {"type": "integer", "minInteger": 0, "maxInteger": 4095},
+            "min": 0, "max": 1
+        }));
+        assert!(ty.validate(&datum(json!(4095))).is_ok());
+        assert!(ty.validate(&datum(json!(["set", []]))).is_ok());
+        assert_eq!(
+            ty.validate(&datum(json!(4096))),
+            Err(vec![Violation::OutOfRange {
+                value: Atom::Integer(4096),
+                min: Some(Atom::Integer(0)),
+                max: Some(Atom::Integer(4095)),
+            }])
+        );
+    }
+
+    #[test]
+    fn test_real_accepts_integer() {
+        let ty = column_type(json!({"key": {"type": "real", "minReal": 0.0}}));
+        assert!(ty.validate(&datum(json!(1))).is_ok());
+        assert!(matches!(
+            ty.validate(&datum(json!(-0.5))).unwrap_err()[0],
+            Violation::OutOfRange { .. }
+        ));
+    }
+
+    #[test]
+    fn test_wrong_type() {
+        let ty = column_type(json!("boolean"));
+        assert_eq!(
+            ty.validate(&datum(json!("true"))),
+            Err(vec![Violation::WrongType {
+                expected: AtomicType::Boolean,
+                found: AtomicType::String,
+            }])
+        );
+    }
+
+    #[test]
+    fn test_string_enum() {
+        let ty = column_type(json!({
+            "key": {"type": "string", "enum": ["set", ["tcp", "udp", "sctp"]]}
+        }));
+        assert!(ty.validate(&datum(json!("udp"))).is_ok());
+        assert_eq!(
+            ty.validate(&datum(json!("icmp"))),
+            Err(vec![Violation::NotInEnum {
+                value: Atom::from("icmp")
+            }])
+        );
+    }
+
+    #[test]
+    fn test_string_length_in_characters() {
+        let base: ConstrainedBaseType = serde_json::from_value(json!({
+            "type": "string", "minLength": 1, "maxLength": 3
+        }))
+        .unwrap();
+        // 3 characters, 6 bytes
+        assert!(base.validate_atom(&Atom::from("äöü")).is_ok());
+        assert_eq!(
+            base.validate_atom(&Atom::from("äöüß")),
+            Err(Violation::StringTooLong {
+                length: 4,
+                max_length: 3
+            })
+        );
+        assert_eq!(
+            base.validate(&Datum::set(["", "ok"])),
+            Err(vec![Violation::StringTooShort {
+                length: 0,
+                min_length: 1
+            }])
+        );
+    }
+
+    #[test]
+    fn test_cardinality() {
+        let ty = column_type(json!({
+            "key": {"type": "uuid", "refTable": "ACL"}, "min": 1, "max": 2
+        }));
+        assert_eq!(
+            ty.validate(&Datum::empty_set()),
+            Err(vec![Violation::TooFewElements { count: 0, min: 1 }])
+        );
+        let three = datum(json!([
+            "set",
+            [
+                ["uuid", "00000000-0000-0000-0000-000000000001"],
+                ["uuid", "00000000-0000-0000-0000-000000000002"],
+                ["named-uuid", "acl0"]
+            ]
+        ]));
+        assert_eq!(
+            ty.validate(&three),
+            Err(vec![Violation::TooManyElements { count: 3, max: 2 }])
+        );
+    }
+
+    #[test]
+    fn test_map() {
+        let ty = column_type(json!({
+            "key": "string",
+            "value": {"type": "integer", "minInteger": 1},
+            "min": 0, "max": "unlimited"
+        }));
+        assert!(ty.validate(&Datum::map([("a", 1), ("b", 2)])).is_ok());
+        assert!(ty.validate(&Datum::empty_set()).is_ok());
+        assert_eq!(
+            ty.validate(&datum(json!("a"))),
+            Err(vec![Violation::ExpectedMap])
+        );
+        assert!(matches!(
+            ty.validate(&Datum::map([("a", 0)])).unwrap_err()[..],
+            [Violation::OutOfRange { .. }]
+        ));
+        assert_eq!(
+            column_type(json!("string")).validate(&Datum::map([("a", "b")])),
+            Err(vec![Violation::UnexpectedMap])
+        );
+    }
+}
@@ -0,0 +1,508 @@
+//! The tables of one database, and the atomic execution of "transact"
+//! requests against them.
+// https://tools.ietf.org/html/rfc7047#section-4.1.3
+
+use crate::integrity;
+use crate::transaction::{Failure, Txn};
+use ovsdb_common::datum::{Datum, Uuid};
+use ovsdb_common::error::Error;
+use ovsdb_common::row::Row;
+use ovsdb_schema::{Integrity, Schema};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::mem;
+
+/// The rows of one table by UUID. Each row has every column of the table,
+/// plus `_version`.
+pub type Rows = BTreeMap<Uuid, Row>;
+
+/// What became of a "transact" request.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Transacted {
+    /// Every operation succeeded and the changes are in the database. The
+    /// results are the reply, one per operation.
+    Committed(Vec<Value>),
+    /// Nothing was changed. The results hold an error in place of the
+    /// operation that failed and nulls after it, or one element more than
+    /// there are operations if the commit itself failed.
+    Failed(Vec<Value>),
+    /// A "wait" operation does not hold yet and nothing was changed. The
+    /// request should run again when the database changes, and with
+    /// `may_block` false once `timeout` milliseconds have passed.
+    Blocked { timeout: Option<u64> },
+}
+
+impl Transacted {
+    /// The reply to the request, unless it blocked.
+    pub fn results(&self) -> Option<&[Value]> {
+        match self {
+            Transacted::Committed(results) | Transacted::Failed(results) => Some(results),
+            Transacted::Blocked { .. } => None,
+        }
+    }
+}
+
+/// A database kept in memory, with the tables of its schema.
+#[derive(Debug, Clone)]
+pub struct Database {
+    schema: Schema,
+    tables: BTreeMap<String, Rows>,
+}
+
+impl Database {
+    /// An empty database of the schema.
+    pub fn new(schema: Schema) -> Self {
+        let tables = schema
+            .tables
+            .keys()
+            .map(|name| (name.clone(), Rows::new()))
+            .collect();
+        Database { schema, tables }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.schema.name
+    }
+
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    /// The rows of `table`, or `None` if the schema has no such table.
+    pub fn rows(&self, table: &str) -> Option<&Rows> {
+        self.tables.get(table)
+    }
+
+    /// Executes the operations of a "transact" request, the params after
+    /// the database name, as one transaction: either all of their changes
+    /// are kept or none are.
+    ///
+    /// When a "wait" does not hold, the transaction blocks if `may_block`
+    /// is set and the wait has a timeout other than 0; otherwise it fails
+    /// with "timed out".
+    pub fn transact(&mut self, operations: &[Value], may_block: bool) -> Transacted {
+        let mut txn = Txn::new(&self.schema, &self.tables, operations, may_block);
+        let mut results = Vec::with_capacity(operations.len());
+        for (index, operation) in operations.iter().enumerate() {
+            match txn.execute(index, operation) {
+                Ok(result) => results.push(result),
+                Err(Failure::Blocked { timeout }) => return Transacted::Blocked { timeout },
+                Err(Failure::Error(error)) => {
+                    results.push(to_json(&error));
+                    results.resize(operations.len(), Value::Null);
+                    return Transacted::Failed(results);
+                }
+            }
+        }
+
+        // the changed tables, then copies of those the commit checks may
+        // change in turn, which are all that is swapped in and, if the
+        // checks fail, back out
+        let mut tables = txn.into_changed();
+        if tables.is_empty() {
+            return Transacted::Committed(results);
+        }
+        let changed: Vec<String> = tables.keys().cloned().collect();
+        let changed: Vec<&str> = changed.iter().map(String::as_str).collect();
+        let affected = Integrity::new(&self.schema).affected_tables(&changed);
+        for name in affected {
+            if !tables.contains_key(name) {
+                tables.insert(name.to_string(), self.tables[name].clone());
+            }
+        }
+        for (name, rows) in &mut tables {
+            mem::swap(
+                self.tables.get_mut(name).expect("a table of the schema"),
+                rows,
+            );
+        }
+        let old = tables;
+
+        let touched: Vec<&str> = old.keys().map(String::as_str).collect();
+        if let Err(error) = integrity::check(&self.schema, &mut self.tables, &changed, &touched) {
+            self.tables.extend(old);
+            results.push(to_json(&error));
+            return Transacted::Failed(results);
+        }
+        for (name, old) in &old {
+            let rows = self.tables.get_mut(name).expect("a table of the schema");
+            for (uuid, row) in rows.iter_mut() {
+                if old.get(uuid).is_none_or(|old| !same_columns(old, row)) {
+                    row.insert("_version".to_string(), Datum::from(new_uuid()));
+                }
+            }
+        }
+        Transacted::Committed(results)
+    }
+}
+
+fn to_json(error: &Error) -> Value {
+    serde_json::to_value(error).unwrap_or_default()
+}
+
+// whether the row changed, which `_version` is not part of
+fn same_columns(a: &Row, b: &Row) -> bool {
+    let columns = |row| Row::iter(row).filter(|(column, _)| *column != "_version");
+    columns(a).eq(columns(b))
+}
+
+/// A random (version 4) UUID, for new rows and versions.
+pub(crate) fn new_uuid() -> Uuid {
+    uuid::Builder::from_random_bytes(fastrand::u128(..).to_le_bytes()).into_uuid()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::*;
+    use serde_json::json;
+
+    fn schema() -> Schema {
+        serde_json::from_value(json!({
+            "name": "Test",
+            "tables": {
+                "Bridge": {
+                    "columns": {
+                        "name": {"type": "string", "mutable": false},
+                        "ports": {"type": {"key": {"type": "uuid", "refTable": "Port"},
+                                           "min": 0, "max": "unlimited"}},
+                        "mirrors": {"type": {"key": {"type": "uuid", "refTable": "Mirror",
+                                                     "refType": "weak"},
+                                             "min": 0, "max": "unlimited"}},
+                        "flood_vlans": {"type": {"key": {"type": "integer",
+                                                         "minInteger": 0, "maxInteger": 4095},
+                                                 "min": 0, "max": 4}},
+                        "rate": {"type": "real"}
+                    },
+                    "isRoot": true,
+                    "indexes": [["name"]]
+                },
+                "Port": {
+                    "columns": {
+                        "name": {"type": "string"},
+                        "tag": {"type": {"key": "integer", "min": 0, "max": 1}}
+                    },
+                    "isRoot": false
+                },
+                "Span": {
+                    "columns": {
+                        "mirror": {"type": {"key": {"type": "uuid", "refTable": "Mirror",
+                                                    "refType": "weak"}}}
+                    },
+                    "isRoot": true
+                },
+                "Mirror": {
+                    "columns": {"name": {"type": "string"}},
+                    "isRoot": true,
+                    "maxRows": 2
+                }
+            }
+        }))
+        .unwrap()
+    }
+
+    fn committed(db: &mut Database, operations: Value) -> Vec<Value> {
+        match db.transact(operations.as_array().unwrap(), false) {
+            Transacted::Committed(results) => results,
+            other => panic!("Expected a commit, got {:?}", other),
+        }
+    }
+
+    fn failed(db: &mut Database, operations: Value) -> Vec<Value> {
+        match db.transact(operations.as_array().unwrap(), false) {
+            Transacted::Failed(results) => results,
+            other => panic!("Expected a failure, got {:?}", other),
+        }
+    }
+
+    fn select(db: &mut Database, table: &str, columns: Value) -> Value {
+        let results = committed(
+            db,
+            json!([{"op": "select", "table": table, "where": [], "columns": columns}]),
+        );
+        results[0]["rows"].clone()
+    }
+
+    #[test]
+    fn test_insert_and_select() {
+        let mut db = Database::new(schema());
+        let results = committed(
+            &mut db,
+            json!([
+                {"op": "insert", "table": "Port", "row": {"name": "p0"}, "uuid-name": "p0"},
+                {"op": "insert", "table": "Bridge",
+                 "row": {"name": "br0", "ports": ["set", [["named-uuid", "p0"]]], "rate": 1}},
+                {"op": "comment", "comment": "adding br0"}
+            ]),
+        );
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0]["uuid"][0], "uuid");
+        assert_eq!(results[2], json!({}));
+        let port = results[0]["uuid"].clone();
+
+        assert_eq!(
+            select(
+                &mut db,
+                "Bridge",
+                json!(["name", "ports", "rate", "flood_vlans"])
+            ),
+            json!([{
+                "name": "br0",
+                "ports": port,
+                "rate": 1.0,
+                "flood_vlans": ["set", []]
+            }])
+        );
+        let rows = select(&mut db, "Port", json!(["_uuid", "tag"]));
+        assert_eq!(rows, json!([{"_uuid": port, "tag": ["set", []]}]));
+
+        let uuid: Datum = serde_json::from_value(port).unwrap();
+        let row = &db.rows("Port").unwrap()[uuid.as_atom().unwrap().as_uuid().unwrap()];
+        assert!(row.contains_key("_version"));
+    }
+
+    #[test]
+    fn test_update_mutate_delete() {
+        let mut db = Database::new(schema());
+        committed(
+            &mut db,
+            json!([{"op": "insert", "table": "Bridge", "row": {"name": "br0"}}]),
+        );
+        let version =
+            |db: &Database| db.rows("Bridge").unwrap().values().next().unwrap()["_version"].clone();
+        let before = version(&db);
+
+        let results = committed(
+            &mut db,
+            json!([
+                {"op": "update", "table": "Bridge", "where": [["name", "==", "br0"]],
+                 "row": {"flood_vlans": ["set", [1, 2]]}},
+                {"op": "mutate", "table": "Bridge", "where": [],
+                 "mutations": [["flood_vlans", "insert", 3], ["rate", "+=", 2]]},
+                {"op": "update", "table": "Bridge", "where": [["name", "==", "br1"]],
+                 "row": {"rate": 5}}
+            ]),
+        );
+        assert_eq!(
+            results,
+            vec![
+                json!({"count": 1}),
+                json!({"count": 1}),
+                json!({"count": 0})
+            ]
+        );
+        assert_eq!(
+            select(&mut db, "Bridge", json!(["flood_vlans", "rate"])),
+            json!([{"flood_vlans": ["set", [1, 2, 3]], "rate": 2.0}])
+        );
+        assert_ne!(version(&db), before);
+
+        // a select changes nothing, so neither does the version
+        let after = version(&db);
+        select(&mut db, "Bridge", json!(["name"]));
+        assert_eq!(version(&db), after);
+
+        let results = committed(
+            &mut db,
+            json!([{"op": "delete", "table": "Bridge", "where": [["name", "!=", "br1"]]}]),
+        );
+        assert_eq!(results, vec![json!({"count": 1})]);
+        assert!(db.rows("Bridge").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_failures_roll_back() {
+        let mut db = Database::new(schema());
+        let results = failed(
+            &mut db,
+            json!([
+                {"op": "insert", "table": "Bridge", "row": {"name": "br0"}},
+                {"op": "mutate", "table": "Bridge", "where": [],
+                 "mutations": [["flood_vlans", "insert", ["set", [1, 2, 3]]],
+                               ["flood_vlans", "insert", ["set", [4, 5]]]]},
+                {"op": "comment", "comment": "never reached"}
+            ]),
+        );
+        assert_eq!(results[0]["uuid"][0], "uuid");
+        assert_eq!(results[1]["error"], "constraint violation");
+        assert_eq!(results[2], Value::Null);
+        assert!(db.rows("Bridge").unwrap().is_empty());
+
+        committed(
+            &mut db,
+            json!([{"op": "insert", "table": "Bridge", "row": {"name": "br0"}}]),
+        );
+        for (operation, error) in [
+            (
+                json!({"op": "insert", "table": "Nope", "row": {}}),
+                "syntax error",
+            ),
+            (
+                json!({"op": "insert", "table": "Bridge", "row": {"nmae": "x"}}),
+                "syntax error",
+            ),
+            (
+                json!({"op": "insert", "table": "Bridge", "row": {"rate": "x"}}),
+                "syntax error",
+            ),
+            (
+                json!({"op": "insert", "table": "Bridge", "row": {"flood_vlans": 5000}}),
+                "constraint violation",
+            ),
+            (
+                json!({"op": "update", "table": "Bridge", "where": [], "row": {"name": "x"}}),
+                "constraint violation",
+            ),
+            (
+                json!({"op": "mutate", "table": "Bridge", "where": [],
+                    "mutations": [["rate", "/=", 0]]}),
+                "domain error",
+            ),
+            (
+                json!({"op": "select", "table": "Bridge", "where": [["rate", "<", "x"]]}),
+                "syntax error",
+            ),
+            (json!({"op": "abort"}), "aborted"),
+            (json!({"op": "assert", "lock": "l0"}), "not owner"),
+            (json!({"op": "frobnicate"}), "syntax error"),
+        ] {
+            let results = failed(&mut db, json!([operation.clone()]));
+            assert_eq!(results[0]["error"], error, "{}", operation);
+        }
+
+        // conditions that RFC 7047 allows, even if the client side rejects them
+        let results = committed(
+            &mut db,
+            json!([
+                {"op": "select", "table": "Bridge", "where": [["rate", "includes", 1]]},
+                {"op": "select", "table": "Port", "where": [["tag", ">", 1]]}
+            ]),
+        );
+        assert_eq!(results, vec![json!({"rows": []}), json!({"rows": []})]);
+
+        let results = failed(
+            &mut db,
+            json!([
+                {"op": "insert", "table": "Port", "row": {}, "uuid-name": "p"},
+                {"op": "insert", "table": "Port", "row": {}, "uuid-name": "p"}
+            ]),
+        );
+        assert_eq!(results[1]["error"], "duplicate uuid-name");
+        let results = failed(
+            &mut db,
+            json!([{"op": "insert", "table": "Bridge",
+                    "row": {"ports": ["named-uuid", "nope"]}}]),
+        );
+        assert_eq!(results[0]["error"], "syntax error");
+    }
+
+    #[test]
+    fn test_commit_checks() {
+        let mut db = Database::new(schema());
+
+        // a strong reference to a row that does not exist
+        let missing = json!(["uuid", "0e5ab1a7-5bd6-4bd4-a5c7-ac5b3a26a3cd"]);
+        let results = failed(
+            &mut db,
+            json!([{"op": "insert", "table": "Bridge", "row": {"ports": missing}}]),
+        );
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[1]["error"], "referential integrity violation");
+
+        // a port nothing refers to is collected right away
+        committed(
+            &mut db,
+            json!([{"op": "insert", "table": "Port", "row": {}}]),
+        );
+        assert!(db.rows("Port").unwrap().is_empty());
+
+        committed(
+            &mut db,
+            json!([
+                {"op": "insert", "table": "Port", "row": {"name": "p0"}, "uuid-name": "p0"},
+                {"op": "insert", "table": "Mirror", "row": {"name": "m0"}, "uuid-name": "m0"},
+                {"op": "insert", "table": "Bridge", "row": {
+                    "name": "br0",
+                    "ports": ["named-uuid", "p0"],
+                    "mirrors": ["named-uuid", "m0"]
+                }},
+                {"op": "insert", "table": "Span", "row": {"mirror": ["named-uuid", "m0"]}}
+            ]),
+        );
+        assert_eq!(db.rows("Port").unwrap().len(), 1);
+
+        // dropping the last strong reference collects the port
+        committed(
+            &mut db,
+            json!([{"op": "update", "table": "Bridge", "where": [],
+                    "row": {"ports": ["set", []]}}]),
+        );
+        assert!(db.rows("Port").unwrap().is_empty());
+
+        // weak references to deleted rows go away, unless that leaves too few
+        let results = failed(
+            &mut db,
+            json!([{"op": "delete", "table": "Mirror", "where": []}]),
+        );
+        assert_eq!(results[1]["error"], "constraint violation");
+        assert_eq!(db.rows("Mirror").unwrap().len(), 1);
+        committed(
+            &mut db,
+            json!([
+                {"op": "delete", "table": "Span", "where": []},
+                {"op": "delete", "table": "Mirror", "where": []}
+            ]),
+        );
+        assert_eq!(
+            select(&mut db, "Bridge", json!(["mirrors"])),
+            json!([{"mirrors": ["set", []]}])
+        );
+
+        // maxRows
+        let results = failed(
+            &mut db,
+            json!([
+                {"op": "insert", "table": "Mirror", "row": {"name": "m1"}},
+                {"op": "insert", "table": "Mirror", "row": {"name": "m2"}},
+                {"op": "insert", "table": "Mirror", "row": {"name": "m3"}}
+            ]),
+        );
+        assert_eq!(results[3]["error"], "constraint violation");
+
+        // indexes
+        let results = failed(
+            &mut db,
+            json!([{"op": "insert", "table": "Bridge", "row": {"name": "br0"}}]),
+        );
+        assert_eq!(results[1]["error"], "constraint violation");
+    }
+
+    #[test]
+    fn test_wait() {
+        let mut db = Database::new(schema());
+        committed(
+            &mut db,
+            json!([{"op": "insert", "table": "Port", "row": {"name": "p0", "tag": 1},
+                    "uuid-name": "p0"},
+                   {"op": "insert", "table": "Bridge", "row": {"name": "br0",
+                    "ports": ["named-uuid", "p0"]}}]),
+        );
+        let wait = |until: &str, tag: i64, timeout: u64| {
+            json!([{"op": "wait", "table": "Port", "where": [["name", "==", "p0"]],
+                    "columns": ["tag"], "until": until, "rows": [{"tag": tag}],
+                    "timeout": timeout}])
+        };
+        committed(&mut db, wait("==", 1, 0));
+        committed(&mut db, wait("!=", 2, 0));
+
+        let results = failed(&mut db, wait("==", 2, 0));
+        assert_eq!(results[0]["error"], "timed out");
+        assert_eq!(
+            db.transact(wait("==", 2, 0).as_array().unwrap(), true),
+            Transacted::Failed(results)
+        );
+        assert_eq!(
+            db.transact(wait("==", 2, 100).as_array().unwrap(), true),
+            Transacted::Blocked { timeout: Some(100) }
+        );
+    }
+}
@@ -0,0 +1,82 @@
+//! The checks of a transaction at commit, once all of its operations have
+//! run: references and garbage collection, then `maxRows` and indexes.
+// https://tools.ietf.org/html/rfc7047#section-4.1.3
+
+use crate::database::Rows;
+use ovsdb_common::datum::Datum;
+use ovsdb_common::error::{Error, Payload};
+use ovsdb_schema::{Integrity, Schema};
+use std::collections::BTreeMap;
+
+/// Checks the tables a transaction left behind, and completes them: rows
+/// of non-root tables without strong references are deleted, and then
+/// weak references to rows that no longer exist are removed. References
+/// are checked where the `changed` tables may have broken them, and only
+/// the `touched` tables, those that changed or that the completion may
+/// change, are checked against `maxRows` and indexes, as the others still
+/// hold.
+pub(crate) fn check(
+    schema: &Schema,
+    tables: &mut BTreeMap<String, Rows>,
+    changed: &[&str],
+    touched: &[&str],
+) -> Result<(), Error> {
+    Integrity::for_changes(schema, changed).enforce(tables)?;
+    check_max_rows(schema, tables, touched)?;
+    check_indexes(schema, tables, touched)
+}
+
+fn check_max_rows(
+    schema: &Schema,
+    tables: &BTreeMap<String, Rows>,
+    touched: &[&str],
+) -> Result<(), Error> {
+    for (name, table) in schema
+        .tables
+        .iter()
+        .filter(|(name, _)| touched.contains(&name.as_str()))
+    {
+        let count = tables[name].len();
+        match table.get_max_rows() {
+            Some(max) if count as u64 > max => {
+                return Err(Error::ConstraintViolation(Payload::with_details(format!(
+                    "transaction causes \"{}\" table to contain {} rows, greater than the \
+                     schema-defined limit of {} row(s)",
+                    name, count, max
+                ))))
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn check_indexes(
+    schema: &Schema,
+    tables: &BTreeMap<String, Rows>,
+    touched: &[&str],
+) -> Result<(), Error> {
+    for (name, table) in schema
+        .tables
+        .iter()
+        .filter(|(name, _)| touched.contains(&name.as_str()))
+    {
+        for index in table.index().into_iter().flatten() {
+            let mut seen = BTreeMap::new();
+            for (uuid, row) in &tables[name] {
+                let key: Vec<Option<&Datum>> = index.iter().map(|column| row.get(column)).collect();
+                if let Some(other) = seen.insert(key, uuid) {
+                    return Err(Error::ConstraintViolation(Payload::with_details(format!(
+                        "transaction causes rows {} and {} of \"{}\" table to have identical \
+                         values for the index on columns ({})",
+                        other,
+                        uuid,
+                        name,
+                        index.join(", ")
+                    ))));
+                }
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,7 @@
+pub mod database;
+mod integrity;
+pub mod server;
+mod transaction;
+
+pub use database::{Database, Rows, Transacted};
+pub use server::Server;
@@ -0,0 +1,51 @@
+use clap::Parser;
+use ovsdb_client::Listener;
+use ovsdb_schema::Schema;
+use ovsdb_server::Server;
+use std::path::PathBuf;
+use std::process;
+
+/// Serves empty in-memory databases of the given schemas.
+#[derive(Parser, Debug)]
+#[command(name = "ovsdb-server", version, about, long_about = None)]
+struct Options {
+    /// The passive remote to listen on, such as ptcp:6640 or
+    /// punix:/run/db.sock.
+    #[arg(long, default_value = "ptcp:6640")]
+    remote: String,
+    /// The schema files of the databases to serve.
+    #[arg(required = true)]
+    schema_files: Vec<PathBuf>,
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    let options = Options::parse();
+    let server = Server::new();
+    for path in &options.schema_files {
+        let schema = Schema::from_path(path).unwrap_or_else(|e| {
+            eprintln!("error: {}", e);
+            process::exit(1);
+        });
+        let name = schema.name.clone();
+        if !server.add_database(schema) {
+            eprintln!(
+                "error: {}: database {} is already served",
+                path.display(),
+                name
+            );
+            process::exit(1);
+        }
+    }
+    let listener = Listener::bind(&options.remote, None)
+        .await
+        .unwrap_or_else(|e| {
+            eprintln!("error: {}: {}", options.remote, e);
+            process::exit(1);
+        });
+    server
+        .serve(listener, |e| {
+            eprintln!("error: accepting a connection: {}", e)
+        })
+        .await;
+}
@@ -0,0 +1,391 @@
+//! Serving databases over JSON-RPC, with the same framing as the client.
+// https://tools.ietf.org/html/rfc7047#section-4.1
+
+use crate::database::{Database, Transacted};
+use futures::{SinkExt, StreamExt};
+use ovsdb_client::codec::JsonCodec;
+use ovsdb_client::Listener;
+use ovsdb_common::error::{Error, Payload};
+use ovsdb_common::jsonrpc::{Message, Request, Response};
+use ovsdb_schema::Schema;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::sync::{mpsc, watch};
+use tokio::task::JoinSet;
+use tokio::time::Instant;
+use tokio_util::codec::Framed;
+
+/// How long to wait before accepting again after an error, doubled for
+/// each one in a row up to [`MAX_ACCEPT_BACKOFF`].
+const ACCEPT_BACKOFF: Duration = Duration::from_millis(100);
+const MAX_ACCEPT_BACKOFF: Duration = Duration::from_secs(1);
+
+/// A database server that keeps its databases in memory. Clones share the
+/// databases.
+///
+/// ```no_run
+/// # async fn example(schema: ovsdb_schema::Schema) -> ovsdb_client::Result<()> {
+/// use ovsdb_client::Listener;
+/// use ovsdb_server::Server;
+///
+/// let server = Server::new();
+/// server.add_database(schema);
+/// let listener = Listener::bind("ptcp:6641", None).await?;
+/// server.serve(listener, |e| eprintln!("{}", e)).await;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Server {
+    shared: Arc<Shared>,
+}
+
+#[derive(Debug)]
+struct Shared {
+    databases: Mutex<BTreeMap<String, Database>>,
+    /// Counts the commits, for blocked transactions to try again.
+    commits: watch::Sender<u64>,
+}
+
+impl Default for Server {
+    fn default() -> Self {
+        Server::new()
+    }
+}
+
+impl Server {
+    /// A server without databases.
+    pub fn new() -> Self {
+        Server {
+            shared: Arc::new(Shared {
+                databases: Mutex::new(BTreeMap::new()),
+                commits: watch::Sender::new(0),
+            }),
+        }
+    }
+
+    /// Serves an empty database of the schema, under the schema's name.
+    /// Returns `false`, and changes nothing, if there already is a
+    /// database of that name.
+    pub fn add_database(&self, schema: Schema) -> bool {
+        let mut databases = self.shared.databases.lock().unwrap();
+        if databases.contains_key(&schema.name) {
+            return false;
+        }
+        databases.insert(schema.name.clone(), Database::new(schema));
+        true
+    }
+
+    pub fn database_names(&self) -> Vec<String> {
+        self.shared
+            .databases
+            .lock()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect()
+    }
+
+    /// Runs `f` on the database named `name`, if there is one.
+    pub fn with_database<T>(&self, name: &str, f: impl FnOnce(&Database) -> T) -> Option<T> {
+        self.shared.databases.lock().unwrap().get(name).map(f)
+    }
+
+    /// Accepts connections on the listener and serves each of them in a
+    /// task of its own, and never returns. For `pssl:` remotes, the TLS
+    /// handshake runs in that task, and its failure drops just that
+    /// connection. Other accept errors, such as running out of file
+    /// descriptors, are handed to `on_error`, and accepting resumes after a
+    /// pause.
+    pub async fn serve(&self, listener: Listener, mut on_error: impl FnMut(&ovsdb_client::Error)) {
+        let mut backoff = ACCEPT_BACKOFF;
+        loop {
+            let incoming = match listener.accept_incoming().await {
+                Ok(incoming) => incoming,
+                Err(ovsdb_client::Error::Io(e)) if is_peer_error(&e) => continue,
+                Err(e) => {
+                    on_error(&e);
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(MAX_ACCEPT_BACKOFF);
+                    continue;
+                }
+            };
+            backoff = ACCEPT_BACKOFF;
+            let server = self.clone();
+            tokio::spawn(async move {
+                if let Ok(stream) = incoming.establish().await {
+                    server.serve_connection(stream).await;
+                }
+            });
+        }
+    }
+
+    /// Answers the requests that arrive on the stream until the peer
+    /// closes it. Replies go out in the order of the requests, except for
+    /// transactions blocked on a "wait".
+    pub async fn serve_connection<S>(&self, stream: S)
+    where
+        S: AsyncRead + AsyncWrite + Send + 'static,
+    {
+        let (mut sink, mut stream) = Framed::new(stream, JsonCodec::default()).split();
+        let (replies, mut outgoing) = mpsc::unbounded_channel::<Message>();
+        let writer = tokio::spawn(async move {
+            while let Some(message) = outgoing.recv().await {
+                if sink.send(message).await.is_err() {
+                    break;
+                }
+            }
+        });
+        // dropped with the connection, which cancels them
+        let mut blocked = JoinSet::new();
+
+        while let Some(Ok(message)) = stream.next().await {
+            while blocked.try_join_next().is_some() {}
+            let request = match message {
+                Message::Request(request) => request,
+                // nothing to answer
+                Message::Response(_) | Message::Notification(_) => continue,
+            };
+            if request.method != "transact" {
+                let _ = replies.send(self.answer(&request).into());
+                continue;
+            }
+            // subscribed before the first try so that no commit in between
+            // goes unnoticed
+            let commits = self.shared.commits.subscribe();
+            match self.transact(&request.params, true) {
+                Ok(Transacted::Blocked { timeout }) => {
+                    let server = self.clone();
+                    let replies = replies.clone();
+                    blocked.spawn(async move {
+                        let result = server.retry(&request.params, commits, timeout).await;
+                        let _ = replies.send(response(request.id, result).into());
+                    });
+                }
+                result => {
+                    let result = result.map(|transacted| json!(transacted.results()));
+                    let _ = replies.send(response(request.id, result).into());
+                }
+            }
+        }
+        drop(blocked);
+        drop(replies);
+        let _ = writer.await;
+    }
+
+    fn answer(&self, request: &Request) -> Response {
+        let result = match request.method.as_str() {
+            "echo" => Ok(request.params.clone()),
+            "list_dbs" => Ok(json!(self.database_names())),
+            "get_schema" => database_name(&request.params).and_then(|name| {
+                self.with_database(name, |database| json!(database.schema()))
+                    .ok_or_else(|| unknown_database(name))
+            }),
+            method => Err(Error::new("unknown method", Some(method.to_string()))),
+        };
+        response(request.id.clone(), result)
+    }
+
+    /// Runs the "transact" request with the given params once.
+    fn transact(&self, params: &Value, may_block: bool) -> Result<Transacted, Error> {
+        let name = database_name(params)?;
+        let operations = &params.as_array().expect("checked by database_name")[1..];
+        let transacted = {
+            let mut databases = self.shared.databases.lock().unwrap();
+            let database = databases
+                .get_mut(name)
+                .ok_or_else(|| unknown_database(name))?;
+            database.transact(operations, may_block)
+        };
+        if let Transacted::Committed(_) = transacted {
+            self.shared.commits.send_modify(|count| *count += 1);
+        }
+        Ok(transacted)
+    }
+
+    /// Runs a blocked transaction again after each commit, until it no
+    /// longer blocks. Once `timeout` milliseconds have passed, it runs one
+    /// last time without blocking, which fails with "timed out" if it
+    /// still does not hold.
+    async fn retry(
+        &self,
+        params: &Value,
+        mut commits: watch::Receiver<u64>,
+        timeout: Option<u64>,
+    ) -> Result<Value, Error> {
+        let deadline = timeout.map(|ms| Instant::now() + Duration::from_millis(ms));
+        let mut may_block = true;
+        loop {
+            tokio::select! {
+                changed = commits.changed() => {
+                    if changed.is_err() {
+                        // the server is gone
+                        may_block = false;
+                    }
+                }
+                _ = tokio::time::sleep_until(deadline.unwrap_or_else(Instant::now)),
+                    if deadline.is_some() => may_block = false,
+            }
+            commits.borrow_and_update();
+            match self.transact(params, may_block)? {
+                Transacted::Blocked { .. } => continue,
+                transacted => return Ok(json!(transacted.results())),
+            }
+        }
+    }
+}
+
+fn response(id: Value, result: Result<Value, Error>) -> Response {
+    match result {
+        Ok(result) => Response::ok(id, result),
+        Err(error) => Response::err(id, error),
+    }
+}
+
+/// The database name that "get_schema" and "transact" take first.
+fn database_name(params: &Value) -> Result<&str, Error> {
+    params
+        .as_array()
+        .and_then(|params| params.first())
+        .and_then(Value::as_str)
+        .ok_or_else(|| {
+            Error::new(
+                "syntax error",
+                Some("expected a database name as the first param".to_string()),
+            )
+        })
+}
+
+fn unknown_database(name: &str) -> Error {
+    Error::UnknownDatabase(Payload::with_details(format!("no database named {}", name)))
+}
+
+// errors of a single connection, after which accepting can go on
+fn is_peer_error(error: &std::io::Error) -> bool {
+    use std::io::ErrorKind;
+    matches!(
+        error.kind(),
+        ErrorKind::ConnectionAborted
+            | ErrorKind::ConnectionReset
+            | ErrorKind::InvalidData
+            | ErrorKind::UnexpectedEof
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::server::*;
+    use ovsdb_client::{Client, Transaction};
+    use ovsdb_common::condition::Condition;
+    use ovsdb_common::mutation::Mutation;
+
+    fn schema() -> Schema {
+        serde_json::from_value(json!({
+            "name": "Test",
+            "tables": {
+                "Global": {
+                    "columns": {"cfg": {"type": "integer"}},
+                    "maxRows": 1
+                }
+            }
+        }))
+        .unwrap()
+    }
+
+    fn connect(server: &Server) -> Client {
+        let (client_side, server_side) = tokio::io::duplex(4096);
+        let server = server.clone();
+        tokio::spawn(async move { server.serve_connection(server_side).await });
+        Client::from_stream(client_side).0
+    }
+
+    #[tokio::test]
+    async fn test_requests() {
+        let server = Server::new();
+        assert!(server.add_database(schema()));
+        assert!(!server.add_database(schema()));
+        let client = connect(&server);
+
+        assert_eq!(client.list_dbs().await.unwrap(), vec!["Test".to_string()]);
+        assert_eq!(client.get_schema("Test").await.unwrap(), schema());
+        assert!(matches!(
+            client.get_schema("Nope").await,
+            Err(ovsdb_client::Error::Rpc(Error::UnknownDatabase(_)))
+        ));
+        assert_eq!(client.echo(json!(["ping"])).await.unwrap(), json!(["ping"]));
+
+        let results = client
+            .transact(
+                "Test",
+                vec![json!({"op": "insert", "table": "Global", "row": {"cfg": 1}})],
+            )
+            .await
+            .unwrap();
+        assert_eq!(results[0]["uuid"][0], "uuid");
+        assert_eq!(
+            server.with_database("Test", |db| db.rows("Global").unwrap().len()),
+            Some(1)
+        );
+
+        let results = client
+            .transact(
+                "Test",
+                vec![json!({"op": "insert", "table": "Global", "row": {}})],
+            )
+            .await
+            .unwrap();
+        assert_eq!(results[1]["error"], "constraint violation");
+    }
+
+    #[tokio::test]
+    async fn test_blocked_wait() {
+        let server = Server::new();
+        server.add_database(schema());
+        let client = connect(&server);
+        let mut txn = Transaction::new("Test");
+        txn.insert("Global", json!({"cfg": 0}).as_object().unwrap().clone());
+        txn.execute(&client).await.unwrap();
+
+        // waits for another client to raise cfg
+        let wait = tokio::spawn({
+            let client = client.clone();
+            async move {
+                client
+                    .transact(
+                        "Test",
+                        vec![json!({"op": "wait", "table": "Global", "where": [],
+                                    "columns": ["cfg"], "until": "==",
+                                    "rows": [{"cfg": 1}], "timeout": 10000})],
+                    )
+                    .await
+            }
+        });
+        // requests behind the blocked one are still answered
+        assert_eq!(client.echo(json!([])).await.unwrap(), json!([]));
+        assert!(!wait.is_finished());
+
+        let other = connect(&server);
+        let mut txn = Transaction::new("Test");
+        txn.mutate(
+            "Global",
+            vec![Condition::eq("cfg", 0)],
+            vec![Mutation::add("cfg", 1)],
+        );
+        txn.execute(&other).await.unwrap();
+        assert_eq!(wait.await.unwrap().unwrap(), vec![json!({})]);
+
+        let results = client
+            .transact(
+                "Test",
+                vec![json!({"op": "wait", "table": "Global", "where": [],
+                            "columns": ["cfg"], "until": "!=",
+                            "rows": [{"cfg": 1}], "timeout": 10})],
+            )
+            .await
+            .unwrap();
+        assert_eq!(results[0]["error"], "timed out");
+    }
+}
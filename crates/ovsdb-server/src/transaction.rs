@@ -0,0 +1,615 @@
+//! The operations of a transaction, run against a working copy of the
+//! tables that the database keeps only if all of them succeed.
+// https://tools.ietf.org/html/rfc7047#section-5.2
+
+use crate::database::{new_uuid, Rows};
+use ovsdb_common::condition::Condition;
+use ovsdb_common::datum::{Atom, Datum, Uuid};
+use ovsdb_common::error::{Error, Payload};
+use ovsdb_common::mutation::Mutation;
+use ovsdb_common::row::Row;
+use ovsdb_schema::schema::Table;
+use ovsdb_schema::{ColumnType, ConditionError, InvalidMutation, Schema, Violation};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+type Object = Map<String, Value>;
+
+/// Why an operation did not complete.
+pub(crate) enum Failure {
+    Error(Error),
+    /// A "wait" that does not hold yet, see `Transacted::Blocked`.
+    Blocked {
+        timeout: Option<u64>,
+    },
+}
+
+impl From<Error> for Failure {
+    fn from(error: Error) -> Self {
+        Failure::Error(error)
+    }
+}
+
+pub(crate) struct Txn<'a> {
+    schema: &'a Schema,
+    /// The tables as the database has them.
+    committed: &'a BTreeMap<String, Rows>,
+    /// The copies of the tables that the transaction changed, made on the
+    /// first change of each.
+    changed: BTreeMap<String, Rows>,
+    /// The UUIDs of the rows inserted with a "uuid-name", given out up
+    /// front so that any operation of the transaction can refer to them.
+    named: HashMap<String, Uuid>,
+    /// The inserts that reuse the "uuid-name" of an earlier one.
+    duplicates: HashSet<usize>,
+    may_block: bool,
+}
+
+impl<'a> Txn<'a> {
+    pub(crate) fn new(
+        schema: &'a Schema,
+        committed: &'a BTreeMap<String, Rows>,
+        operations: &[Value],
+        may_block: bool,
+    ) -> Self {
+        let mut named = HashMap::new();
+        let mut duplicates = HashSet::new();
+        for (index, operation) in operations.iter().enumerate() {
+            if operation["op"] != "insert" {
+                continue;
+            }
+            if let Some(Value::String(name)) = operation.get("uuid-name") {
+                if named.contains_key(name) {
+                    duplicates.insert(index);
+                } else {
+                    named.insert(name.clone(), new_uuid());
+                }
+            }
+        }
+        Txn {
+            schema,
+            committed,
+            changed: BTreeMap::new(),
+            named,
+            duplicates,
+            may_block,
+        }
+    }
+
+    /// The tables that the transaction changed, with all of their rows.
+    pub(crate) fn into_changed(self) -> BTreeMap<String, Rows> {
+        self.changed
+    }
+
+    /// Runs the operation at `index` of the transaction and returns its
+    /// result.
+    pub(crate) fn execute(&mut self, index: usize, operation: &Value) -> Result<Value, Failure> {
+        let operation = operation
+            .as_object()
+            .ok_or_else(|| syntax_error("operation is not an object"))?;
+        let op: String = member(operation, "op")?;
+        match op.as_str() {
+            "insert" => Ok(self.insert(index, operation)?),
+            "select" => Ok(self.select(operation)?),
+            "update" => Ok(self.update(operation)?),
+            "mutate" => Ok(self.mutate(operation)?),
+            "delete" => Ok(self.delete(operation)?),
+            "wait" => self.wait(operation),
+            "commit" => {
+                member::<bool>(operation, "durable")?;
+                Ok(json!({}))
+            }
+            "abort" => Err(Error::Aborted(Payload::default()).into()),
+            "comment" => {
+                member::<String>(operation, "comment")?;
+                Ok(json!({}))
+            }
+            "assert" => {
+                let lock: String = member(operation, "lock")?;
+                Err(Error::NotOwner(Payload::with_details(format!(
+                    "lock '{}' is not held, as this server has no locks",
+                    lock
+                )))
+                .into())
+            }
+            _ => Err(syntax_error(format!("unknown operation '{}'", op)).into()),
+        }
+    }
+
+    fn insert(&mut self, index: usize, operation: &Object) -> Result<Value, Error> {
+        let (name, table) = self.table(operation)?;
+        let uuid = match optional::<String>(operation, "uuid-name")? {
+            Some(uuid_name) if self.duplicates.contains(&index) => {
+                return Err(Error::DuplicateUuidName(Payload::with_details(format!(
+                    "uuid-name '{}' is already used by an earlier insert",
+                    uuid_name
+                ))))
+            }
+            Some(uuid_name) => self.named[&uuid_name],
+            None => new_uuid(),
+        };
+        let mut row: Row = table
+            .columns
+            .iter()
+            .map(|(column, schema)| (column.clone(), schema.type_.default_datum()))
+            .collect();
+        row.extend(self.row(table, &member(operation, "row")?, false)?);
+        row.insert("_version".to_string(), Datum::from(new_uuid()));
+        self.rows_mut(&name).insert(uuid, row);
+        Ok(json!({ "uuid": Datum::from(uuid) }))
+    }
+
+    fn select(&self, operation: &Object) -> Result<Value, Error> {
+        let (name, table) = self.table(operation)?;
+        let conditions = self.conditions(table, operation)?;
+        let columns: Option<Vec<String>> = optional(operation, "columns")?;
+        if let Some(columns) = &columns {
+            check_columns(table, columns)?;
+        }
+        let rows: Vec<Row> = self
+            .matching(&name, &conditions)
+            .iter()
+            .map(|uuid| project(uuid, &self.rows(&name)[uuid], columns.as_deref()))
+            .collect();
+        Ok(json!({ "rows": rows }))
+    }
+
+    fn update(&mut self, operation: &Object) -> Result<Value, Error> {
+        let (name, table) = self.table(operation)?;
+        let conditions = self.conditions(table, operation)?;
+        let row = self.row(table, &member(operation, "row")?, true)?;
+        let uuids = self.matching(&name, &conditions);
+        if uuids.is_empty() {
+            return Ok(json!({ "count": 0 }));
+        }
+        let rows = self.rows_mut(&name);
+        for uuid in &uuids {
+            rows.get_mut(uuid)
+                .expect("a matched row")
+                .extend(row.clone());
+        }
+        Ok(json!({ "count": uuids.len() }))
+    }
+
+    fn mutate(&mut self, operation: &Object) -> Result<Value, Error> {
+        let (name, table) = self.table(operation)?;
+        let conditions = self.conditions(table, operation)?;
+        let mutations = member::<Vec<Mutation>>(operation, "mutations")?
+            .into_iter()
+            .map(|mut mutation| {
+                mutation.value = self.resolve(mutation.value)?;
+                if let Some(type_) = table.column_type(&mutation.column) {
+                    mutation.value = type_.mutation_type(mutation.mutator).coerce(mutation.value);
+                }
+                table.check_mutation(&mutation).map_err(mutation_error)?;
+                Ok(mutation)
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        let uuids = self.matching(&name, &conditions);
+        if uuids.is_empty() {
+            return Ok(json!({ "count": 0 }));
+        }
+        let rows = self.rows_mut(&name);
+        for uuid in &uuids {
+            let row = rows.get_mut(uuid).expect("a matched row");
+            for mutation in &mutations {
+                let datum = table
+                    .apply_mutation(mutation, &row[&mutation.column])
+                    .map_err(mutation_error)?;
+                row.insert(mutation.column.clone(), datum);
+            }
+        }
+        Ok(json!({ "count": uuids.len() }))
+    }
+
+    fn delete(&mut self, operation: &Object) -> Result<Value, Error> {
+        let (name, table) = self.table(operation)?;
+        let conditions = self.conditions(table, operation)?;
+        let uuids = self.matching(&name, &conditions);
+        if uuids.is_empty() {
+            return Ok(json!({ "count": 0 }));
+        }
+        let rows = self.rows_mut(&name);
+        for uuid in &uuids {
+            rows.remove(uuid);
+        }
+        Ok(json!({ "count": uuids.len() }))
+    }
+
+    fn wait(&self, operation: &Object) -> Result<Value, Failure> {
+        let (name, table) = self.table(operation)?;
+        let conditions = self.conditions(table, operation)?;
+        let columns: Vec<String> = member(operation, "columns")?;
+        check_columns(table, &columns)?;
+        let until: String = member(operation, "until")?;
+        let equal = match until.as_str() {
+            "==" => true,
+            "!=" => false,
+            _ => return Err(syntax_error(format!("unknown \"until\" '{}'", until)).into()),
+        };
+        let mut expected = member::<Vec<Value>>(operation, "rows")?
+            .iter()
+            .map(|row| self.wait_row(table, &columns, row))
+            .collect::<Result<Vec<_>, _>>()?;
+        let timeout: Option<u64> = optional(operation, "timeout")?;
+
+        // the rows are compared as a multiset, in no particular order
+        let mut actual: Vec<Row> = self
+            .matching(&name, &conditions)
+            .iter()
+            .map(|uuid| project(uuid, &self.rows(&name)[uuid], Some(&columns)))
+            .collect();
+        actual.sort();
+        expected.sort();
+        if (actual == expected) == equal {
+            return Ok(json!({}));
+        }
+        if self.may_block && timeout != Some(0) {
+            return Err(Failure::Blocked { timeout });
+        }
+        Err(Error::TimedOut(Payload::with_details(format!(
+            "\"wait\" for rows of table {} {} the given ones timed out",
+            name, until
+        )))
+        .into())
+    }
+
+    fn table(&self, operation: &Object) -> Result<(String, &'a Table), Error> {
+        let name: String = member(operation, "table")?;
+        let table = self
+            .schema
+            .get_table(&name)
+            .ok_or_else(|| syntax_error(format!("unknown table '{}'", name)))?;
+        Ok((name, table))
+    }
+
+    fn rows(&self, table: &str) -> &Rows {
+        self.changed
+            .get(table)
+            .unwrap_or_else(|| &self.committed[table])
+    }
+
+    /// Copies the table on its first change, so only ask for it with rows
+    /// to change.
+    fn rows_mut(&mut self, table: &str) -> &mut Rows {
+        let committed = self.committed;
+        self.changed
+            .entry(table.to_string())
+            .or_insert_with(|| committed[table].clone())
+    }
+
+    /// The rows of `table` that satisfy all of the conditions.
+    fn matching(&self, table: &str, conditions: &[Condition]) -> Vec<Uuid> {
+        self.rows(table)
+            .iter()
+            .filter(|(uuid, row)| conditions.iter().all(|c| c.evaluate(uuid, row)))
+            .map(|(uuid, _)| *uuid)
+            .collect()
+    }
+
+    /// Replaces the "named-uuid"s of the datum by the UUIDs of their rows.
+    fn resolve(&self, datum: Datum) -> Result<Datum, Error> {
+        let resolve = |atom: Atom| match atom {
+            Atom::NamedUuid(name) => self
+                .named
+                .get(&name)
+                .map(|uuid| Atom::Uuid(*uuid))
+                .ok_or_else(|| syntax_error(format!("unknown uuid-name '{}'", name))),
+            atom => Ok(atom),
+        };
+        Ok(match datum {
+            Datum::Scalar(atom) => Datum::Scalar(resolve(atom)?),
+            Datum::Set(set) => Datum::Set(set.into_iter().map(resolve).collect::<Result<_, _>>()?),
+            Datum::Map(map) => Datum::Map(
+                map.into_iter()
+                    .map(|(key, value)| Ok((resolve(key)?, resolve(value)?)))
+                    .collect::<Result<_, Error>>()?,
+            ),
+        })
+    }
+
+    /// The value given for `column`, resolved, coerced to `type_` and
+    /// checked against it.
+    fn datum(&self, column: &str, type_: &ColumnType, value: &Value) -> Result<Datum, Error> {
+        let datum: Datum = serde_json::from_value(value.clone())
+            .map_err(|e| syntax_error(format!("column '{}': {}", column, e)))?;
+        let datum = type_.coerce(self.resolve(datum)?);
+        type_
+            .validate(&datum)
+            .map_err(|violations| invalid_value(column, &violations))?;
+        Ok(datum)
+    }
+
+    /// The "row" of an insert or update. Updates may not change immutable
+    /// columns.
+    fn row(&self, table: &Table, value: &Value, update: bool) -> Result<Row, Error> {
+        let object = value
+            .as_object()
+            .ok_or_else(|| syntax_error("\"row\" is not an object"))?;
+        object
+            .iter()
+            .map(|(column, value)| {
+                let schema = table
+                    .columns
+                    .get(column)
+                    .ok_or_else(|| syntax_error(format!("unknown column '{}'", column)))?;
+                if update && !schema.mutable {
+                    return Err(Error::ConstraintViolation(Payload::with_details(format!(
+                        "cannot update immutable column '{}'",
+                        column
+                    ))));
+                }
+                Ok((column.clone(), self.datum(column, &schema.type_, value)?))
+            })
+            .collect()
+    }
+
+    /// One of the "rows" of a wait, which may only have the waited on
+    /// `columns`.
+    fn wait_row(&self, table: &Table, columns: &[String], value: &Value) -> Result<Row, Error> {
+        let object = value
+            .as_object()
+            .ok_or_else(|| syntax_error("a row of \"rows\" is not an object"))?;
+        object
+            .iter()
+            .map(|(column, value)| {
+                let type_ = table
+                    .column_type(column)
+                    .filter(|_| columns.contains(column))
+                    .ok_or_else(|| syntax_error(format!("column '{}' is not waited on", column)))?;
+                Ok((column.clone(), self.datum(column, &type_, value)?))
+            })
+            .collect()
+    }
+
+    /// The "where" of the operation, resolved, coerced and checked.
+    fn conditions(&self, table: &Table, operation: &Object) -> Result<Vec<Condition>, Error> {
+        member::<Vec<Condition>>(operation, "where")?
+            .into_iter()
+            .map(|condition| {
+                let condition = match condition {
+                    Condition::Clause {
+                        column,
+                        function,
+                        value,
+                    } => {
+                        let mut value = self.resolve(value)?;
+                        if let Some(type_) = table.column_type(&column) {
+                            value = type_.condition_type(function).coerce(value);
+                        }
+                        Condition::Clause {
+                            column,
+                            function,
+                            value,
+                        }
+                    }
+                    constant => constant,
+                };
+                table
+                    .check_server_condition(&condition)
+                    .map_err(|error| match error {
+                        ConditionError::InvalidValue { column, violations } => {
+                            invalid_value(&column, &violations)
+                        }
+                        error => syntax_error(error.to_string()),
+                    })?;
+                Ok(condition)
+            })
+            .collect()
+    }
+}
+
+fn syntax_error(details: impl Into<String>) -> Error {
+    Error::new("syntax error", Some(details.into()))
+}
+
+/// Member `name` of the operation, which must be there.
+fn member<T: DeserializeOwned>(operation: &Object, name: &str) -> Result<T, Error> {
+    let value = operation
+        .get(name)
+        .ok_or_else(|| syntax_error(format!("missing \"{}\"", name)))?;
+    serde_json::from_value(value.clone()).map_err(|e| syntax_error(format!("\"{}\": {}", name, e)))
+}
+
+fn optional<T: DeserializeOwned>(operation: &Object, name: &str) -> Result<Option<T>, Error> {
+    match operation.get(name) {
+        None => Ok(None),
+        Some(_) => member(operation, name).map(Some),
+    }
+}
+
+fn check_columns(table: &Table, columns: &[String]) -> Result<(), Error> {
+    match columns
+        .iter()
+        .find(|column| table.column_type(column).is_none())
+    {
+        Some(column) => Err(syntax_error(format!("unknown column '{}'", column))),
+        None => Ok(()),
+    }
+}
+
+/// The row with its `_uuid`, or only its `columns` if given.
+fn project(uuid: &Uuid, row: &Row, columns: Option<&[String]>) -> Row {
+    let Some(columns) = columns else {
+        let mut row = row.clone();
+        row.insert("_uuid".to_string(), Datum::from(*uuid));
+        return row;
+    };
+    columns
+        .iter()
+        .map(|column| {
+            let datum = match column.as_str() {
+                "_uuid" => Datum::from(*uuid),
+                _ => row[column].clone(),
+            };
+            (column.clone(), datum)
+        })
+        .collect()
+}
+
+// As in ovsdb-server, a value that is not of the column type is a syntax
+// error, while one of the right type that breaks its constraints, such as
+// `maxInteger`, is a constraint violation.
+fn invalid_value(column: &str, violations: &[Violation]) -> Error {
+    let constraint = violations.iter().all(|violation| {
+        matches!(
+            violation,
+            Violation::OutOfRange { .. }
+                | Violation::NotInEnum { .. }
+                | Violation::StringTooShort { .. }
+                | Violation::StringTooLong { .. }
+        )
+    });
+    let details = format!(
+        "invalid value for column '{}': {}",
+        column,
+        violations
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    match constraint {
+        true => Error::ConstraintViolation(Payload::with_details(details)),
+        false => syntax_error(details),
+    }
+}
+
+fn mutation_error(error: InvalidMutation) -> Error {
+    match error {
+        InvalidMutation::Immutable(_) => {
+            Error::ConstraintViolation(Payload::with_details(error.to_string()))
+        }
+        InvalidMutation::InvalidValue { column, violations } => invalid_value(&column, &violations),
+        InvalidMutation::Failed { .. } | InvalidMutation::ConstraintViolation { .. } => {
+            error.into()
+        }
+        InvalidMutation::UnknownColumn(_) | InvalidMutation::NotApplicable { .. } => {
+            syntax_error(error.to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::transaction::*;
+
+    fn schema() -> Schema {
+        serde_json::from_value(json!({
+            "name": "Test",
+            "tables": {
+                "Bridge": {
+                    "columns": {
+                        "name": {"type": "string"},
+                        "ports": {"type": {"key": {"type": "uuid", "refTable": "Port"},
+                                           "min": 0, "max": "unlimited"}}
+                    },
+                    "isRoot": true
+                },
+                "Port": {"columns": {"name": {"type": "string"}}}
+            }
+        }))
+        .unwrap()
+    }
+
+    fn tables(schema: &Schema) -> BTreeMap<String, Rows> {
+        schema
+            .tables
+            .keys()
+            .map(|name| (name.clone(), Rows::new()))
+            .collect()
+    }
+
+    // the results of the operations, up to the first failure
+    fn run(txn: &mut Txn, operations: &Value) -> Vec<Result<Value, String>> {
+        let mut results = Vec::new();
+        for (index, operation) in operations.as_array().unwrap().iter().enumerate() {
+            match txn.execute(index, operation) {
+                Ok(result) => results.push(Ok(result)),
+                Err(Failure::Error(error)) => {
+                    results.push(Err(error.error().to_string()));
+                    break;
+                }
+                Err(Failure::Blocked { .. }) => {
+                    results.push(Err("blocked".to_string()));
+                    break;
+                }
+            }
+        }
+        results
+    }
+
+    #[test]
+    fn test_named_uuids() {
+        let schema = schema();
+        let committed = tables(&schema);
+
+        // an insert may refer to a row that a later one inserts
+        let operations = json!([
+            {"op": "insert", "table": "Bridge",
+             "row": {"name": "br0", "ports": ["named-uuid", "p0"]}},
+            {"op": "insert", "table": "Port", "row": {"name": "p0"}, "uuid-name": "p0"}
+        ]);
+        let mut txn = Txn::new(&schema, &committed, operations.as_array().unwrap(), false);
+        assert!(run(&mut txn, &operations).iter().all(Result::is_ok));
+        let changed = txn.into_changed();
+        let port = changed["Port"].keys().next().unwrap();
+        let bridge = changed["Bridge"].values().next().unwrap();
+        assert_eq!(bridge["ports"], Datum::set([*port]));
+
+        let operations = json!([
+            {"op": "insert", "table": "Bridge", "row": {"ports": ["named-uuid", "p9"]}}
+        ]);
+        let mut txn = Txn::new(&schema, &committed, operations.as_array().unwrap(), false);
+        assert_eq!(
+            run(&mut txn, &operations),
+            vec![Err("syntax error".to_string())]
+        );
+
+        let operations = json!([
+            {"op": "insert", "table": "Port", "row": {}, "uuid-name": "p0"},
+            {"op": "insert", "table": "Port", "row": {}, "uuid-name": "p0"}
+        ]);
+        let mut txn = Txn::new(&schema, &committed, operations.as_array().unwrap(), false);
+        assert_eq!(
+            run(&mut txn, &operations)[1],
+            Err("duplicate uuid-name".to_string())
+        );
+    }
+
+    #[test]
+    fn test_select_and_wait_on_uuid_and_version() {
+        let schema = schema();
+        let mut committed = tables(&schema);
+        let uuid = Uuid::from_u128(1);
+        let version = Uuid::from_u128(2);
+        committed.get_mut("Port").unwrap().insert(
+            uuid,
+            Row::from([
+                ("name".to_string(), Datum::from("p0")),
+                ("_version".to_string(), Datum::from(version)),
+            ]),
+        );
+
+        let operations = json!([
+            {"op": "select", "table": "Port", "where": [["_uuid", "==", ["uuid", uuid]]],
+             "columns": ["_uuid", "_version"]},
+            {"op": "wait", "table": "Port", "where": [], "columns": ["_uuid", "_version"],
+             "until": "==", "rows": [{"_uuid": ["uuid", uuid], "_version": ["uuid", version]}]},
+            {"op": "wait", "table": "Port", "where": [], "columns": ["_uuid"],
+             "until": "!=", "rows": [{"_uuid": ["uuid", uuid]}], "timeout": 0}
+        ]);
+        let mut txn = Txn::new(&schema, &committed, operations.as_array().unwrap(), true);
+        assert_eq!(
+            run(&mut txn, &operations),
+            vec![
+                Ok(json!({"rows": [{"_uuid": ["uuid", uuid], "_version": ["uuid", version]}]})),
+                Ok(json!({})),
+                Err("timed out".to_string())
+            ]
+        );
+        // reading copies nothing
+        assert!(txn.into_changed().is_empty());
+    }
+}